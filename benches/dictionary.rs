@@ -0,0 +1,45 @@
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+use std::hint::black_box;
+use voice_input::domain::dict::{DictionaryMatcher, EntryStatus, WordEntry};
+
+/// 辞書の件数（目安: 1 ミリ秒未満で 1 万文字へ適用できること）
+const ENTRY_COUNT: usize = 1_000;
+/// 転写テキストの文字数
+const TEXT_CHARS: usize = 10_000;
+
+fn entries(count: usize) -> Vec<WordEntry> {
+    (0..count)
+        .map(|i| WordEntry {
+            surface: format!("単語{:04}", i),
+            replacement: format!("word{}", i),
+            hit: 0,
+            status: EntryStatus::Active,
+        })
+        .collect()
+}
+
+/// 辞書の語が散らばった転写テキストを作る
+fn transcript(chars: usize) -> String {
+    (0..)
+        .map(|i| format!("これは単語{:04}です。", (i * 37) % ENTRY_COUNT))
+        .flat_map(|chunk| chunk.chars().collect::<Vec<_>>())
+        .take(chars)
+        .collect()
+}
+
+fn benchmark_dictionary_apply(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dictionary_apply");
+    let mut entries = entries(ENTRY_COUNT);
+    let matcher = DictionaryMatcher::new(&entries);
+    let text = transcript(TEXT_CHARS);
+
+    group.throughput(Throughput::Elements(TEXT_CHARS as u64));
+    group.bench_function("1000_entries_10000_chars", |b| {
+        b.iter(|| black_box(matcher.apply(black_box(&text), &mut entries)));
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_dictionary_apply);
+criterion_main!(benches);
@@ -0,0 +1,65 @@
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use std::hint::black_box;
+use std::time::Duration;
+use voice_input::infrastructure::audio::encoder::flac::encode_flac_i16;
+
+/// 転写前の変換後と同じ 16kHz モノラル
+const SAMPLE_RATE: u32 = 16_000;
+
+/// 無音では圧縮が簡単になりすぎるため、正弦波を重ねた音声を作る
+fn synthetic_samples(secs: u32) -> Vec<i16> {
+    (0..SAMPLE_RATE * secs)
+        .map(|i| {
+            let t = i as f32 / SAMPLE_RATE as f32;
+            let wave = (t * 440.0 * std::f32::consts::TAU).sin() * 0.6
+                + (t * 1_250.0 * std::f32::consts::TAU).sin() * 0.3;
+            (wave * i16::MAX as f32 * 0.5) as i16
+        })
+        .collect()
+}
+
+fn benchmark_compression_levels(c: &mut Criterion) {
+    let mut group = c.benchmark_group("flac_compression_level");
+    group
+        .sample_size(20)
+        .measurement_time(Duration::from_secs(10));
+
+    let samples = synthetic_samples(30);
+    group.throughput(Throughput::Elements(samples.len() as u64));
+    for level in [0u8, 1, 3, 5, 8] {
+        group.bench_with_input(BenchmarkId::new("30s", level), &level, |b, &level| {
+            b.iter(|| {
+                black_box(encode_flac_i16(&samples, SAMPLE_RATE, 1, level).unwrap());
+            });
+        });
+    }
+
+    group.finish();
+}
+
+fn benchmark_buffer_lengths(c: &mut Criterion) {
+    let mut group = c.benchmark_group("flac_buffer_length");
+    group
+        .sample_size(20)
+        .measurement_time(Duration::from_secs(10));
+
+    // 録音の長さによるエンコード時間の伸び方を見る
+    for secs in [5u32, 10, 30, 60] {
+        let samples = synthetic_samples(secs);
+        group.throughput(Throughput::Elements(samples.len() as u64));
+        group.bench_with_input(BenchmarkId::new("level5", secs), &samples, |b, samples| {
+            b.iter(|| {
+                black_box(encode_flac_i16(samples, SAMPLE_RATE, 1, 5).unwrap());
+            });
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    benchmark_compression_levels,
+    benchmark_buffer_lengths,
+);
+criterion_main!(benches);
@@ -6,7 +6,7 @@ REPO_ROOT="${VOICE_INPUT_REPO_ROOT:-$(cd "${DEPLOY_ROOT}/../.." && pwd)}"
 LAUNCH_AGENT_LABEL="${VOICE_INPUT_LAUNCH_AGENT_LABEL:-com.user.voiceinputd}"
 LAUNCH_AGENT_TARGET="gui/$(id -u)/${LAUNCH_AGENT_LABEL}"
 LAUNCH_AGENT_PLIST_PATH="${VOICE_INPUT_LAUNCH_AGENT_PLIST_PATH:-$HOME/Library/LaunchAgents/${LAUNCH_AGENT_LABEL}.plist}"
-SOCKET_PATH="${VOICE_INPUT_SOCKET_PATH:-/tmp/voice_input.sock}"
+SOCKET_PATH="${VOICE_INPUT_SOCKET_PATH:-/tmp/voice_input-$(id -u).sock}"
 STDOUT_PATH="${VOICE_INPUT_STDOUT_PATH:-/tmp/voice_inputd.out}"
 STDERR_PATH="${VOICE_INPUT_STDERR_PATH:-/tmp/voice_inputd.err}"
 ENV_FILE_PATH="${VOICE_INPUT_ENV_FILE_PATH:-${REPO_ROOT}/.env}"
@@ -1,5 +1,19 @@
+use std::path::Path;
 use thiserror::Error;
 
+/// 転写に渡せる音声ファイルの拡張子と MIME タイプ
+const AUDIO_FILE_TYPES: &[(&str, &str)] = &[
+    ("wav", "audio/wav"),
+    ("flac", "audio/flac"),
+    ("mp3", "audio/mpeg"),
+    ("mpga", "audio/mpeg"),
+    ("mpeg", "audio/mpeg"),
+    ("m4a", "audio/mp4"),
+    ("mp4", "audio/mp4"),
+    ("ogg", "audio/ogg"),
+    ("webm", "audio/webm"),
+];
+
 /// 音声データの返却形式
 #[derive(Debug, Clone)]
 pub struct AudioData {
@@ -8,6 +22,46 @@ pub struct AudioData {
     pub file_name: String,
 }
 
+impl AudioData {
+    /// 拡張子から音声ファイルの MIME タイプを判定する
+    pub fn mime_type_for_path(path: &Path) -> Option<&'static str> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        AUDIO_FILE_TYPES
+            .iter()
+            .find(|(ext, _)| *ext == extension)
+            .map(|(_, mime)| *mime)
+    }
+
+    /// 音声ファイルの内容から作成する。未対応の拡張子なら `None`
+    pub fn from_file(path: &Path, bytes: Vec<u8>) -> Option<Self> {
+        let mime_type = Self::mime_type_for_path(path)?;
+        let file_name = path.file_name()?.to_string_lossy().to_string();
+        Some(Self {
+            bytes,
+            mime_type,
+            file_name,
+        })
+    }
+
+    /// 先頭のマジックバイトから WAV / FLAC を判定して作成する。どちらでもなければ `None`
+    ///
+    /// 拡張子のない標準入力などから受け取った音声に使う。
+    pub fn from_stream_bytes(bytes: Vec<u8>) -> Option<Self> {
+        let (mime_type, file_name) = if bytes.starts_with(b"fLaC") {
+            ("audio/flac", "audio.flac")
+        } else if bytes.starts_with(b"RIFF") && bytes.get(8..12) == Some(b"WAVE") {
+            ("audio/wav", "audio.wav")
+        } else {
+            return None;
+        };
+        Some(Self {
+            bytes,
+            mime_type,
+            file_name: file_name.to_string(),
+        })
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum AudioBackendError {
     #[error("audio backend state error: {message}")]
@@ -40,6 +94,16 @@ pub trait AudioBackend {
     fn recover_after_wake(&self) -> Result<(), AudioBackendError> {
         Ok(())
     }
+
+    /// 直近の `stop_recording` で推定した SNR（dB）。推定しない実装は `None`。
+    fn last_capture_snr_db(&self) -> Option<f32> {
+        None
+    }
+
+    /// 録音中の直近の入力に発話が含まれていれば `true`。判定しない実装は `false`。
+    fn is_speech_active(&self) -> bool {
+        false
+    }
 }
 
 /// `AudioBackend` の薄いラッパ。録音 port をアプリケーション層へ提供する。
@@ -72,6 +136,16 @@ impl<T: AudioBackend> Recorder<T> {
     pub fn recover_after_wake(&self) -> Result<(), AudioBackendError> {
         self.backend.recover_after_wake()
     }
+
+    /// 直近の録音の推定 SNR（dB）を返します。
+    pub fn last_capture_snr_db(&self) -> Option<f32> {
+        self.backend.last_capture_snr_db()
+    }
+
+    /// 直近の入力に発話が続いているかを返します。
+    pub fn is_speech_active(&self) -> bool {
+        self.backend.is_speech_active()
+    }
 }
 
 #[cfg(test)]
@@ -115,6 +189,30 @@ mod tests {
         }
     }
 
+    /// 音声ファイルは拡張子の大文字小文字を問わずMIMEタイプを判定する
+    #[test]
+    fn audio_file_mime_type_is_detected_from_extension() {
+        let audio = AudioData::from_file(Path::new("/tmp/memo.M4A"), vec![1]).unwrap();
+
+        assert_eq!(audio.mime_type, "audio/mp4");
+        assert_eq!(audio.file_name, "memo.M4A");
+        assert!(AudioData::from_file(Path::new("/tmp/notes.txt"), vec![1]).is_none());
+        assert!(AudioData::mime_type_for_path(Path::new("/tmp/noext")).is_none());
+    }
+
+    /// 拡張子のない音声は先頭のマジックバイトで WAV / FLAC を判定する
+    #[test]
+    fn stream_bytes_are_detected_from_magic() {
+        let flac = AudioData::from_stream_bytes(b"fLaC\0\0".to_vec()).unwrap();
+        assert_eq!(flac.mime_type, "audio/flac");
+
+        let wav = AudioData::from_stream_bytes(b"RIFF\0\0\0\0WAVEfmt ".to_vec()).unwrap();
+        assert_eq!(wav.mime_type, "audio/wav");
+        assert_eq!(wav.file_name, "audio.wav");
+
+        assert!(AudioData::from_stream_bytes(b"ID3\x04".to_vec()).is_none());
+    }
+
     /// stopがAudioDataを返す
     #[test]
     fn stop_returns_audio_data() {
@@ -0,0 +1,75 @@
+//! 時刻取得の抽象
+//!
+//! 録音時間・自動停止の期限・日時挿入マクロのような時刻依存の判定を、スリープなしで
+//! 決定的にテストできるようにする。
+
+use chrono::NaiveDateTime;
+#[cfg(test)]
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// 現在時刻の取得元
+pub trait Clock {
+    /// 経過時間の計測に使う単調増加の現在時刻を返す
+    fn now(&self) -> Instant;
+
+    /// 日時挿入に使うローカルタイムゾーンの壁時計時刻を返す
+    fn local_now(&self) -> NaiveDateTime;
+}
+
+/// OS の時計をそのまま使う既定の実装
+///
+/// 単調時計は tokio の時計から取り、自動停止のタイマーと同じ時間軸で期限を判定する。
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        tokio::time::Instant::now().into_std()
+    }
+
+    fn local_now(&self) -> NaiveDateTime {
+        chrono::Local::now().naive_local()
+    }
+}
+
+/// 手動で進めるテスト用の時計
+///
+/// スレッドをまたいで使うサービスにも渡せるよう、時刻は `Mutex` で保持する。
+#[cfg(test)]
+#[derive(Debug)]
+pub(crate) struct ManualClock {
+    now: Mutex<(Instant, NaiveDateTime)>,
+}
+
+#[cfg(test)]
+impl ManualClock {
+    /// 現在時刻から始まり、壁時計は 2026-03-05 14:07:00 を指す時計を作成する
+    pub(crate) fn new() -> Self {
+        let local_now = chrono::NaiveDate::from_ymd_opt(2026, 3, 5)
+            .and_then(|date| date.and_hms_opt(14, 7, 0))
+            .expect("valid fixed date");
+        Self {
+            now: Mutex::new((Instant::now(), local_now)),
+        }
+    }
+
+    /// 単調時計と壁時計を同じだけ進める
+    pub(crate) fn advance(&self, duration: std::time::Duration) {
+        let elapsed = chrono::TimeDelta::from_std(duration).expect("duration within range");
+        let mut now = self.now.lock().unwrap();
+        now.0 += duration;
+        now.1 += elapsed;
+    }
+}
+
+#[cfg(test)]
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.now.lock().unwrap().0
+    }
+
+    fn local_now(&self) -> NaiveDateTime {
+        self.now.lock().unwrap().1
+    }
+}
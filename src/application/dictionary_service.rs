@@ -1,4 +1,4 @@
-use crate::domain::dict::{WordEntry, remove_entry, upsert_entry};
+use crate::domain::dict::{EntryAnalysis, WordEntry, analyze_entries, remove_entry, upsert_entry};
 use std::io;
 
 /// 辞書永続化 port
@@ -39,6 +39,11 @@ impl DictionaryService {
         }
         Ok(deleted)
     }
+
+    /// 蓄積された置換回数からエントリの効果を分析。
+    pub fn analyze(&self) -> io::Result<Vec<EntryAnalysis>> {
+        Ok(analyze_entries(&self.repo.load()?))
+    }
 }
 
 #[cfg(test)]
@@ -1,15 +1,17 @@
 pub mod audio;
+pub mod clock;
 pub mod dictionary_service;
 pub mod recording_service;
 pub mod transcription_service;
 
 pub use audio::{AudioBackend, AudioBackendError, AudioData, Recorder};
+pub use clock::{Clock, SystemClock};
 pub use dictionary_service::{DictRepository, DictionaryService};
 pub use recording_service::{
     ActiveRecordingSession, RecordedAudio, RecordingConfig, RecordingContext, RecordingOptions,
     RecordingService, RecordingState, StopRecordingOutcome, StoppedSessionContext,
 };
 pub use transcription_service::{
-    TranscriptionClient, TranscriptionClientError, TranscriptionEvent, TranscriptionLogEntry,
-    TranscriptionLogWriter, TranscriptionOptions, TranscriptionService,
+    DiffReply, TranscriptionClient, TranscriptionClientError, TranscriptionEvent,
+    TranscriptionLogEntry, TranscriptionLogWriter, TranscriptionOptions, TranscriptionService,
 };
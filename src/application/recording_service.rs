@@ -8,9 +8,11 @@
 use std::cell::RefCell;
 use std::rc::Rc;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::sync::oneshot;
 
-use crate::application::{AudioBackend, AudioData, Recorder};
+use crate::application::{AudioBackend, AudioData, Clock, Recorder, SystemClock};
+use crate::domain::input::TrailingAction;
 use crate::error::{Result, VoiceInputError};
 
 /// 録音状態
@@ -33,16 +35,34 @@ pub struct ActiveRecordingSession {
     pub music_was_playing: bool,
     /// 録音開始時点で取得した選択テキストまたはCLIプロンプト
     pub start_prompt: Option<String>,
+    /// 入力前に転写結果を読み上げるか
+    pub readback: bool,
+    /// セッションラベル
+    pub label: Option<String>,
+    /// 入力後に続けて行う動作
+    pub then: TrailingAction,
+    /// 転写結果を入力せず音声コマンドとして解釈するか
+    pub voice_command: bool,
+    /// 録音開始時刻
+    pub started_at: Instant,
+    /// 発話継続により延長した自動停止の秒数
+    pub auto_stop_extended_secs: u64,
 }
 
 impl ActiveRecordingSession {
-    fn new(session_id: u64, options: RecordingOptions) -> Self {
+    fn new(session_id: u64, options: RecordingOptions, started_at: Instant) -> Self {
         let (cancel, _cancel_rx) = oneshot::channel::<()>();
         Self {
             session_id,
             cancel: Some(cancel),
             music_was_playing: false,
             start_prompt: options.prompt,
+            readback: options.readback,
+            label: options.label,
+            then: options.then,
+            voice_command: options.voice_command,
+            started_at,
+            auto_stop_extended_secs: 0,
         }
     }
 }
@@ -97,6 +117,26 @@ impl RecordingState {
         }
     }
 
+    fn auto_stop_remaining(&self, now: Instant, max_duration_secs: u64) -> Option<Duration> {
+        match self {
+            Self::Idle => None,
+            Self::Recording(session) => {
+                let limit_secs = max_duration_secs.saturating_add(session.auto_stop_extended_secs);
+                let deadline = session.started_at + Duration::from_secs(limit_secs);
+                Some(deadline.saturating_duration_since(now))
+            }
+        }
+    }
+
+    fn elapsed_ms(&self, now: Instant) -> u64 {
+        match self {
+            Self::Idle => 0,
+            Self::Recording(session) => now
+                .saturating_duration_since(session.started_at)
+                .as_millis() as u64,
+        }
+    }
+
     fn stopped_context(&self) -> Result<StoppedSessionContext> {
         match self {
             Self::Idle => Err(VoiceInputError::RecordingNotStarted),
@@ -104,6 +144,10 @@ impl RecordingState {
                 session_id: session.session_id,
                 start_prompt: session.start_prompt.clone(),
                 music_was_playing: session.music_was_playing,
+                readback: session.readback,
+                label: session.label.clone(),
+                then: session.then,
+                voice_command: session.voice_command,
             }),
         }
     }
@@ -115,6 +159,10 @@ pub struct StoppedSessionContext {
     pub session_id: u64,
     pub start_prompt: Option<String>,
     pub music_was_playing: bool,
+    pub readback: bool,
+    pub label: Option<String>,
+    pub then: TrailingAction,
+    pub voice_command: bool,
 }
 
 /// 録音停止結果
@@ -122,6 +170,8 @@ pub struct StoppedSessionContext {
 pub struct RecordedAudio {
     pub audio_data: AudioData,
     pub duration_ms: u64,
+    /// 録音から推定した SNR（dB）。推定できない場合は `None`
+    pub snr_db: Option<f32>,
 }
 
 /// 録音停止結果
@@ -136,21 +186,51 @@ pub struct StopRecordingOutcome {
 pub struct RecordingConfig {
     /// 最大録音時間（秒）
     pub max_duration_secs: u64,
+    /// 転写対象とする最小録音時間（ミリ秒）
+    pub min_duration_ms: u64,
+    /// 発話中に最大録音時間へ達した場合に延長できる上限（秒）
+    pub max_extension_secs: u64,
 }
 
 impl Default for RecordingConfig {
     fn default() -> Self {
         Self {
             max_duration_secs: 30,
+            min_duration_ms: 500,
+            max_extension_secs: 10,
         }
     }
 }
 
+impl RecordingConfig {
+    /// 発話継続時に一度に延長する秒数
+    pub const AUTO_STOP_EXTENSION_STEP_SECS: u64 = 2;
+
+    /// 誤操作とみなして転写を見送る短さかを判定
+    pub fn is_too_short(&self, duration_ms: u64) -> bool {
+        duration_ms < self.min_duration_ms
+    }
+
+    /// 既に延長した秒数から次の延長秒数を返す。上限に達していれば `None`
+    pub fn next_extension_secs(&self, extended_secs: u64) -> Option<u64> {
+        let remaining = self.max_extension_secs.saturating_sub(extended_secs);
+        (remaining > 0).then(|| remaining.min(Self::AUTO_STOP_EXTENSION_STEP_SECS))
+    }
+}
+
 /// 録音オプション
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct RecordingOptions {
     /// 録音開始時のプロンプト
     pub prompt: Option<String>,
+    /// 入力前に転写結果を読み上げる
+    pub readback: bool,
+    /// 後から転写ログを探すためのラベル
+    pub label: Option<String>,
+    /// 入力後に続けて行う動作
+    pub then: TrailingAction,
+    /// 転写結果を入力せず音声コマンドとして解釈する
+    pub voice_command: bool,
 }
 
 /// 録音コンテキスト情報
@@ -184,16 +264,28 @@ pub struct RecordingService<T: AudioBackend> {
     pub config: RecordingConfig,
     /// セッションIDカウンター
     session_counter: Arc<Mutex<u64>>,
+    /// 録音時間と自動停止の期限の計測に使う時計
+    clock: Rc<dyn Clock>,
 }
 
 impl<T: AudioBackend> RecordingService<T> {
     /// 新しいRecordingServiceを作成
     pub fn new(recorder: Rc<RefCell<Recorder<T>>>, config: RecordingConfig) -> Self {
+        Self::with_clock(recorder, config, Rc::new(SystemClock))
+    }
+
+    /// 時計を差し替えてRecordingServiceを作成
+    pub fn with_clock(
+        recorder: Rc<RefCell<Recorder<T>>>,
+        config: RecordingConfig,
+        clock: Rc<dyn Clock>,
+    ) -> Self {
         Self {
             recorder,
             context: Arc::new(Mutex::new(RecordingContext::new())),
             config,
             session_counter: Arc::new(Mutex::new(0)),
+            clock,
         }
     }
 
@@ -229,7 +321,11 @@ impl<T: AudioBackend> RecordingService<T> {
             .start()
             .map_err(VoiceInputError::from)?;
 
-        ctx.state = RecordingState::Recording(ActiveRecordingSession::new(session_id, options));
+        ctx.state = RecordingState::Recording(ActiveRecordingSession::new(
+            session_id,
+            options,
+            self.clock.now(),
+        ));
 
         // タイマー処理は呼び出し元で実装（spawn_localの制約のため）
 
@@ -244,6 +340,7 @@ impl<T: AudioBackend> RecordingService<T> {
             .map_err(|e| VoiceInputError::SystemError(format!("Context lock error: {}", e)))?;
 
         let stopped_context = ctx.state.stopped_context()?;
+        let duration_ms = ctx.state.elapsed_ms(self.clock.now());
         if let RecordingState::Recording(session) = &mut ctx.state {
             if let Some(cancel) = session.cancel.take() {
                 let _ = cancel.send(());
@@ -261,16 +358,44 @@ impl<T: AudioBackend> RecordingService<T> {
         };
 
         ctx.state = RecordingState::Idle;
+        let snr_db = self.recorder.borrow().last_capture_snr_db();
 
         Ok(StopRecordingOutcome {
             result: RecordedAudio {
                 audio_data,
-                duration_ms: 0, // TODO: 実際の録音時間を計算
+                duration_ms,
+                snr_db,
             },
             context: stopped_context,
         })
     }
 
+    /// 録音を中止し、取得済みの音声を破棄する
+    ///
+    /// 転写へ回さないため、音声未取得による停止失敗も中止成功として扱う。
+    pub async fn cancel_recording(&self) -> Result<StoppedSessionContext> {
+        let mut ctx = self
+            .context
+            .lock()
+            .map_err(|e| VoiceInputError::SystemError(format!("Context lock error: {}", e)))?;
+
+        let stopped_context = ctx.state.stopped_context()?;
+        if let RecordingState::Recording(session) = &mut ctx.state {
+            if let Some(cancel) = session.cancel.take() {
+                let _ = cancel.send(());
+            }
+        }
+
+        match self.recorder.borrow_mut().stop() {
+            Ok(_discarded) => {}
+            Err(crate::application::AudioBackendError::NoAudioCaptured { .. }) => {}
+            Err(err) => return Err(VoiceInputError::from(err)),
+        }
+
+        ctx.state = RecordingState::Idle;
+        Ok(stopped_context)
+    }
+
     /// 録音中かどうかを確認
     pub fn is_recording(&self) -> bool {
         if let Ok(ctx) = self.context.lock() {
@@ -298,6 +423,15 @@ impl<T: AudioBackend> RecordingService<T> {
         Ok(*counter > session_id)
     }
 
+    /// 直近に発行したセッションIDを返す（録音を伴わない転写の紐付け用）
+    pub fn latest_session_id(&self) -> Result<u64> {
+        let counter = self
+            .session_counter
+            .lock()
+            .map_err(|e| VoiceInputError::SystemError(format!("Counter lock error: {}", e)))?;
+        Ok(*counter)
+    }
+
     /// 自動停止キャンセルチャネルを取得（タイマー処理用）
     pub fn take_cancel_receiver(&self) -> Option<oneshot::Receiver<()>> {
         if let Ok(mut ctx) = self.context.lock() {
@@ -321,6 +455,63 @@ impl<T: AudioBackend> RecordingService<T> {
         Ok(ctx.state.context_info())
     }
 
+    /// 録音中セッションのラベルを取得
+    pub fn active_label(&self) -> Result<Option<String>> {
+        let ctx = self
+            .context
+            .lock()
+            .map_err(|e| VoiceInputError::SystemError(format!("Context lock error: {}", e)))?;
+        Ok(match &ctx.state {
+            RecordingState::Idle => None,
+            RecordingState::Recording(session) => session.label.clone(),
+        })
+    }
+
+    /// 録音中に発話が続いているかを確認
+    pub fn is_speech_active(&self) -> bool {
+        self.is_recording() && self.recorder.borrow().is_speech_active()
+    }
+
+    /// 自動停止の延長を記録し、延長の累計秒数を返す
+    pub fn record_auto_stop_extension(&self, secs: u64) -> Result<u64> {
+        let mut ctx = self
+            .context
+            .lock()
+            .map_err(|e| VoiceInputError::SystemError(format!("Context lock error: {}", e)))?;
+        match &mut ctx.state {
+            RecordingState::Idle => Err(VoiceInputError::RecordingNotStarted),
+            RecordingState::Recording(session) => {
+                session.auto_stop_extended_secs += secs;
+                Ok(session.auto_stop_extended_secs)
+            }
+        }
+    }
+
+    /// 自動停止の期限（最大録音時間と延長分）までの残り時間を注入した時計で求める
+    ///
+    /// 期限を過ぎていればゼロを返す。
+    pub fn auto_stop_remaining(&self) -> Result<Duration> {
+        let ctx = self
+            .context
+            .lock()
+            .map_err(|e| VoiceInputError::SystemError(format!("Context lock error: {}", e)))?;
+        ctx.state
+            .auto_stop_remaining(self.clock.now(), self.config.max_duration_secs)
+            .ok_or(VoiceInputError::RecordingNotStarted)
+    }
+
+    /// 録音中セッションの自動停止延長秒数を取得
+    pub fn auto_stop_extended_secs(&self) -> Result<u64> {
+        let ctx = self
+            .context
+            .lock()
+            .map_err(|e| VoiceInputError::SystemError(format!("Context lock error: {}", e)))?;
+        Ok(match &ctx.state {
+            RecordingState::Idle => 0,
+            RecordingState::Recording(session) => session.auto_stop_extended_secs,
+        })
+    }
+
     /// Apple Music再生状態を設定
     pub fn set_music_was_playing(&self, was_playing: bool) -> Result<()> {
         let mut ctx = self
@@ -510,11 +701,13 @@ mod tests {
         let recorder = Rc::new(RefCell::new(Recorder::new(backend)));
         let config = RecordingConfig {
             max_duration_secs: 30,
+            min_duration_ms: 500,
+            max_extension_secs: 10,
         };
         let service = RecordingService::new(recorder, config);
 
         // 録音開始
-        let options = RecordingOptions { prompt: None };
+        let options = RecordingOptions::default();
         service.start_recording(options).await.unwrap();
 
         // キャンセルレシーバーを取得
@@ -547,6 +740,8 @@ mod tests {
         let recorder = Rc::new(RefCell::new(Recorder::new(backend)));
         let config = RecordingConfig {
             max_duration_secs: 30,
+            min_duration_ms: 500,
+            max_extension_secs: 10,
         };
         let service = RecordingService::new(recorder, config);
 
@@ -555,6 +750,7 @@ mod tests {
             // 録音開始
             let options = RecordingOptions {
                 prompt: Some(format!("Test {}", i)),
+                ..RecordingOptions::default()
             };
             let session_id = service.start_recording(options).await.unwrap();
             assert!(session_id > 0, "Session ID should be positive");
@@ -591,11 +787,13 @@ mod tests {
         let recorder = Rc::new(RefCell::new(Recorder::new(backend)));
         let config = RecordingConfig {
             max_duration_secs: 30,
+            min_duration_ms: 500,
+            max_extension_secs: 10,
         };
         let service = RecordingService::new(recorder, config);
 
         service
-            .start_recording(RecordingOptions { prompt: None })
+            .start_recording(RecordingOptions::default())
             .await
             .unwrap();
 
@@ -604,7 +802,7 @@ mod tests {
         assert!(matches!(error, VoiceInputError::NoAudioCaptured(_)));
         assert!(!service.is_recording());
         service
-            .start_recording(RecordingOptions { prompt: None })
+            .start_recording(RecordingOptions::default())
             .await
             .unwrap();
         assert!(service.is_recording());
@@ -618,6 +816,8 @@ mod tests {
         let recorder = Rc::new(RefCell::new(Recorder::new(backend)));
         let config = RecordingConfig {
             max_duration_secs: 30,
+            min_duration_ms: 500,
+            max_extension_secs: 10,
         };
         let service = RecordingService::new(recorder, config);
 
@@ -634,11 +834,13 @@ mod tests {
         let recorder = Rc::new(RefCell::new(Recorder::new(backend)));
         let config = RecordingConfig {
             max_duration_secs: 30,
+            min_duration_ms: 500,
+            max_extension_secs: 10,
         };
         let service = RecordingService::new(recorder, config);
 
         service
-            .start_recording(RecordingOptions { prompt: None })
+            .start_recording(RecordingOptions::default())
             .await
             .unwrap();
 
@@ -647,6 +849,77 @@ mod tests {
         assert_eq!(recover_calls.load(Ordering::SeqCst), 0);
     }
 
+    /// 録音時間は注入した時計で計測され、スリープなしで決定的に検証できる
+    #[tokio::test]
+    async fn stop_reports_duration_measured_by_injected_clock() {
+        let clock = Rc::new(crate::application::clock::ManualClock::new());
+        let recorder = Rc::new(RefCell::new(Recorder::new(MockAudioBackend::new())));
+        let service = RecordingService::with_clock(
+            recorder,
+            RecordingConfig::default(),
+            clock.clone() as Rc<dyn Clock>,
+        );
+
+        service
+            .start_recording(RecordingOptions::default())
+            .await
+            .unwrap();
+        clock.advance(Duration::from_millis(1234));
+        let outcome = service.stop_recording().await.unwrap();
+
+        assert_eq!(outcome.result.duration_ms, 1234);
+        assert!(!service.config().is_too_short(outcome.result.duration_ms));
+    }
+
+    /// 自動停止の期限は注入した時計で測り、延長した分だけ後ろへずれる
+    #[tokio::test]
+    async fn auto_stop_remaining_is_measured_by_injected_clock() {
+        let clock = Rc::new(crate::application::clock::ManualClock::new());
+        let recorder = Rc::new(RefCell::new(Recorder::new(MockAudioBackend::new())));
+        let service = RecordingService::with_clock(
+            recorder,
+            RecordingConfig {
+                max_duration_secs: 5,
+                ..RecordingConfig::default()
+            },
+            clock.clone() as Rc<dyn Clock>,
+        );
+        assert!(service.auto_stop_remaining().is_err());
+
+        service
+            .start_recording(RecordingOptions::default())
+            .await
+            .unwrap();
+        clock.advance(Duration::from_secs(4));
+        assert_eq!(
+            service.auto_stop_remaining().unwrap(),
+            Duration::from_secs(1)
+        );
+
+        clock.advance(Duration::from_secs(2));
+        assert_eq!(service.auto_stop_remaining().unwrap(), Duration::ZERO);
+
+        service.record_auto_stop_extension(2).unwrap();
+        assert_eq!(
+            service.auto_stop_remaining().unwrap(),
+            Duration::from_secs(1)
+        );
+    }
+
+    /// 自動停止の延長は一定刻みで進み、上限で打ち切られる
+    #[test]
+    fn auto_stop_extension_is_stepped_and_capped() {
+        let config = RecordingConfig {
+            max_duration_secs: 30,
+            min_duration_ms: 500,
+            max_extension_secs: 5,
+        };
+
+        assert_eq!(config.next_extension_secs(0), Some(2));
+        assert_eq!(config.next_extension_secs(4), Some(1));
+        assert_eq!(config.next_extension_secs(5), None);
+    }
+
     /// コンテキスト状態が期待通りに遷移する
     #[test]
     fn context_state_transitions_are_consistent() {
@@ -654,6 +927,8 @@ mod tests {
         let recorder = Rc::new(RefCell::new(Recorder::new(backend)));
         let config = RecordingConfig {
             max_duration_secs: 30,
+            min_duration_ms: 500,
+            max_extension_secs: 10,
         };
         let service = RecordingService::new(recorder, config);
 
@@ -673,6 +948,7 @@ mod tests {
             service
                 .start_recording(RecordingOptions {
                     prompt: Some("prompt".to_string()),
+                    ..RecordingOptions::default()
                 })
                 .await
                 .unwrap();
@@ -691,11 +967,13 @@ mod tests {
         let recorder = Rc::new(RefCell::new(Recorder::new(backend)));
         let config = RecordingConfig {
             max_duration_secs: 30,
+            min_duration_ms: 500,
+            max_extension_secs: 10,
         };
         let service = RecordingService::new(recorder, config);
 
         let first_session = service
-            .start_recording(RecordingOptions { prompt: None })
+            .start_recording(RecordingOptions::default())
             .await
             .unwrap();
         assert!(service.is_active_session(first_session).unwrap());
@@ -705,7 +983,7 @@ mod tests {
         assert!(!service.is_active_session(first_session).unwrap());
 
         let second_session = service
-            .start_recording(RecordingOptions { prompt: None })
+            .start_recording(RecordingOptions::default())
             .await
             .unwrap();
         assert_ne!(first_session, second_session);
@@ -720,11 +998,13 @@ mod tests {
         let recorder = Rc::new(RefCell::new(Recorder::new(backend)));
         let config = RecordingConfig {
             max_duration_secs: 30,
+            min_duration_ms: 500,
+            max_extension_secs: 10,
         };
         let service = RecordingService::new(recorder, config);
 
         let first_session = service
-            .start_recording(RecordingOptions { prompt: None })
+            .start_recording(RecordingOptions::default())
             .await
             .unwrap();
         assert!(!service.has_started_newer_session(first_session).unwrap());
@@ -733,7 +1013,7 @@ mod tests {
         assert!(!service.has_started_newer_session(first_session).unwrap());
 
         let second_session = service
-            .start_recording(RecordingOptions { prompt: None })
+            .start_recording(RecordingOptions::default())
             .await
             .unwrap();
 
@@ -742,6 +1022,47 @@ mod tests {
         assert!(!service.has_started_newer_session(second_session).unwrap());
     }
 
+    /// 読み上げ指定は停止後の文脈へ引き継がれる
+    #[tokio::test]
+    async fn readback_option_is_carried_to_stopped_context() {
+        let backend = MockAudioBackend::new();
+        let recorder = Rc::new(RefCell::new(Recorder::new(backend)));
+        let service = RecordingService::new(recorder, RecordingConfig::default());
+
+        service
+            .start_recording(RecordingOptions {
+                readback: true,
+                ..RecordingOptions::default()
+            })
+            .await
+            .unwrap();
+        let outcome = service.stop_recording().await.unwrap();
+
+        assert!(outcome.context.readback);
+    }
+
+    /// ラベルは録音中に参照でき、停止後の文脈へ引き継がれる
+    #[tokio::test]
+    async fn label_is_visible_while_recording_and_carried_to_context() {
+        let backend = MockAudioBackend::new();
+        let recorder = Rc::new(RefCell::new(Recorder::new(backend)));
+        let service = RecordingService::new(recorder, RecordingConfig::default());
+
+        service
+            .start_recording(RecordingOptions {
+                label: Some("議事録".to_string()),
+                ..RecordingOptions::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(service.active_label().unwrap().as_deref(), Some("議事録"));
+
+        let outcome = service.stop_recording().await.unwrap();
+
+        assert_eq!(outcome.context.label.as_deref(), Some("議事録"));
+        assert_eq!(service.active_label().unwrap(), None);
+    }
+
     /// 録音停止失敗時も録音状態と文脈が維持される
     #[tokio::test]
     async fn stop_failure_keeps_active_session_state() {
@@ -749,12 +1070,15 @@ mod tests {
         let recorder = Rc::new(RefCell::new(Recorder::new(backend)));
         let config = RecordingConfig {
             max_duration_secs: 30,
+            min_duration_ms: 500,
+            max_extension_secs: 10,
         };
         let service = RecordingService::new(recorder, config);
 
         let session_id = service
             .start_recording(RecordingOptions {
                 prompt: Some("prompt".to_string()),
+                ..RecordingOptions::default()
             })
             .await
             .unwrap();
@@ -774,4 +1098,83 @@ mod tests {
             (Some("prompt".to_string()), true)
         );
     }
+
+    /// 録音中止で待機状態に戻り、自動停止タイマーにも通知される
+    #[tokio::test]
+    async fn cancel_returns_to_idle_and_notifies_timer() {
+        let backend = MockAudioBackend::new();
+        let recorder = Rc::new(RefCell::new(Recorder::new(backend)));
+        let service = RecordingService::new(recorder, RecordingConfig::default());
+
+        let session_id = service
+            .start_recording(RecordingOptions::default())
+            .await
+            .unwrap();
+        let cancel_rx = service.take_cancel_receiver().unwrap();
+
+        let context = service.cancel_recording().await.unwrap();
+
+        assert_eq!(context.session_id, session_id);
+        assert!(!service.is_recording());
+        assert!(timeout(Duration::from_millis(10), cancel_rx).await.is_ok());
+    }
+
+    /// 音声未取得でも録音中止は成功する
+    #[tokio::test]
+    async fn cancel_succeeds_without_captured_audio() {
+        let backend = NoAudioCapturedBackend::new();
+        let recorder = Rc::new(RefCell::new(Recorder::new(backend)));
+        let service = RecordingService::new(recorder, RecordingConfig::default());
+
+        service
+            .start_recording(RecordingOptions::default())
+            .await
+            .unwrap();
+
+        assert!(service.cancel_recording().await.is_ok());
+        assert!(!service.is_recording());
+    }
+
+    /// 待機中の録音中止は未開始エラーになる
+    #[tokio::test]
+    async fn cancel_while_idle_reports_not_started() {
+        let backend = MockAudioBackend::new();
+        let recorder = Rc::new(RefCell::new(Recorder::new(backend)));
+        let service = RecordingService::new(recorder, RecordingConfig::default());
+
+        let error = service.cancel_recording().await.unwrap_err();
+
+        assert!(matches!(error, VoiceInputError::RecordingNotStarted));
+    }
+
+    /// 停止結果に録音開始からの経過時間が入る
+    #[tokio::test]
+    async fn stop_reports_elapsed_duration() {
+        let backend = MockAudioBackend::new();
+        let recorder = Rc::new(RefCell::new(Recorder::new(backend)));
+        let service = RecordingService::new(recorder, RecordingConfig::default());
+
+        service
+            .start_recording(RecordingOptions::default())
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        let outcome = service.stop_recording().await.unwrap();
+
+        assert!(outcome.result.duration_ms >= 20);
+    }
+
+    /// 最小録音時間未満だけを短すぎると判定する
+    #[test]
+    fn too_short_threshold_is_exclusive() {
+        let config = RecordingConfig {
+            max_duration_secs: 30,
+            min_duration_ms: 500,
+            max_extension_secs: 10,
+        };
+
+        assert!(config.is_too_short(499));
+        assert!(!config.is_too_short(500));
+    }
 }
@@ -5,15 +5,19 @@
 //! - 辞書変換の適用
 //! - 同時実行数の制御
 
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use tokio::sync::Semaphore;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, oneshot};
 
-use crate::application::{AudioData, DictRepository};
-use crate::domain::dict::apply_replacements_with_mappings;
+use crate::application::{AudioData, Clock, DictRepository, SystemClock};
+use crate::domain::dict::{
+    DictionaryMatcher, ReplacementOutput, WordEntry, parse_register_word_command, upsert_entry,
+};
 use crate::domain::transcription::{
-    FinalizedTranscription, TranscriptionOutput, TranscriptionToken, plan_low_confidence_selection,
+    FinalizedTranscription, TranscriptDiff, TranscriptionOutput, TranscriptionToken,
+    plan_low_confidence_selection,
 };
+use crate::domain::voice_macro::{StampFormats, expand_case_macros, expand_stamp_macros};
 use crate::error::{Result, VoiceInputError};
 use crate::utils::config::EnvConfig;
 use crate::utils::profiling;
@@ -40,6 +44,9 @@ pub struct TranscriptionLogEntry {
     pub processed_text: String,
     /// トークン情報
     pub tokens: Vec<TranscriptionToken>,
+    /// 録音開始時に指定されたラベル
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
 }
 
 /// 転写ログの非同期保存要求
@@ -51,17 +58,23 @@ pub trait TranscriptionLogWriter: Send + Sync {
 /// 音声文字起こし機能の抽象化
 #[async_trait]
 pub trait TranscriptionClient: Send + Sync {
-    /// 音声データを文字起こし
-    async fn transcribe(&self, audio: AudioData, language: &str) -> Result<TranscriptionOutput>;
+    /// 音声データを文字起こし。`prompt` は対応するプロバイダーのみ利用する
+    async fn transcribe(
+        &self,
+        audio: AudioData,
+        language: &str,
+        prompt: Option<&str>,
+    ) -> Result<TranscriptionOutput>;
 
     /// 音声データをストリーミングで文字起こしする
     async fn transcribe_streaming(
         &self,
         audio: AudioData,
         language: &str,
+        prompt: Option<&str>,
         _event_tx: mpsc::UnboundedSender<TranscriptionEvent>,
     ) -> Result<TranscriptionOutput> {
-        self.transcribe(audio, language).await
+        self.transcribe(audio, language, prompt).await
     }
 }
 
@@ -74,13 +87,22 @@ pub enum TranscriptionEvent {
     Completed(FinalizedTranscription),
 }
 
+/// 後処理差分の返信先。差分がない場合はその理由を送る
+pub type DiffReply = oneshot::Sender<std::result::Result<TranscriptDiff, String>>;
+
 /// 転写オプション
-#[derive(Clone, Debug)]
+#[derive(Debug)]
 pub struct TranscriptionOptions {
     /// 言語設定
     pub language: String,
     /// プロンプト（コンテキスト）
     pub prompt: Option<String>,
+    /// 転写ログへ残すセッションラベル
+    pub label: Option<String>,
+    /// 転写結果を入力せず音声コマンドとして解釈する
+    pub voice_command: bool,
+    /// 転写完了時（失敗時も含む）に後処理差分を返す先
+    pub diff_reply: Option<DiffReply>,
 }
 
 impl Default for TranscriptionOptions {
@@ -88,6 +110,9 @@ impl Default for TranscriptionOptions {
         Self {
             language: "ja".to_string(),
             prompt: None,
+            label: None,
+            voice_command: false,
+            diff_reply: None,
         }
     }
 }
@@ -104,6 +129,10 @@ pub struct TranscriptionService {
     semaphore: Arc<Semaphore>,
     /// 調査用ログ保存
     log_writer: Option<Box<dyn TranscriptionLogWriter>>,
+    /// 辞書が変わるまで使い回す置換器
+    dict_matcher: Mutex<Option<Arc<DictionaryMatcher>>>,
+    /// 日時挿入マクロの展開に使う時計
+    clock: Arc<dyn Clock + Send + Sync>,
 }
 
 impl TranscriptionService {
@@ -118,6 +147,8 @@ impl TranscriptionService {
             dict_repo,
             semaphore: Arc::new(Semaphore::new(max_concurrent)),
             log_writer: None,
+            dict_matcher: Mutex::new(None),
+            clock: Arc::new(SystemClock),
         }
     }
 
@@ -133,15 +164,33 @@ impl TranscriptionService {
             dict_repo,
             semaphore: Arc::new(Semaphore::new(max_concurrent)),
             log_writer: Some(log_writer),
+            dict_matcher: Mutex::new(None),
+            clock: Arc::new(SystemClock),
         }
     }
 
+    /// 日時挿入マクロの展開に使う時計を差し替える
+    pub fn with_clock(mut self, clock: Arc<dyn Clock + Send + Sync>) -> Self {
+        self.clock = clock;
+        self
+    }
+
     /// 音声データを文字起こし
     pub async fn transcribe(
         &self,
         audio: AudioData,
-        options: TranscriptionOptions,
+        mut options: TranscriptionOptions,
     ) -> Result<FinalizedTranscription> {
+        let diff_reply = options.diff_reply.take();
+        let result = self.transcribe_with_diff(audio, options).await;
+        reply_diff(diff_reply, result)
+    }
+
+    async fn transcribe_with_diff(
+        &self,
+        audio: AudioData,
+        options: TranscriptionOptions,
+    ) -> Result<(FinalizedTranscription, DiffOutcome)> {
         let overall_timer = profiling::Timer::start("transcription.total");
 
         // セマフォで同時実行数を制限
@@ -151,12 +200,47 @@ impl TranscriptionService {
 
         // 転写実行
         let api_timer = profiling::Timer::start("transcription.api");
-        let output = self.client.transcribe(audio, &options.language).await?;
+        let output = self
+            .client
+            .transcribe(audio, &options.language, options.prompt.as_deref())
+            .await?;
         api_timer.log();
 
+        // 音声コマンドとして録音した場合は入力せず、単語登録ならその場で辞書へ登録する
+        if options.voice_command {
+            let entry = parse_register_word_command(&output.text);
+            let reason = match &entry {
+                Some(entry) => {
+                    self.register_word(entry.clone())?;
+                    format!(
+                        "registered dictionary entry {} -> {}; nothing was typed",
+                        entry.surface, entry.replacement
+                    )
+                }
+                None => format!("not a recognized voice command: {:?}", output.text),
+            };
+            overall_timer.log();
+            return Ok((
+                FinalizedTranscription {
+                    text: String::new(),
+                    low_confidence_selection: None,
+                    registered_entry: entry,
+                },
+                Err(reason),
+            ));
+        }
+
         // 辞書変換を適用
         let dict_timer = profiling::Timer::start("transcription.dict");
         let processed = self.apply_dictionary(&output.text)?;
+        // 日時挿入・識別子変換のマクロを展開する（ストリーミングでは入力済みのため行わない）
+        let processed = expand_case_macros(expand_stamp_macros(
+            processed,
+            self.clock.local_now(),
+            StampFormats {
+                datetime: EnvConfig::get().transcription.timestamp_format.as_deref(),
+            },
+        ));
         if profiling::enabled() {
             dict_timer.log_with(&format!(
                 "text_len={} processed_len={}",
@@ -168,23 +252,37 @@ impl TranscriptionService {
         }
 
         let finalized = self.build_finalized_transcription(&output, &processed);
-        self.enqueue_transcription_log(&output, &finalized.text);
+        self.enqueue_transcription_log(&output, &finalized.text, options.label);
+        let diff = TranscriptDiff::from_replacement(&output.text, &processed);
 
         if profiling::enabled() {
             overall_timer.log_with(&format!("processed_len={}", finalized.text.len()));
         } else {
             overall_timer.log();
         }
-        Ok(finalized)
+        Ok((finalized, Ok(diff)))
     }
 
     /// 音声データをストリーミングで文字起こし
     pub async fn transcribe_streaming(
         &self,
         audio: AudioData,
-        options: TranscriptionOptions,
+        mut options: TranscriptionOptions,
         event_tx: mpsc::UnboundedSender<TranscriptionEvent>,
     ) -> Result<FinalizedTranscription> {
+        let diff_reply = options.diff_reply.take();
+        let result = self
+            .transcribe_streaming_with_diff(audio, options, event_tx)
+            .await;
+        reply_diff(diff_reply, result)
+    }
+
+    async fn transcribe_streaming_with_diff(
+        &self,
+        audio: AudioData,
+        options: TranscriptionOptions,
+        event_tx: mpsc::UnboundedSender<TranscriptionEvent>,
+    ) -> Result<(FinalizedTranscription, DiffOutcome)> {
         let overall_timer = profiling::Timer::start("transcription.streaming_total");
 
         let _permit = self.semaphore.acquire().await.map_err(|e| {
@@ -194,7 +292,12 @@ impl TranscriptionService {
         let api_timer = profiling::Timer::start("transcription.streaming_api");
         let output = self
             .client
-            .transcribe_streaming(audio, &options.language, event_tx.clone())
+            .transcribe_streaming(
+                audio,
+                &options.language,
+                options.prompt.as_deref(),
+                event_tx.clone(),
+            )
             .await?;
         api_timer.log();
 
@@ -211,7 +314,8 @@ impl TranscriptionService {
         }
 
         let finalized = self.build_finalized_transcription(&output, &processed);
-        self.enqueue_transcription_log(&output, &finalized.text);
+        self.enqueue_transcription_log(&output, &finalized.text, options.label);
+        let diff = TranscriptDiff::from_replacement(&output.text, &processed);
         let _ = event_tx.send(TranscriptionEvent::Completed(finalized.clone()));
 
         if profiling::enabled() {
@@ -220,7 +324,7 @@ impl TranscriptionService {
             overall_timer.log();
         }
 
-        Ok(finalized)
+        Ok((finalized, Ok(diff)))
     }
 
     fn build_finalized_transcription(
@@ -244,16 +348,29 @@ impl TranscriptionService {
         FinalizedTranscription {
             text: processed.text.clone(),
             low_confidence_selection,
+            registered_entry: None,
         }
     }
 
+    /// 音声コマンドで指定された単語を辞書へ追加または更新する
+    fn register_word(&self, entry: WordEntry) -> Result<()> {
+        let mut entries = self.dict_repo.load().map_err(|e| {
+            VoiceInputError::SystemError(format!("Failed to load dictionary: {}", e))
+        })?;
+        upsert_entry(&mut entries, entry);
+        self.dict_repo
+            .save(&entries)
+            .map_err(|e| VoiceInputError::SystemError(format!("Failed to save dictionary: {}", e)))
+    }
+
     /// 辞書変換を適用
-    fn apply_dictionary(&self, text: &str) -> Result<crate::domain::dict::ReplacementOutput> {
+    fn apply_dictionary(&self, text: &str) -> Result<ReplacementOutput> {
         let mut entries = self.dict_repo.load().map_err(|e| {
             VoiceInputError::SystemError(format!("Failed to load dictionary: {}", e))
         })?;
 
-        let result = apply_replacements_with_mappings(text, &mut entries);
+        let matcher = self.dictionary_matcher(&entries);
+        let result = matcher.apply(text, &mut entries);
 
         // 変更があった場合は保存
         if entries.iter().any(|e| e.hit > 0) {
@@ -265,8 +382,29 @@ impl TranscriptionService {
         Ok(result)
     }
 
+    /// 辞書内容が変わっていれば置換器を作り直して返す
+    fn dictionary_matcher(&self, entries: &[WordEntry]) -> Arc<DictionaryMatcher> {
+        let mut cached = self
+            .dict_matcher
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        match cached.as_ref() {
+            Some(matcher) if matcher.is_built_for(entries) => matcher.clone(),
+            _ => {
+                let matcher = Arc::new(DictionaryMatcher::new(entries));
+                *cached = Some(matcher.clone());
+                matcher
+            }
+        }
+    }
+
     /// 調査用の転写ログ保存を非同期キューに積む
-    fn enqueue_transcription_log(&self, output: &TranscriptionOutput, processed_text: &str) {
+    fn enqueue_transcription_log(
+        &self,
+        output: &TranscriptionOutput,
+        processed_text: &str,
+        label: Option<String>,
+    ) {
         let Some(log_writer) = &self.log_writer else {
             return;
         };
@@ -276,6 +414,7 @@ impl TranscriptionService {
             raw_text: output.text.clone(),
             processed_text: processed_text.to_string(),
             tokens: output.tokens.clone(),
+            label,
         };
 
         if let Err(error) = log_writer.enqueue(entry) {
@@ -289,6 +428,30 @@ impl TranscriptionService {
     }
 }
 
+/// 転写の結果に応じた差分、または差分がない理由
+type DiffOutcome = std::result::Result<TranscriptDiff, String>;
+
+/// 差分の返信先があれば結果を送り、転写結果だけを返す。失敗した場合は理由を送る
+fn reply_diff(
+    reply: Option<DiffReply>,
+    result: Result<(FinalizedTranscription, DiffOutcome)>,
+) -> Result<FinalizedTranscription> {
+    match result {
+        Ok((finalized, diff)) => {
+            if let Some(reply) = reply {
+                let _ = reply.send(diff);
+            }
+            Ok(finalized)
+        }
+        Err(e) => {
+            if let Some(reply) = reply {
+                let _ = reply.send(Err(format!("transcription failed: {}", e)));
+            }
+            Err(e)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -331,6 +494,7 @@ mod tests {
             &self,
             _audio: AudioData,
             _language: &str,
+            _prompt: Option<&str>,
         ) -> Result<TranscriptionOutput> {
             *self.call_count.lock().unwrap() += 1;
             Ok(TranscriptionOutput::from_text(self.response.clone()))
@@ -365,6 +529,22 @@ mod tests {
         }
     }
 
+    /// 外部から内容を書き換えられる辞書リポジトリ
+    struct SharedDictRepo {
+        entries: Arc<Mutex<Vec<crate::domain::dict::WordEntry>>>,
+    }
+
+    impl DictRepository for SharedDictRepo {
+        fn load(&self) -> std::io::Result<Vec<crate::domain::dict::WordEntry>> {
+            Ok(self.entries.lock().unwrap().clone())
+        }
+
+        fn save(&self, entries: &[crate::domain::dict::WordEntry]) -> std::io::Result<()> {
+            *self.entries.lock().unwrap() = entries.to_vec();
+            Ok(())
+        }
+    }
+
     struct MockLogWriter {
         entries: Arc<Mutex<Vec<TranscriptionLogEntry>>>,
     }
@@ -384,6 +564,84 @@ mod tests {
         }
     }
 
+    /// 日時挿入マクロは注入した時計の時刻で展開する
+    #[tokio::test]
+    async fn stamp_macro_uses_injected_clock() {
+        init_env_config();
+        let clock = Arc::new(crate::application::clock::ManualClock::new());
+        let service = TranscriptionService::new(
+            Box::new(MockTranscriptionClient::new("会議は日付を挿入")),
+            Box::new(SharedDictRepo {
+                entries: Arc::new(Mutex::new(Vec::new())),
+            }),
+            1,
+        )
+        .with_clock(clock.clone());
+        let audio = AudioData {
+            bytes: vec![0u8; 100],
+            mime_type: "audio/wav",
+            file_name: "audio.wav".to_string(),
+        };
+
+        clock.advance(std::time::Duration::from_secs(24 * 60 * 60));
+        let finalized = service
+            .transcribe(audio, TranscriptionOptions::default())
+            .await
+            .unwrap();
+
+        assert_eq!(finalized.text, "会議は2026年3月6日");
+    }
+
+    /// 音声コマンドとして録音した単語登録だけを、入力せずに辞書へ登録する
+    #[tokio::test]
+    async fn register_word_command_upserts_dictionary_instead_of_input() {
+        init_env_config();
+        let entries = Arc::new(Mutex::new(Vec::new()));
+        let service = TranscriptionService::new(
+            Box::new(MockTranscriptionClient::new(
+                "単語登録：くろーど を Claude に登録",
+            )),
+            Box::new(SharedDictRepo {
+                entries: entries.clone(),
+            }),
+            1,
+        );
+
+        let audio = AudioData {
+            bytes: vec![0u8; 100],
+            mime_type: "audio/wav",
+            file_name: "audio.wav".to_string(),
+        };
+
+        // 通常の録音ではコマンドの言い回しもそのまま入力する
+        let typed = service
+            .transcribe(audio.clone(), TranscriptionOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(typed.text, "単語登録：くろーど を Claude に登録");
+        assert!(typed.registered_entry.is_none());
+        assert!(entries.lock().unwrap().is_empty());
+
+        let finalized = service
+            .transcribe(
+                audio,
+                TranscriptionOptions {
+                    voice_command: true,
+                    ..TranscriptionOptions::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(finalized.text, "");
+        let registered = finalized
+            .registered_entry
+            .expect("entry should be registered");
+        assert_eq!(registered.surface, "くろーど");
+        assert_eq!(registered.replacement, "Claude");
+        assert_eq!(entries.lock().unwrap().as_slice(), [registered]);
+    }
+
     /// 辞書変換が転写結果に適用される
     #[tokio::test]
     async fn transcription_applies_dictionary() {
@@ -403,6 +661,72 @@ mod tests {
         assert_eq!(result.text, "これはtestです");
     }
 
+    /// 転写が完了すると生テキストとの差分が返信先へ送られる
+    #[tokio::test]
+    async fn transcription_replies_diff_against_raw_text() {
+        init_env_config();
+        let service = TranscriptionService::new(
+            Box::new(MockTranscriptionClient::new("これはテストです")),
+            Box::new(MockDictRepo::new()),
+            1,
+        );
+        let (diff_tx, diff_rx) = oneshot::channel();
+        let audio = AudioData {
+            bytes: vec![0u8; 100],
+            mime_type: "audio/wav",
+            file_name: "audio.wav".to_string(),
+        };
+
+        service
+            .transcribe(
+                audio,
+                TranscriptionOptions {
+                    diff_reply: Some(diff_tx),
+                    ..TranscriptionOptions::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        let diff = diff_rx.await.unwrap().unwrap();
+        assert_eq!(diff.raw_text, "これはテストです");
+        assert_eq!(diff.processed_text, "これはtestです");
+        assert_eq!(diff.changes.len(), 1);
+    }
+
+    /// 辞書の置換内容が変わると次の転写から新しい置換が使われる
+    #[tokio::test]
+    async fn dictionary_changes_are_reflected_in_next_transcription() {
+        init_env_config();
+        let entries = Arc::new(Mutex::new(MockDictRepo::new().entries));
+        let service = TranscriptionService::new(
+            Box::new(MockTranscriptionClient::new("これはテストです")),
+            Box::new(SharedDictRepo {
+                entries: entries.clone(),
+            }),
+            1,
+        );
+        let audio = AudioData {
+            bytes: vec![0u8; 100],
+            mime_type: "audio/wav",
+            file_name: "audio.wav".to_string(),
+        };
+
+        let first = service
+            .transcribe(audio.clone(), TranscriptionOptions::default())
+            .await
+            .unwrap();
+        entries.lock().unwrap()[0].replacement = "TEST".to_string();
+        let second = service
+            .transcribe(audio, TranscriptionOptions::default())
+            .await
+            .unwrap();
+
+        assert_eq!(first.text, "これはtestです");
+        assert_eq!(second.text, "これはTESTです");
+        assert_eq!(entries.lock().unwrap()[0].hit, 2);
+    }
+
     /// 転写処理でプロファイルログが出力される
     #[tokio::test]
     async fn profile_log_is_emitted_during_transcription() {
@@ -496,6 +820,7 @@ mod tests {
             TranscriptionEvent::Completed(FinalizedTranscription {
                 text: "これはtestです".to_string(),
                 low_confidence_selection: None,
+                registered_entry: None,
             })
         );
     }
@@ -512,6 +837,7 @@ mod tests {
                 &self,
                 _audio: AudioData,
                 _language: &str,
+                _prompt: Option<&str>,
             ) -> Result<TranscriptionOutput> {
                 Ok(TranscriptionOutput::from_text(
                     "これはテストです".to_string(),
@@ -522,6 +848,7 @@ mod tests {
                 &self,
                 _audio: AudioData,
                 _language: &str,
+                _prompt: Option<&str>,
                 event_tx: mpsc::UnboundedSender<TranscriptionEvent>,
             ) -> Result<TranscriptionOutput> {
                 let _ = event_tx.send(TranscriptionEvent::Delta("これは".to_string()));
@@ -564,6 +891,7 @@ mod tests {
                 TranscriptionEvent::Completed(FinalizedTranscription {
                     text: "これはtestです".to_string(),
                     low_confidence_selection: None,
+                    registered_entry: None,
                 }),
             ]
         );
@@ -581,6 +909,7 @@ mod tests {
                 &self,
                 _audio: AudioData,
                 _language: &str,
+                _prompt: Option<&str>,
             ) -> Result<TranscriptionOutput> {
                 Ok(TranscriptionOutput {
                     text: "これはテストです".to_string(),
@@ -616,7 +945,13 @@ mod tests {
         };
 
         let result = service
-            .transcribe(audio, TranscriptionOptions::default())
+            .transcribe(
+                audio,
+                TranscriptionOptions {
+                    label: Some("議事録".to_string()),
+                    ..TranscriptionOptions::default()
+                },
+            )
             .await
             .unwrap();
 
@@ -624,6 +959,7 @@ mod tests {
 
         let entries = recorded_entries.lock().unwrap().clone();
         assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].label.as_deref(), Some("議事録"));
         assert_eq!(entries[0].raw_text, "これはテストです");
         assert_eq!(entries[0].processed_text, "これはtestです");
         assert_eq!(
@@ -7,38 +7,31 @@
 //!  - 直接入力 & Apple Music の自動ポーズ / 再開
 //!    を非同期・協調的に実行します。
 //!
-//! *ソケットパス*: `/tmp/voice_input.sock`（環境変数で上書き可能）
+//! *ソケットパス*: `/tmp/voice_input-<uid>.sock`（環境変数で上書き可能）
 
-#![allow(clippy::await_holding_refcell_ref)]
+use std::{error::Error, process, time::Duration};
 
-use std::{
-    error::Error,
-    fs, process,
-    time::{Duration, SystemTime},
-};
-
-use futures::{SinkExt, StreamExt};
-use tokio::{
-    net::{UnixListener, UnixStream},
-    sync::Semaphore,
-    task::{LocalSet, spawn_local},
-};
-use tokio_util::codec::{FramedRead, FramedWrite, LinesCodec};
+use tokio::task::LocalSet;
 use voice_input::{
     error::{Result, VoiceInputError},
     infrastructure::{
         audio::CpalAudioBackend,
-        command_handler::CommandHandler,
+        daemon,
         external::text_input,
-        runtime_recovery::{SleepWakeDetector, WakeRecoveryRetryPolicy},
+        legacy_state::cleanup_legacy_state,
+        resource_limits::apply_nice_level,
         service_container::ServiceContainer,
-        transcription_worker::spawn_transcription_worker,
+        soak::{DEFAULT_SOAK_CYCLES, SoakLimits, run_soak},
+        startup::{self, StartupStage},
     },
-    ipc::{IpcCmd, IpcResp, socket_path},
+    ipc::socket_path,
     load_env,
     utils::config::EnvConfig,
 };
 
+/// 終了時に実行中の転写の完了を待つ上限
+const SHUTDOWN_DRAIN_TIMEOUT: Duration = Duration::from_secs(10);
+
 // ────────────────────────────────────────────────────────
 // エントリポイント： single‑thread Tokio runtime
 // ────────────────────────────────────────────────────────
@@ -46,160 +39,110 @@ use voice_input::{
 /// エントリポイント。環境変数を読み込み、`async_main` を current‑thread ランタイムで実行します。
 #[tokio::main(flavor = "current_thread")]
 async fn main() -> std::result::Result<(), Box<dyn Error>> {
-    load_env();
-
     // 環境変数設定を初期化
-    EnvConfig::init().map_err(|e| VoiceInputError::ConfigInitError(e.to_string()))?;
+    startup::measure(StartupStage::ConfigLoad, || {
+        load_env();
+        EnvConfig::init()
+    })
+    .map_err(|e| VoiceInputError::ConfigInitError(e.to_string()))?;
+
+    // 期限は設定の読み込み後に決まるため、読み込み自体はここで事後に確かめ、以降の段階は実行中に監視する
+    let stage_deadline = EnvConfig::get()
+        .resources
+        .startup_stage_deadline_ms
+        .map(Duration::from_millis);
+    startup::snapshot()
+        .check_deadline(stage_deadline)
+        .map_err(|e| VoiceInputError::SystemError(e.to_string()))?;
+    startup::set_stage_deadline(stage_deadline);
 
     // `spawn_local` はこのスレッドだけで動かしたい非同期ジョブを登録する。LocalSet はその実行エンジン
     let local = LocalSet::new();
+    let mut args = std::env::args().skip(1);
+    if args.next().as_deref() == Some("--soak") {
+        let cycles = match args.next() {
+            Some(value) => value
+                .parse()
+                .map_err(|_| format!("--soak expects a cycle count: {}", value))?,
+            None => DEFAULT_SOAK_CYCLES,
+        };
+        return local.run_until(soak_main(cycles)).await;
+    }
+
     local
         .run_until(async_main())
         .await
         .map_err(|e| Box::new(e) as Box<dyn Error>)
 }
 
+/// 模擬バックエンドで録音〜入力を反復し、リソースの増加があれば失敗終了する。
+async fn soak_main(cycles: usize) -> std::result::Result<(), Box<dyn Error>> {
+    println!("voice-inputd soak: running {} simulated cycles", cycles);
+    let report = run_soak(cycles).await?;
+    for line in report.format_lines() {
+        println!("{}", line);
+    }
+
+    let violations = report.violations(&SoakLimits::default());
+    if violations.is_empty() {
+        println!("soak: ok");
+        return Ok(());
+    }
+    for violation in &violations {
+        eprintln!("soak: {}", violation);
+    }
+    process::exit(1);
+}
+
 /// ソケット待受・クライアントハンドリング・転写ワーカーを起動する本体。
 async fn async_main() -> Result<()> {
-    // 既存ソケットがあれば削除して再バインド
     let path = socket_path();
-    let _ = fs::remove_file(&path);
-    let listener = UnixListener::bind(&path)
-        .map_err(|e| VoiceInputError::IpcConnectionFailed(e.to_string()))?;
+    let listener = startup::measure(StartupStage::SocketBind, || daemon::bind_socket(&path))?;
     println!("voice-inputd listening on {:?}", path);
 
-    // サービスコンテナを初期化
-    let mut container = ServiceContainer::<CpalAudioBackend>::new()?;
-    let command_handler = container.command_handler.clone();
-    let recording_service = container.recording_service.clone();
-    let transcription_rx = container
-        .take_transcription_rx()
-        .expect("Transcription receiver should be available");
-
-    // 転写ワーカーの起動
-    let max_concurrent_transcriptions = EnvConfig::get().recommended_transcription_parallelism();
-    let semaphore = std::sync::Arc::new(Semaphore::new(max_concurrent_transcriptions));
-    let transcription_service = container.transcription_service.clone();
-
-    text_input::init_worker().map_err(|e| VoiceInputError::SystemError(e.to_string()))?;
-    spawn_runtime_recovery_monitor(recording_service.clone());
-
-    spawn_local(spawn_transcription_worker(
-        semaphore.clone(),
-        transcription_rx,
-        transcription_service,
-        recording_service,
-    ));
-
-    // クライアント接続ループ
-    loop {
-        let (stream, _) = listener
-            .accept()
-            .await
-            .map_err(|e| VoiceInputError::IpcConnectionFailed(e.to_string()))?;
-        let handler = command_handler.clone();
-        spawn_local(async move {
-            let _ = handle_client(stream, handler).await;
-        });
+    // 旧バージョンが /tmp などに残したファイルを片付ける
+    for line in cleanup_legacy_state(&path).format_lines() {
+        println!("{}", line);
     }
-}
 
-fn spawn_runtime_recovery_monitor(
-    recording_service: std::rc::Rc<
-        std::cell::RefCell<voice_input::application::RecordingService<CpalAudioBackend>>,
-    >,
-) {
-    const CHECK_INTERVAL: Duration = Duration::from_secs(15);
-    const WAKE_THRESHOLD: Duration = Duration::from_secs(45);
-
-    spawn_local(async move {
-        let mut detector = SleepWakeDetector::new(SystemTime::now(), WAKE_THRESHOLD);
-        let retry_policy = WakeRecoveryRetryPolicy::after_wake();
-        let mut ticker = tokio::time::interval(CHECK_INTERVAL);
-        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
-
-        loop {
-            ticker.tick().await;
-            if !detector.record_tick(SystemTime::now()) {
-                continue;
-            }
-
-            if recording_service.borrow().is_recording() {
-                eprintln!("Wake detected while recording; deferred runtime recovery.");
-                continue;
-            }
-
-            let mut recovered = false;
-            for attempt in 1..=retry_policy.max_attempts {
-                let audio_result = recording_service.borrow().recover_after_wake();
-                let text_result = text_input::recover_after_wake()
-                    .map_err(|e| VoiceInputError::SystemError(e.to_string()));
-
-                match (audio_result, text_result) {
-                    (Ok(()), Ok(())) => {
-                        recovered = true;
-                        println!("Recovered runtime resources after wake.");
-                        break;
-                    }
-                    (audio_result, text_result) => {
-                        if let Err(err) = audio_result {
-                            eprintln!(
-                                "Wake recovery attempt {} failed for audio backend: {}",
-                                attempt, err
-                            );
-                        }
-                        if let Err(err) = text_result {
-                            eprintln!(
-                                "Wake recovery attempt {} failed for text input worker: {}",
-                                attempt, err
-                            );
-                        }
-                    }
-                }
-
-                tokio::time::sleep(retry_policy.retry_interval).await;
-            }
-
-            if recovered {
-                continue;
-            }
-
-            eprintln!("Wake recovery failed; exiting to let LaunchAgent restart the daemon.");
-            process::exit(75);
+    let resources = EnvConfig::get().resources.clone();
+    if let Some(level) = resources.nice_level {
+        // nice 値の適用失敗は致命的ではないため警告に留める
+        if let Err(err) = apply_nice_level(level) {
+            eprintln!("{}", err);
         }
-    });
-}
+    }
+
+    // サービスコンテナを初期化
+    let mut container = startup::measure(
+        StartupStage::ServiceContainer,
+        ServiceContainer::<CpalAudioBackend>::new,
+    )?;
 
-/// 1 クライアントとの IPC セッションを処理します。
-async fn handle_client(
-    stream: UnixStream,
-    command_handler: std::rc::Rc<std::cell::RefCell<CommandHandler<CpalAudioBackend>>>,
-) -> Result<()> {
-    let (r, w) = stream.into_split();
-    let mut reader = FramedRead::new(r, LinesCodec::new());
-    let mut writer = FramedWrite::new(w, LinesCodec::new());
-
-    if let Some(Ok(line)) = reader.next().await {
-        let cmd: IpcCmd = serde_json::from_str(&line)
-            .map_err(|e| VoiceInputError::IpcSerializationError(e.to_string()))?;
-
-        let resp = command_handler
-            .borrow()
-            .handle(cmd)
-            .await
-            .unwrap_or_else(|e| IpcResp {
-                ok: false,
-                msg: e.to_string(),
-            });
-
-        writer
-            .send(
-                serde_json::to_string(&resp)
-                    .map_err(|e| VoiceInputError::IpcSerializationError(e.to_string()))?,
-            )
-            .await
-            .map_err(|e| VoiceInputError::IpcConnectionFailed(e.to_string()))?;
+    startup::measure(StartupStage::TextInputWorker, text_input::init_worker)
+        .map_err(|e| VoiceInputError::SystemError(e.to_string()))?;
+
+    for line in startup::snapshot().format_lines() {
+        println!("{}", line);
     }
+
+    daemon::spawn_workers(
+        &mut container,
+        &resources,
+        EnvConfig::get().recording.screen_lock_action,
+    )?;
+    daemon::spawn_config_reload_on_hangup(container.command_handler.clone())?;
+
+    // クライアント接続ループ（SIGTERM / SIGINT で抜けて終了処理を行う）
+    daemon::serve(
+        &listener,
+        container.command_handler.clone(),
+        daemon::wait_for_shutdown_signal(),
+    )
+    .await?;
+
+    println!("voice-inputd shutting down");
+    daemon::shutdown(&mut container, &path, SHUTDOWN_DRAIN_TIMEOUT).await;
     Ok(())
 }
 
@@ -1,4 +1,6 @@
+use crate::domain::input::TrailingAction;
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 
 #[derive(Parser)]
 #[command(author, version, about = "Voice Input client (daemon control + dict)")]
@@ -7,6 +9,14 @@ pub struct Cli {
     #[arg(long)]
     pub list_devices: bool,
 
+    /// 成功時の出力を抑え、デーモン側の計測ログも抑える（`--verbose` より優先）
+    #[arg(long, short, global = true)]
+    pub quiet: bool,
+
+    /// デーモン側で処理段階ごとの計測ログを出力する（`status` ではサブシステム別の直近エラーも表示）
+    #[arg(long, short, global = true)]
+    pub verbose: bool,
+
     #[command(subcommand)]
     pub cmd: Option<Cmd>,
 }
@@ -18,18 +28,72 @@ pub enum Cmd {
         /// Whisper へ追加のプロンプト
         #[arg(long)]
         prompt: Option<String>,
+        /// 入力前に転写結果を読み上げる
+        #[arg(long)]
+        readback: bool,
+        /// 転写ログへ残すラベル（例: 文書名）
+        #[arg(long)]
+        label: Option<String>,
+        /// 入力後の動作: none / space / newline（Shift+Return）/ enter（送信）
+        #[arg(long, default_value = "none")]
+        then: TrailingAction,
     },
+    /// 音声コマンドとして録音開始（停止後の転写は入力せず、「単語登録：X を Y に登録」などとして解釈）
+    VoiceCommand,
     /// 録音停止
-    Stop,
+    Stop {
+        /// 転写完了後、辞書適用による変更点を表示
+        #[arg(long)]
+        show_diff: bool,
+    },
+    /// 録音中止（転写せずに破棄）
+    Cancel,
     /// 録音開始 / 停止トグル
     Toggle {
         #[arg(long)]
         prompt: Option<String>,
+        /// 入力前に転写結果を読み上げる
+        #[arg(long)]
+        readback: bool,
+        /// 入力後の動作: none / space / newline（Shift+Return）/ enter（送信）
+        #[arg(long, default_value = "none")]
+        then: TrailingAction,
+    },
+    /// 既存の音声ファイルを転写して入力
+    Transcribe {
+        /// 転写する音声ファイル。`-` で標準入力から WAV / FLAC を読む
+        #[arg(required_unless_present = "from_clipboard")]
+        file: Option<PathBuf>,
+        /// クリップボード上の音声ファイル参照を転写
+        #[arg(long, conflicts_with = "file")]
+        from_clipboard: bool,
+        /// 転写完了後、辞書適用による変更点を表示
+        #[arg(long)]
+        show_diff: bool,
+    },
+    /// 直近の録音を録り直さずに再転写して入力
+    RetryLast {
+        /// 転写完了後、辞書適用による変更点を表示
+        #[arg(long)]
+        show_diff: bool,
     },
     /// デーモン状態取得
     Status,
     /// ヘルスチェック
     Health,
+    /// 直近にデーモンが受け付けた IPC コマンドの記録を表示
+    Audit,
+    /// 環境変数ファイルを読み直し、再起動せずに反映できる設定をデーモンへ適用
+    ReloadConfig,
+    /// 録音開始・エンコード・転写 API・入力の所要時間を計測
+    Bench {
+        /// 転写 API の往復計測を省く
+        #[arg(long)]
+        skip_api: bool,
+        /// 前面アプリへ短い文字列を入力して入力時間も計測する
+        #[arg(long)]
+        paste: bool,
+    },
     /// 🔤 辞書操作
     Dict {
         #[command(subcommand)]
@@ -53,6 +117,8 @@ pub enum DictCmd {
     Remove { surface: String },
     /// 一覧表示
     List,
+    /// 置換回数から効果の低いエントリを洗い出す
+    Analyze,
 }
 
 #[derive(Subcommand)]
@@ -1,10 +1,11 @@
 //! 単語辞書エンティティとリポジトリ抽象 – ドメイン層
 
+use aho_corasick::{AhoCorasick, MatchKind};
 use serde::{Deserialize, Serialize};
 use std::ops::Range;
 
 /// 1 単語エントリ
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct WordEntry {
     pub surface: String,     // 転写文中の語
     pub replacement: String, // 置換後
@@ -46,66 +47,154 @@ pub struct ReplacementOutput {
     pub span_mappings: Vec<ReplacementSpanMapping>,
 }
 
+/// 有効エントリの surface から構築した Aho-Corasick による置換器
+///
+/// 辞書が変わったときだけ作り直し、転写ごとの適用は一回の走査で済ませる。
+#[derive(Debug, Clone)]
+pub struct DictionaryMatcher {
+    automaton: Option<AhoCorasick>,
+    /// パターン番号に対応する (エントリ位置, surface, replacement)
+    patterns: Vec<(usize, String, String)>,
+}
+
+impl DictionaryMatcher {
+    /// 辞書エントリから置換器を構築する。Draft と空の surface は対象外
+    pub fn new(entries: &[WordEntry]) -> Self {
+        let patterns: Vec<(usize, String, String)> = entries
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.status == EntryStatus::Active && !e.surface.is_empty())
+            .map(|(index, e)| (index, e.surface.clone(), e.replacement.clone()))
+            .collect();
+
+        // LeftmostFirst で「先頭から見て、同じ位置では辞書順で先のエントリ」を優先する
+        let automaton = (!patterns.is_empty())
+            .then(|| {
+                AhoCorasick::builder()
+                    .match_kind(MatchKind::LeftmostFirst)
+                    .build(patterns.iter().map(|(_, surface, _)| surface))
+                    .ok()
+            })
+            .flatten();
+
+        Self {
+            automaton,
+            patterns,
+        }
+    }
+
+    /// 構築時と同じ置換内容の辞書か（hit 数の違いは無視する）
+    pub fn is_built_for(&self, entries: &[WordEntry]) -> bool {
+        let mut active = entries
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.status == EntryStatus::Active && !e.surface.is_empty());
+        self.patterns.iter().all(|(index, surface, replacement)| {
+            active.next().is_some_and(|(i, e)| {
+                i == *index && e.surface == *surface && e.replacement == *replacement
+            })
+        }) && active.next().is_none()
+    }
+
+    /// 置換を適用し、置換した回数だけ対応エントリの `hit` を加算する
+    ///
+    /// `entries` は構築時と同じ並びの辞書であること。
+    pub fn apply(&self, text: &str, entries: &mut [WordEntry]) -> ReplacementOutput {
+        let mut out = String::with_capacity(text.len());
+        let mut span_mappings = Vec::with_capacity(text.len());
+        let mut raw_index = 0;
+        let mut processed_index = 0;
+        let mut last_end = 0;
+
+        let Some(automaton) = &self.automaton else {
+            push_unchanged(
+                text,
+                &mut out,
+                &mut span_mappings,
+                &mut raw_index,
+                &mut processed_index,
+            );
+            return ReplacementOutput {
+                text: out,
+                span_mappings,
+            };
+        };
+
+        for found in automaton.find_iter(text) {
+            push_unchanged(
+                &text[last_end..found.start()],
+                &mut out,
+                &mut span_mappings,
+                &mut raw_index,
+                &mut processed_index,
+            );
+
+            let (entry_index, surface, replacement) = &self.patterns[found.pattern().as_usize()];
+            if let Some(entry) = entries.get_mut(*entry_index) {
+                entry.hit += 1;
+            }
+            let surface_len = surface.chars().count();
+            let replacement_len = replacement.chars().count();
+            out.push_str(replacement);
+            span_mappings.push(ReplacementSpanMapping {
+                raw_char_range: raw_index..raw_index + surface_len,
+                processed_char_range: processed_index..processed_index + replacement_len,
+            });
+            raw_index += surface_len;
+            processed_index += replacement_len;
+            last_end = found.end();
+        }
+
+        push_unchanged(
+            &text[last_end..],
+            &mut out,
+            &mut span_mappings,
+            &mut raw_index,
+            &mut processed_index,
+        );
+        ReplacementOutput {
+            text: out,
+            span_mappings,
+        }
+    }
+}
+
+/// 置換されなかった区間を 1 文字ずつの位置対応付きで書き出す
+fn push_unchanged(
+    segment: &str,
+    out: &mut String,
+    span_mappings: &mut Vec<ReplacementSpanMapping>,
+    raw_index: &mut usize,
+    processed_index: &mut usize,
+) {
+    out.push_str(segment);
+    for _ in segment.chars() {
+        span_mappings.push(ReplacementSpanMapping {
+            raw_char_range: *raw_index..*raw_index + 1,
+            processed_char_range: *processed_index..*processed_index + 1,
+        });
+        *raw_index += 1;
+        *processed_index += 1;
+    }
+}
+
 /// 与えられた文字列に辞書を適用して置換を行います。
 ///
 /// `entries` の各 `surface` を `replacement` へ置換し、
 /// 置換が行われた回数だけ `hit` をインクリメントします。
-/// TODO 事前構造化（surface_chars のキャッシュ） や、必要なら Aho-Corasick の導入検討で、辞書サイズ増加時の劣化を防ぐ
 pub fn apply_replacements(text: &str, entries: &mut [WordEntry]) -> String {
     apply_replacements_with_mappings(text, entries).text
 }
 
 /// 与えられた文字列に辞書を適用し、文字位置対応も返します。
+///
+/// 呼び出しごとに置換器を構築するため、繰り返し適用する場合は
+/// [`DictionaryMatcher`] を使い回すこと。
 pub fn apply_replacements_with_mappings(
     text: &str,
     entries: &mut [WordEntry],
 ) -> ReplacementOutput {
-    for e in entries
-        .iter_mut()
-        .filter(|e| e.status == EntryStatus::Active)
-    {
-        let count = text.matches(&e.surface).count();
-        e.hit += count as u32;
-    }
-
-    let mut out = String::new();
-    let mut i = 0;
-    let mut processed_index = 0;
-    let mut span_mappings = Vec::new();
-    let chars: Vec<char> = text.chars().collect();
-    while i < chars.len() {
-        let mut replaced = false;
-        for e in entries.iter().filter(|e| e.status == EntryStatus::Active) {
-            let surface_chars: Vec<char> = e.surface.chars().collect();
-            if i + surface_chars.len() <= chars.len()
-                && chars[i..i + surface_chars.len()] == surface_chars[..]
-            {
-                out.push_str(&e.replacement);
-                let replacement_len = e.replacement.chars().count();
-                span_mappings.push(ReplacementSpanMapping {
-                    raw_char_range: i..i + surface_chars.len(),
-                    processed_char_range: processed_index..processed_index + replacement_len,
-                });
-                i += surface_chars.len();
-                processed_index += replacement_len;
-                replaced = true;
-                break;
-            }
-        }
-        if !replaced {
-            out.push(chars[i]);
-            span_mappings.push(ReplacementSpanMapping {
-                raw_char_range: i..i + 1,
-                processed_char_range: processed_index..processed_index + 1,
-            });
-            i += 1;
-            processed_index += 1;
-        }
-    }
-    ReplacementOutput {
-        text: out,
-        span_mappings,
-    }
+    DictionaryMatcher::new(entries).apply(text, entries)
 }
 
 /// 辞書エントリを追加または置換する。
@@ -127,6 +216,137 @@ pub fn remove_entry(entries: &mut Vec<WordEntry>, surface: &str) -> bool {
     len_before != entries.len()
 }
 
+/// 辞書分析で検出するエントリの問題
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryIssue {
+    /// 有効だが一度も置換されていない
+    Unused,
+    /// surface と replacement が同じで置換しても変化しない
+    NoOp,
+    /// surface が 1 文字で、無関係な語の一部まで置換しやすい
+    SingleChar,
+    /// ドラフトのため置換に使われない
+    Draft,
+}
+
+impl std::fmt::Display for EntryIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EntryIssue::Unused => write!(f, "never fired"),
+            EntryIssue::NoOp => write!(f, "replacement equals surface"),
+            EntryIssue::SingleChar => write!(f, "single-character surface may over-match"),
+            EntryIssue::Draft => write!(f, "draft entry is not applied"),
+        }
+    }
+}
+
+/// 1 エントリの分析結果
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntryAnalysis {
+    pub surface: String,
+    pub replacement: String,
+    pub hit: u32,
+    /// 全エントリの置換回数に占める割合（%）
+    pub hit_share: f64,
+    pub issues: Vec<EntryIssue>,
+}
+
+/// 蓄積された `hit` から辞書エントリの効果を分析する
+///
+/// 置換回数の多い順に並べ、効果がない・誤置換を招きやすいエントリに問題を付ける。
+pub fn analyze_entries(entries: &[WordEntry]) -> Vec<EntryAnalysis> {
+    let total_hits: u64 = entries.iter().map(|entry| u64::from(entry.hit)).sum();
+    let mut analyses: Vec<EntryAnalysis> = entries
+        .iter()
+        .map(|entry| {
+            let mut issues = Vec::new();
+            match entry.status {
+                EntryStatus::Draft => issues.push(EntryIssue::Draft),
+                EntryStatus::Active if entry.hit == 0 => issues.push(EntryIssue::Unused),
+                EntryStatus::Active => {}
+            }
+            if entry.surface == entry.replacement {
+                issues.push(EntryIssue::NoOp);
+            }
+            if entry.surface.chars().count() <= 1 {
+                issues.push(EntryIssue::SingleChar);
+            }
+            EntryAnalysis {
+                surface: entry.surface.clone(),
+                replacement: entry.replacement.clone(),
+                hit: entry.hit,
+                hit_share: if total_hits == 0 {
+                    0.0
+                } else {
+                    f64::from(entry.hit) * 100.0 / total_hits as f64
+                },
+                issues,
+            }
+        })
+        .collect();
+    analyses.sort_by(|a, b| b.hit.cmp(&a.hit).then_with(|| a.surface.cmp(&b.surface)));
+    analyses
+}
+
+/// 英語の単語登録コマンドの接頭辞
+const REGISTER_WORD_PREFIX_EN: &str = "register word";
+/// 日本語の単語登録コマンドの接頭辞
+const REGISTER_WORD_PREFIX_JA: &str = "単語登録";
+/// 接頭辞の直後に必要な区切り
+const REGISTER_WORD_DELIMITERS: [char; 2] = [':', '：'];
+/// 日本語コマンドの置換後に続く言い回し（長いものから照合する）
+const REGISTER_WORD_SUFFIXES_JA: [&str; 3] = ["として登録", "に登録", "で登録"];
+/// 登録する表記の先頭には来ない助詞（「単語登録の手順を…」のような普通の文を除く）
+const LEADING_PARTICLES: [char; 10] = ['の', 'を', 'に', 'が', 'は', 'で', 'と', 'へ', 'も', 'や'];
+
+/// 音声での単語登録コマンドを解釈し、登録するエントリを返す
+///
+/// 「register word: X as Y」または「単語登録：X を Y に登録（として登録 / で登録）」の形式だけを受け付ける。
+/// 普通の文を取り違えないよう、接頭辞の後の区切りと日本語の末尾の「登録」を必須にする。
+pub fn parse_register_word_command(text: &str) -> Option<WordEntry> {
+    let command = text
+        .trim()
+        .trim_end_matches(['。', '.', '!', '！', '、', ',', ' ', '　']);
+
+    let (surface, replacement) = if let Some(rest) = command
+        .get(..REGISTER_WORD_PREFIX_EN.len())
+        .filter(|prefix| prefix.eq_ignore_ascii_case(REGISTER_WORD_PREFIX_EN))
+        .map(|_| &command[REGISTER_WORD_PREFIX_EN.len()..])
+    {
+        let rest = strip_register_word_delimiter(rest)?;
+        let split_at = rest.to_ascii_lowercase().find(" as ")?;
+        (&rest[..split_at], &rest[split_at + " as ".len()..])
+    } else {
+        let rest = command.strip_prefix(REGISTER_WORD_PREFIX_JA)?;
+        let rest = strip_register_word_delimiter(rest)?;
+        let (surface, replacement) = rest.split_once('を')?;
+        let replacement = REGISTER_WORD_SUFFIXES_JA
+            .iter()
+            .find_map(|suffix| replacement.strip_suffix(suffix))?;
+        (surface, replacement)
+    };
+
+    let surface = surface.trim();
+    let replacement = replacement.trim();
+    if surface.is_empty() || replacement.is_empty() || surface.starts_with(LEADING_PARTICLES) {
+        return None;
+    }
+    Some(WordEntry {
+        surface: surface.to_string(),
+        replacement: replacement.to_string(),
+        hit: 0,
+        status: EntryStatus::Active,
+    })
+}
+
+/// 接頭辞に続く区切りを取り除く。区切りがなければ `None`
+fn strip_register_word_delimiter(rest: &str) -> Option<&str> {
+    let rest = rest
+        .trim_start_matches([' ', '　'])
+        .strip_prefix(REGISTER_WORD_DELIMITERS)?;
+    Some(rest.trim_start_matches([' ', '　']))
+}
+
 // === Unit tests ==========================================================
 #[cfg(test)]
 mod tests {
@@ -274,4 +494,137 @@ mod tests {
         assert_eq!(entries[0].surface, "baz");
         assert!(!remove_entry(&mut entries, "missing"));
     }
+
+    fn bench_entries(count: usize) -> Vec<WordEntry> {
+        (0..count)
+            .map(|i| WordEntry {
+                surface: format!("単語{:04}", i),
+                replacement: format!("word{}", i),
+                hit: 0,
+                status: EntryStatus::Active,
+            })
+            .collect()
+    }
+
+    /// 同じ位置で複数のsurfaceが一致する場合は辞書で先のエントリを優先する
+    #[test]
+    fn earlier_entry_wins_when_surfaces_overlap() {
+        let mut entries = vec![
+            WordEntry {
+                surface: "ab".into(),
+                replacement: "X".into(),
+                hit: 0,
+                status: EntryStatus::Active,
+            },
+            WordEntry {
+                surface: "abc".into(),
+                replacement: "Y".into(),
+                hit: 0,
+                status: EntryStatus::Active,
+            },
+        ];
+
+        assert_eq!(apply_replacements("abcd", &mut entries), "Xcd");
+        assert_eq!(entries[0].hit, 1);
+        assert_eq!(entries[1].hit, 0);
+    }
+
+    /// 空のsurfaceは置換対象にならない
+    #[test]
+    fn empty_surface_is_ignored() {
+        let mut entries = vec![WordEntry {
+            surface: String::new(),
+            replacement: "x".into(),
+            hit: 0,
+            status: EntryStatus::Active,
+        }];
+
+        assert_eq!(apply_replacements("abc", &mut entries), "abc");
+        assert_eq!(entries[0].hit, 0);
+    }
+
+    /// hit数の変化では置換器を作り直さず、置換内容の変化では作り直す
+    #[test]
+    fn matcher_is_rebuilt_only_when_replacements_change() {
+        let mut entries = bench_entries(3);
+        let matcher = DictionaryMatcher::new(&entries);
+
+        entries[0].hit = 10;
+        assert!(matcher.is_built_for(&entries));
+
+        entries[1].replacement = "changed".into();
+        assert!(!matcher.is_built_for(&entries));
+
+        entries[1].replacement = "word1".into();
+        entries[2].status = EntryStatus::Draft;
+        assert!(!matcher.is_built_for(&entries));
+    }
+
+    /// 英語・日本語の単語登録コマンドから登録エントリを取り出す
+    #[test]
+    fn register_word_commands_are_parsed_in_both_languages() {
+        let english = parse_register_word_command("Register word: cloud code as Claude Code.")
+            .expect("english command should parse");
+        assert_eq!(english.surface, "cloud code");
+        assert_eq!(english.replacement, "Claude Code");
+
+        let japanese = parse_register_word_command("単語登録：くろーどをClaudeとして登録。")
+            .expect("japanese command should parse");
+        assert_eq!(japanese.surface, "くろーど");
+        assert_eq!(japanese.replacement, "Claude");
+        assert_eq!(japanese.status, EntryStatus::Active);
+    }
+
+    /// コマンド形式でない発話や片側が空の指定は登録しない
+    #[test]
+    fn non_command_text_is_not_registered() {
+        assert_eq!(
+            parse_register_word_command("今日は単語登録をしました"),
+            None
+        );
+        assert_eq!(
+            parse_register_word_command("register word: as Claude"),
+            None
+        );
+        assert_eq!(parse_register_word_command("単語登録 くろーど"), None);
+        assert_eq!(parse_register_word_command("単語登録の手順を教えて"), None);
+        assert_eq!(
+            parse_register_word_command("単語登録：の手順をまとめに登録"),
+            None
+        );
+        assert_eq!(
+            parse_register_word_command("単語登録 くろーど を Claude に登録"),
+            None
+        );
+    }
+
+    /// 置換回数の多い順に並び、効果のないエントリに問題が付く
+    #[test]
+    fn analyze_entries_ranks_by_hits_and_flags_issues() {
+        let entry = |surface: &str, replacement: &str, hit: u32, status: EntryStatus| WordEntry {
+            surface: surface.into(),
+            replacement: replacement.into(),
+            hit,
+            status,
+        };
+        let analyses = analyze_entries(&[
+            entry("くろーど", "Claude", 1, EntryStatus::Active),
+            entry("rust", "Rust", 3, EntryStatus::Active),
+            entry("Git", "Git", 0, EntryStatus::Active),
+            entry("ぬ", "nu", 0, EntryStatus::Draft),
+        ]);
+
+        let surfaces: Vec<&str> = analyses.iter().map(|a| a.surface.as_str()).collect();
+        assert_eq!(surfaces, vec!["rust", "くろーど", "Git", "ぬ"]);
+        assert_eq!(analyses[0].hit_share, 75.0);
+        assert!(analyses[0].issues.is_empty());
+        assert_eq!(
+            analyses[2].issues,
+            vec![EntryIssue::Unused, EntryIssue::NoOp]
+        );
+        assert_eq!(
+            analyses[3].issues,
+            vec![EntryIssue::Draft, EntryIssue::SingleChar]
+        );
+    }
 }
@@ -0,0 +1,72 @@
+//! テキスト入力後の動作 – ドメイン層
+
+use serde::{Deserialize, Serialize};
+
+/// 転写結果の入力後に続けて行う動作
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TrailingAction {
+    /// 何もしない
+    #[default]
+    None,
+    /// 末尾に空白を 1 つ入力する
+    Space,
+    /// 送信せずに改行する（Shift+Return）
+    Newline,
+    /// Return を押す（チャットアプリでは即送信）
+    Enter,
+}
+
+impl TrailingAction {
+    /// 入力後に何か動作を行うか
+    pub fn is_none(&self) -> bool {
+        matches!(self, TrailingAction::None)
+    }
+}
+
+impl std::fmt::Display for TrailingAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TrailingAction::None => write!(f, "none"),
+            TrailingAction::Space => write!(f, "space"),
+            TrailingAction::Newline => write!(f, "newline"),
+            TrailingAction::Enter => write!(f, "enter"),
+        }
+    }
+}
+
+impl std::str::FromStr for TrailingAction {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "none" => Ok(TrailingAction::None),
+            "space" => Ok(TrailingAction::Space),
+            "newline" => Ok(TrailingAction::Newline),
+            "enter" => Ok(TrailingAction::Enter),
+            other => Err(format!(
+                "unknown trailing action '{}': expected none, space, newline or enter",
+                other
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TrailingAction;
+
+    /// CLI 引数の文字列と表示名が往復できる
+    #[test]
+    fn trailing_action_parses_its_display_name() {
+        for action in [
+            TrailingAction::None,
+            TrailingAction::Space,
+            TrailingAction::Newline,
+            TrailingAction::Enter,
+        ] {
+            assert_eq!(action.to_string().parse::<TrailingAction>(), Ok(action));
+        }
+        assert!("tab".parse::<TrailingAction>().is_err());
+    }
+}
@@ -1,2 +1,4 @@
 pub mod dict;
+pub mod input;
 pub mod transcription;
+pub mod voice_macro;
@@ -1,4 +1,4 @@
-use crate::domain::dict::ReplacementSpanMapping;
+use crate::domain::dict::{ReplacementOutput, ReplacementSpanMapping, WordEntry};
 use serde::{Deserialize, Serialize};
 
 /// 転写トークン単位の信頼度情報
@@ -58,6 +58,9 @@ pub struct FinalizedTranscription {
     pub text: String,
     /// 低信頼語の選択計画
     pub low_confidence_selection: Option<LowConfidenceSelection>,
+    /// 単語登録コマンドとして辞書へ登録したエントリ（この場合は入力しない）
+    #[serde(default)]
+    pub registered_entry: Option<WordEntry>,
 }
 
 /// 辞書変換後テキストに対する低信頼語の選択範囲を組み立てる
@@ -138,6 +141,64 @@ pub fn plan_low_confidence_selection(
     })
 }
 
+/// 後処理で置き換わった 1 箇所
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TranscriptChange {
+    pub raw: String,
+    pub processed: String,
+}
+
+/// 生の転写結果と後処理後テキストの差分
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TranscriptDiff {
+    pub raw_text: String,
+    pub processed_text: String,
+    pub changes: Vec<TranscriptChange>,
+}
+
+impl TranscriptDiff {
+    /// 辞書適用結果の文字位置対応から差分を組み立てる
+    pub fn from_replacement(raw_text: &str, processed: &ReplacementOutput) -> Self {
+        let raw_chars: Vec<char> = raw_text.chars().collect();
+        let processed_chars: Vec<char> = processed.text.chars().collect();
+        let changes = processed
+            .span_mappings
+            .iter()
+            .filter_map(|mapping| {
+                let raw = raw_chars.get(mapping.raw_char_range.clone())?;
+                let replaced = processed_chars.get(mapping.processed_char_range.clone())?;
+                (raw != replaced).then(|| TranscriptChange {
+                    raw: raw.iter().collect(),
+                    processed: replaced.iter().collect(),
+                })
+            })
+            .collect();
+
+        Self {
+            raw_text: raw_text.to_string(),
+            processed_text: processed.text.clone(),
+            changes,
+        }
+    }
+
+    /// CLI 表示用の行に整形する
+    pub fn format_lines(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!("- {}", self.raw_text),
+            format!("+ {}", self.processed_text),
+        ];
+        if self.changes.is_empty() {
+            lines.push("(no changes)".to_string());
+        }
+        lines.extend(
+            self.changes
+                .iter()
+                .map(|change| format!("  {:?} → {:?}", change.raw, change.processed)),
+        );
+        lines
+    }
+}
+
 fn map_raw_range_to_processed(
     raw_start: usize,
     raw_end: usize,
@@ -263,4 +324,45 @@ mod tests {
 
         assert_eq!(selection, None);
     }
+
+    /// 辞書で置き換わった箇所だけが差分として列挙される
+    #[test]
+    fn transcript_diff_lists_replaced_spans() {
+        let mut entries = vec![WordEntry {
+            surface: "くろーど".to_string(),
+            replacement: "Claude".to_string(),
+            hit: 0,
+            status: EntryStatus::Active,
+        }];
+        let processed = apply_replacements_with_mappings("くろーどに聞く", &mut entries);
+
+        let diff = TranscriptDiff::from_replacement("くろーどに聞く", &processed);
+
+        assert_eq!(
+            diff.changes,
+            vec![TranscriptChange {
+                raw: "くろーど".to_string(),
+                processed: "Claude".to_string(),
+            }]
+        );
+        assert_eq!(
+            diff.format_lines(),
+            vec![
+                "- くろーどに聞く",
+                "+ Claudeに聞く",
+                "  \"くろーど\" → \"Claude\""
+            ]
+        );
+    }
+
+    /// 置換がなければ変更なしと表示される
+    #[test]
+    fn transcript_diff_without_replacements_reports_no_changes() {
+        let processed = apply_replacements_with_mappings("そのまま", &mut []);
+
+        let diff = TranscriptDiff::from_replacement("そのまま", &processed);
+
+        assert!(diff.changes.is_empty());
+        assert_eq!(diff.format_lines().last().unwrap(), "(no changes)");
+    }
 }
@@ -0,0 +1,468 @@
+//! 音声マクロ – ドメイン層
+//!
+//! 「日時を挿入」「insert timestamp」のような決まった言い回しを、
+//! 転写後のテキスト上で現在日時などへ展開する。
+//! 「camel case hello world」のように続く英単語を識別子の書式へ変換するマクロも扱う。
+
+use chrono::NaiveDateTime;
+use std::fmt::Write;
+
+use crate::domain::dict::{ReplacementOutput, ReplacementSpanMapping};
+
+/// 展開後の書式を決めるロケール
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MacroLocale {
+    Japanese,
+    English,
+}
+
+/// 挿入する内容
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StampKind {
+    DateTime,
+    Date,
+    Time,
+}
+
+impl StampKind {
+    /// ロケールごとの既定の書式
+    fn default_format(self, locale: MacroLocale) -> &'static str {
+        match (locale, self) {
+            (MacroLocale::Japanese, StampKind::DateTime) => "%Y年%-m月%-d日 %H:%M",
+            (MacroLocale::Japanese, StampKind::Date) => "%Y年%-m月%-d日",
+            (MacroLocale::Japanese, StampKind::Time) => "%H:%M",
+            (MacroLocale::English, StampKind::DateTime) => "%B %-d, %Y %-I:%M %p",
+            (MacroLocale::English, StampKind::Date) => "%B %-d, %Y",
+            (MacroLocale::English, StampKind::Time) => "%-I:%M %p",
+        }
+    }
+}
+
+/// 日時挿入マクロの言い回し。英語は大文字小文字を区別せず単語単位で照合する
+const STAMP_MACROS: [(&str, MacroLocale, StampKind); 6] = [
+    ("日時を挿入", MacroLocale::Japanese, StampKind::DateTime),
+    ("日付を挿入", MacroLocale::Japanese, StampKind::Date),
+    ("時刻を挿入", MacroLocale::Japanese, StampKind::Time),
+    (
+        "insert timestamp",
+        MacroLocale::English,
+        StampKind::DateTime,
+    ),
+    ("insert date", MacroLocale::English, StampKind::Date),
+    ("insert time", MacroLocale::English, StampKind::Time),
+];
+
+/// 日時の書式指定
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StampFormats<'a> {
+    /// 日時マクロの書式（strftime 形式）。未指定ならロケールの既定
+    pub datetime: Option<&'a str>,
+}
+
+impl StampFormats<'_> {
+    fn render(&self, kind: StampKind, locale: MacroLocale, now: NaiveDateTime) -> String {
+        let custom = match kind {
+            StampKind::DateTime => self.datetime,
+            StampKind::Date | StampKind::Time => None,
+        };
+        if let Some(format) = custom {
+            let mut rendered = String::new();
+            if write!(rendered, "{}", now.format(format)).is_ok() {
+                return rendered;
+            }
+        }
+        // 既定の書式は常に有効
+        now.format(kind.default_format(locale)).to_string()
+    }
+}
+
+/// 辞書適用後のテキストで日時挿入マクロを展開する
+///
+/// 文字位置対応も展開後のテキストに合わせて更新するため、差分表示や
+/// 低信頼語の選択は展開前の言い回しを置換元として扱う。
+pub fn expand_stamp_macros(
+    output: ReplacementOutput,
+    now: NaiveDateTime,
+    formats: StampFormats<'_>,
+) -> ReplacementOutput {
+    let chars: Vec<char> = output.text.chars().collect();
+    let expansions = find_phrases(&chars, &STAMP_MACROS)
+        .into_iter()
+        .map(|(start, end, locale, kind)| (start, end, formats.render(kind, locale, now)))
+        .collect();
+    splice_expansions(output, &chars, expansions)
+}
+
+/// 識別子の書式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdentifierCase {
+    /// `helloWorld`
+    Camel,
+    /// `hello_world`
+    Snake,
+}
+
+impl IdentifierCase {
+    /// 単語列を識別子へ変換する。単語はすべて小文字にそろえる
+    fn join(self, words: &[String]) -> String {
+        let lower = words.iter().map(|word| word.to_ascii_lowercase());
+        match self {
+            IdentifierCase::Snake => lower.collect::<Vec<_>>().join("_"),
+            IdentifierCase::Camel => lower
+                .enumerate()
+                .map(|(index, word)| {
+                    if index == 0 {
+                        return word;
+                    }
+                    let mut chars = word.chars();
+                    chars
+                        .next()
+                        .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                        .unwrap_or_default()
+                })
+                .collect(),
+        }
+    }
+}
+
+/// 識別子マクロの言い回し。英語は大文字小文字を区別せず単語単位で照合する
+const CASE_MACROS: [(&str, MacroLocale, IdentifierCase); 4] = [
+    (
+        "キャメルケース",
+        MacroLocale::Japanese,
+        IdentifierCase::Camel,
+    ),
+    (
+        "スネークケース",
+        MacroLocale::Japanese,
+        IdentifierCase::Snake,
+    ),
+    ("camel case", MacroLocale::English, IdentifierCase::Camel),
+    ("snake case", MacroLocale::English, IdentifierCase::Snake),
+];
+
+/// 識別子マクロの終わりを明示する言い回し（展開時に取り除く）
+const CASE_MACRO_END: &str = "end case";
+
+/// 識別子マクロが一つの識別子にまとめる最大の単語数
+const MAX_IDENTIFIER_WORDS: usize = 4;
+
+/// 辞書適用後のテキストで識別子マクロを展開する
+///
+/// 言い回しの後に続く英単語（空白・ハイフン区切り）を、「end case」・句読点など英数字
+/// 以外の文字・`MAX_IDENTIFIER_WORDS` 語目のいずれかまで一つの識別子にまとめる。
+/// 単語が続かない言い回しはそのまま残す。
+pub fn expand_case_macros(output: ReplacementOutput) -> ReplacementOutput {
+    let chars: Vec<char> = output.text.chars().collect();
+    let expansions = find_phrases(&chars, &CASE_MACROS)
+        .into_iter()
+        .filter_map(|(start, phrase_end, _, case)| {
+            let (end, words) = identifier_words(&chars, phrase_end)?;
+            Some((start, end, case.join(&words)))
+        })
+        .collect();
+    splice_expansions(output, &chars, expansions)
+}
+
+/// `from` 以降の英単語を集め、最後の単語の終了位置と単語列を返す
+///
+/// 言い回し直後の区切り（空白と読点・コロン一つ）は読み飛ばす。
+/// 終わりの言い回しで止まった場合は、その言い回しまでを終了位置に含める。
+fn identifier_words(chars: &[char], from: usize) -> Option<(usize, Vec<String>)> {
+    let mut index = from;
+    while chars.get(index).is_some_and(|c| c.is_whitespace()) {
+        index += 1;
+    }
+    if chars
+        .get(index)
+        .is_some_and(|c| matches!(c, ',' | '、' | ':' | '：'))
+    {
+        index += 1;
+    }
+
+    let mut words = Vec::new();
+    let mut end = index;
+    while words.len() < MAX_IDENTIFIER_WORDS {
+        while chars
+            .get(index)
+            .is_some_and(|c| c.is_whitespace() || *c == '-')
+        {
+            index += 1;
+        }
+        if !words.is_empty() && matches_english_phrase(chars, index, CASE_MACRO_END) {
+            end = index + CASE_MACRO_END.chars().count();
+            break;
+        }
+        let word_start = index;
+        while chars.get(index).is_some_and(|c| c.is_ascii_alphanumeric()) {
+            index += 1;
+        }
+        if index == word_start {
+            break;
+        }
+        words.push(chars[word_start..index].iter().collect());
+        end = index;
+    }
+    (!words.is_empty()).then_some((end, words))
+}
+
+/// 展開結果（文字単位の開始・終了と置換後の文字列）をテキストへ反映する
+///
+/// 展開箇所に掛かる位置対応は一つにまとめ、以降の対応は長さの差だけずらす。
+fn splice_expansions(
+    output: ReplacementOutput,
+    chars: &[char],
+    expansions: Vec<(usize, usize, String)>,
+) -> ReplacementOutput {
+    if expansions.is_empty() {
+        return output;
+    }
+
+    let mut text = String::with_capacity(output.text.len());
+    let mut span_mappings = Vec::with_capacity(output.span_mappings.len());
+    let mut mappings = output.span_mappings.into_iter().peekable();
+    let mut cursor = 0;
+    let mut shift: isize = 0;
+    let shifted = |index: usize, shift: isize| index.saturating_add_signed(shift);
+
+    for (start, end, expanded) in expansions {
+        if start < cursor {
+            continue;
+        }
+        // マクロより前の対応はずらすだけ
+        while let Some(mapping) = mappings.next_if(|m| m.processed_char_range.end <= start) {
+            span_mappings.push(ReplacementSpanMapping {
+                raw_char_range: mapping.raw_char_range,
+                processed_char_range: shifted(mapping.processed_char_range.start, shift)
+                    ..shifted(mapping.processed_char_range.end, shift),
+            });
+        }
+
+        // マクロに掛かる対応は一つにまとめる（辞書置換の結果と重なる場合も含む）
+        let mut span_start = start;
+        let mut span_end = end;
+        let mut raw_range: Option<std::ops::Range<usize>> = None;
+        while let Some(mapping) = mappings.next_if(|m| m.processed_char_range.start < span_end) {
+            span_start = span_start.min(mapping.processed_char_range.start);
+            span_end = span_end.max(mapping.processed_char_range.end);
+            raw_range = Some(match raw_range {
+                Some(range) => range.start..mapping.raw_char_range.end,
+                None => mapping.raw_char_range,
+            });
+        }
+
+        text.extend(&chars[cursor..span_start]);
+        let mut replacement: String = chars[span_start..start].iter().collect();
+        replacement.push_str(&expanded);
+        replacement.extend(&chars[end..span_end]);
+        let replacement_len = replacement.chars().count();
+        text.push_str(&replacement);
+
+        if let Some(raw_char_range) = raw_range {
+            span_mappings.push(ReplacementSpanMapping {
+                raw_char_range,
+                processed_char_range: shifted(span_start, shift)
+                    ..shifted(span_start, shift) + replacement_len,
+            });
+        }
+        shift += replacement_len as isize - (span_end - span_start) as isize;
+        cursor = span_end;
+    }
+
+    text.extend(&chars[cursor..]);
+    span_mappings.extend(mappings.map(|mapping| ReplacementSpanMapping {
+        raw_char_range: mapping.raw_char_range,
+        processed_char_range: shifted(mapping.processed_char_range.start, shift)
+            ..shifted(mapping.processed_char_range.end, shift),
+    }));
+
+    ReplacementOutput {
+        text,
+        span_mappings,
+    }
+}
+
+/// `index` から英語の言い回しが単語単位で始まるか（大文字小文字は区別しない）
+fn matches_english_phrase(chars: &[char], index: usize, phrase: &str) -> bool {
+    let len = phrase.chars().count();
+    chars.get(index..index + len).is_some_and(|candidate| {
+        candidate
+            .iter()
+            .zip(phrase.chars())
+            .all(|(c, p)| c.eq_ignore_ascii_case(&p))
+    }) && !index
+        .checked_sub(1)
+        .is_some_and(|before| chars[before].is_alphanumeric())
+        && !chars
+            .get(index + len)
+            .is_some_and(|after| after.is_alphanumeric())
+}
+
+/// 言い回しの出現位置（文字単位の開始・終了）を先頭から重ならないように返す
+fn find_phrases<T: Copy>(
+    chars: &[char],
+    phrases: &[(&str, MacroLocale, T)],
+) -> Vec<(usize, usize, MacroLocale, T)> {
+    let mut found = Vec::new();
+    let mut index = 0;
+    while index < chars.len() {
+        let matched = phrases.iter().find_map(|(phrase, locale, kind)| {
+            let len = phrase.chars().count();
+            let candidate = chars.get(index..index + len)?;
+            let is_match = match locale {
+                MacroLocale::Japanese => candidate.iter().copied().eq(phrase.chars()),
+                MacroLocale::English => matches_english_phrase(chars, index, phrase),
+            };
+            is_match.then_some((index, index + len, *locale, *kind))
+        });
+
+        match matched {
+            Some(found_macro) => {
+                index = found_macro.1;
+                found.push(found_macro);
+            }
+            None => index += 1,
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::dict::{EntryStatus, WordEntry, apply_replacements_with_mappings};
+    use chrono::NaiveDate;
+
+    fn now() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 3, 5)
+            .unwrap()
+            .and_hms_opt(14, 7, 0)
+            .unwrap()
+    }
+
+    fn expand(text: &str, formats: StampFormats<'_>) -> ReplacementOutput {
+        let output = apply_replacements_with_mappings(text, &mut []);
+        expand_stamp_macros(output, now(), formats)
+    }
+
+    /// 日本語の言い回しは日本語の書式で展開する
+    #[test]
+    fn japanese_macros_expand_with_japanese_format() {
+        let defaults = StampFormats::default();
+
+        assert_eq!(
+            expand("会議メモ 日時を挿入。", defaults).text,
+            "会議メモ 2026年3月5日 14:07。"
+        );
+        assert_eq!(expand("日付を挿入", defaults).text, "2026年3月5日");
+        assert_eq!(expand("時刻を挿入", defaults).text, "14:07");
+    }
+
+    /// 英語の言い回しは大文字小文字を問わず、単語単位で英語の書式に展開する
+    #[test]
+    fn english_macros_expand_with_english_format() {
+        let defaults = StampFormats::default();
+
+        assert_eq!(
+            expand("Insert timestamp.", defaults).text,
+            "March 5, 2026 2:07 PM."
+        );
+        assert_eq!(
+            expand("due insert date", defaults).text,
+            "due March 5, 2026"
+        );
+        assert_eq!(expand("insert time", defaults).text, "2:07 PM");
+        assert_eq!(expand("reinsert dates", defaults).text, "reinsert dates");
+    }
+
+    /// 日時の書式は設定で上書きでき、不正な書式ならロケールの既定に戻す
+    #[test]
+    fn custom_datetime_format_overrides_locale_default() {
+        let custom = StampFormats {
+            datetime: Some("%Y-%m-%dT%H:%M"),
+        };
+        assert_eq!(expand("日時を挿入", custom).text, "2026-03-05T14:07");
+        assert_eq!(expand("日付を挿入", custom).text, "2026年3月5日");
+
+        let invalid = StampFormats {
+            datetime: Some("%Q"),
+        };
+        assert_eq!(
+            expand("insert timestamp", invalid).text,
+            "March 5, 2026 2:07 PM"
+        );
+    }
+
+    /// 続く英単語を句読点の手前までキャメルケース・スネークケースにまとめる
+    #[test]
+    fn case_macros_join_following_words() {
+        let expand_case =
+            |text: &str| expand_case_macros(apply_replacements_with_mappings(text, &mut [])).text;
+
+        assert_eq!(expand_case("camel case hello world"), "helloWorld");
+        assert_eq!(expand_case("Snake case, Hello World."), "hello_world.");
+        assert_eq!(
+            expand_case("変数は キャメルケース user id です"),
+            "変数は userId です"
+        );
+        assert_eq!(
+            expand_case("スネークケース max-retry count"),
+            "max_retry_count"
+        );
+        assert_eq!(expand_case("camel case。"), "camel case。");
+        assert_eq!(expand_case("camel cases are fun"), "camel cases are fun");
+    }
+
+    /// 「end case」か最大単語数で識別子を区切り、続く英文は残す
+    #[test]
+    fn case_macros_stop_at_end_phrase_or_word_limit() {
+        let expand_case =
+            |text: &str| expand_case_macros(apply_replacements_with_mappings(text, &mut [])).text;
+
+        assert_eq!(
+            expand_case("camel case user id End Case is required"),
+            "userId is required"
+        );
+        assert_eq!(
+            expand_case("snake case max retry count for uploads please"),
+            "max_retry_count_for uploads please"
+        );
+        assert_eq!(expand_case("camel case end case"), "endCase");
+    }
+
+    /// 展開箇所は言い回し全体の置換として位置対応に残る
+    #[test]
+    fn span_mappings_follow_expanded_text() {
+        let mut entries = vec![WordEntry {
+            surface: "めも".into(),
+            replacement: "メモ".into(),
+            hit: 0,
+            status: EntryStatus::Active,
+        }];
+        let output = apply_replacements_with_mappings("めも時刻を挿入です", &mut entries);
+        let expanded = expand_stamp_macros(output, now(), StampFormats::default());
+
+        assert_eq!(expanded.text, "メモ14:07です");
+        assert_eq!(
+            expanded.span_mappings,
+            vec![
+                ReplacementSpanMapping {
+                    raw_char_range: 0..2,
+                    processed_char_range: 0..2,
+                },
+                ReplacementSpanMapping {
+                    raw_char_range: 2..7,
+                    processed_char_range: 2..7,
+                },
+                ReplacementSpanMapping {
+                    raw_char_range: 7..8,
+                    processed_char_range: 7..8,
+                },
+                ReplacementSpanMapping {
+                    raw_char_range: 8..9,
+                    processed_char_range: 8..9,
+                },
+            ]
+        );
+    }
+}
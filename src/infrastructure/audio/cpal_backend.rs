@@ -5,7 +5,8 @@ use crate::utils::config::EnvConfig;
 use crate::utils::profiling;
 use audioadapter_buffers::SizeError;
 use cpal::{
-    Device, DeviceDescription, SampleFormat, Stream, StreamConfig,
+    Device, DeviceDescription, InputCallbackInfo, SampleFormat, Stream, StreamConfig,
+    StreamInstant,
     traits::{DeviceTrait, HostTrait, StreamTrait},
 };
 use rubato::{
@@ -29,6 +30,8 @@ struct MemoryRecordingState {
     channels: u16,
     generation: u64,
     accepting_input: Arc<AtomicBool>,
+    /// callback のキャプチャ時刻から実測サンプルレートを追跡する
+    rate_monitor: Arc<Mutex<SampleRateMonitor>>,
 }
 
 struct ProcessedAudio<'a> {
@@ -55,7 +58,12 @@ struct ReadyInputStream {
     identity: StreamIdentity,
 }
 
-type CaptureTarget = (Arc<Mutex<Vec<i16>>>, Arc<AtomicBool>, u64);
+type CaptureTarget = (
+    Arc<Mutex<Vec<i16>>>,
+    Arc<AtomicBool>,
+    u64,
+    Arc<Mutex<SampleRateMonitor>>,
+);
 
 const TARGET_SAMPLE_RATE: u32 = 16_000;
 const MIN_RESAMPLE_FRAMES: usize = 256;
@@ -63,6 +71,18 @@ const INPUT_SETUP_REVALIDATION_INTERVAL: Duration = Duration::from_secs(2);
 const INPUT_READINESS_TIMEOUT: Duration = Duration::from_millis(80);
 const INPUT_READINESS_POLL_INTERVAL: Duration = Duration::from_millis(10);
 const MIN_CAPTURE_DURATION: Duration = Duration::from_millis(100);
+/// 実測サンプルレートを 1 回判定するのに使うストリーム時刻の長さ
+const RATE_CHECK_WINDOW: Duration = Duration::from_millis(500);
+/// 不一致とみなすまでに続けて外れる必要がある判定窓の数
+const RATE_MISMATCH_WINDOWS: u32 = 3;
+/// これより長く callback の間隔が空いた判定窓は捨てる（スリープ復帰・デバイスの一時停止）
+const RATE_CHECK_MAX_CALLBACK_GAP: Duration = Duration::from_millis(250);
+/// 設定値と実測値のずれを不一致とみなす割合
+const SAMPLE_RATE_MISMATCH_TOLERANCE: f64 = 0.2;
+/// 実測値を丸める先の標準サンプルレート
+const STANDARD_SAMPLE_RATES: [u32; 9] = [
+    8_000, 11_025, 16_000, 22_050, 24_000, 32_000, 44_100, 48_000, 96_000,
+];
 
 /// Audio processing errors
 #[derive(Debug, thiserror::Error)]
@@ -134,6 +154,8 @@ pub struct CpalAudioBackend {
     recording_state: Arc<Mutex<Option<MemoryRecordingState>>>,
     /// 入力デバイスと設定のキャッシュ
     input_setup_cache: InputSetupCache<CachedInputSetup>,
+    /// 直近の録音から推定した SNR（dB）
+    last_snr_db: Mutex<Option<f32>>,
 }
 
 impl Default for CpalAudioBackend {
@@ -145,6 +167,7 @@ impl Default for CpalAudioBackend {
             stream_needs_rebuild: Arc::new(AtomicBool::new(false)),
             recording_state: Arc::new(Mutex::new(None)),
             input_setup_cache: InputSetupCache::new(),
+            last_snr_db: Mutex::new(None),
         }
     }
 }
@@ -310,6 +333,102 @@ fn has_minimum_capture(samples_len: usize, sample_rate: u32, channels: u16) -> b
     samples_len >= min_capture_samples(sample_rate, channels, MIN_CAPTURE_DURATION)
 }
 
+/// callback のキャプチャ時刻から実際のサンプルレートを推定し、
+/// ストリーム設定と食い違い続ける場合は最も近い標準レートを報告する。
+///
+/// アグリゲートデバイスや一部の USB インターフェースは録音途中でレートを切り替えるため、
+/// 設定値のままヘッダを書くと早回し・遅回しの音声になる。
+/// 壁時計ではなくデバイス側のクロックに基づく `StreamInstant` で測るので、
+/// callback の遅延や Bluetooth の立ち上がりで届くフレームの偏りはずれとして現れない。
+/// callback の間隔が空いた窓は捨て、同じレートへのずれが複数の窓で続いたときだけ不一致とみなす。
+#[derive(Debug)]
+struct SampleRateMonitor {
+    configured_rate: u32,
+    channels: u16,
+    window_start: Option<StreamInstant>,
+    last_capture: Option<StreamInstant>,
+    window_frames: usize,
+    pending_rate: Option<u32>,
+    consecutive_mismatches: u32,
+    detected_rate: Option<u32>,
+}
+
+impl SampleRateMonitor {
+    fn new(configured_rate: u32, channels: u16) -> Self {
+        Self {
+            configured_rate,
+            channels: channels.max(1),
+            window_start: None,
+            last_capture: None,
+            window_frames: 0,
+            pending_rate: None,
+            consecutive_mismatches: 0,
+            detected_rate: None,
+        }
+    }
+
+    /// callback 1 回分（キャプチャ時刻とサンプル数）を記録する
+    fn observe(&mut self, capture: StreamInstant, samples_len: usize) {
+        let gap = self.last_capture.map(|last| capture.duration_since(&last));
+        self.last_capture = Some(capture);
+        match (self.window_start, gap) {
+            (Some(start), Some(Some(gap))) if gap <= RATE_CHECK_MAX_CALLBACK_GAP => {
+                let elapsed = capture.duration_since(&start).unwrap_or_default();
+                if elapsed >= RATE_CHECK_WINDOW {
+                    let observed = self.window_frames as f64 / elapsed.as_secs_f64();
+                    self.record_window(observed);
+                    self.window_start = Some(capture);
+                    self.window_frames = 0;
+                }
+            }
+            // 最初の callback、または時刻が飛んだ窓は判定に使わない
+            _ => {
+                self.window_start = Some(capture);
+                self.window_frames = 0;
+            }
+        }
+        self.window_frames += samples_len / self.channels as usize;
+    }
+
+    fn record_window(&mut self, observed: f64) {
+        let configured = self.configured_rate as f64;
+        if configured == 0.0
+            || (observed - configured).abs() / configured <= SAMPLE_RATE_MISMATCH_TOLERANCE
+        {
+            self.pending_rate = None;
+            self.consecutive_mismatches = 0;
+            return;
+        }
+        let snapped = snap_to_standard_rate(observed);
+        if self.pending_rate == Some(snapped) {
+            self.consecutive_mismatches += 1;
+        } else {
+            self.pending_rate = Some(snapped);
+            self.consecutive_mismatches = 1;
+        }
+        if self.consecutive_mismatches >= RATE_MISMATCH_WINDOWS && snapped != self.configured_rate {
+            self.detected_rate = Some(snapped);
+        }
+    }
+
+    /// 設定値と食い違い続けた実測レート
+    fn detected_rate(&self) -> Option<u32> {
+        self.detected_rate
+    }
+}
+
+fn snap_to_standard_rate(observed: f64) -> u32 {
+    STANDARD_SAMPLE_RATES
+        .iter()
+        .copied()
+        .min_by(|a, b| {
+            (*a as f64 - observed)
+                .abs()
+                .total_cmp(&(*b as f64 - observed).abs())
+        })
+        .unwrap_or(TARGET_SAMPLE_RATE)
+}
+
 fn try_capture_buffer(
     recording: &AtomicBool,
     capture_generation: &AtomicU64,
@@ -319,13 +438,14 @@ fn try_capture_buffer(
         return None;
     }
 
-    let (buffer, accepting_input, generation) = {
+    let (buffer, accepting_input, generation, rate_monitor) = {
         let state = recording_state.lock().unwrap();
         let state = state.as_ref()?;
         (
             state.buffer.clone(),
             state.accepting_input.clone(),
             state.generation,
+            state.rate_monitor.clone(),
         )
     };
 
@@ -335,7 +455,7 @@ fn try_capture_buffer(
         return None;
     }
 
-    Some((buffer, accepting_input, generation))
+    Some((buffer, accepting_input, generation, rate_monitor))
 }
 
 fn append_input_i16(
@@ -343,8 +463,9 @@ fn append_input_i16(
     capture_generation: &AtomicU64,
     recording_state: &Arc<Mutex<Option<MemoryRecordingState>>>,
     data: &[i16],
+    capture: StreamInstant,
 ) {
-    let Some((buffer, accepting_input, generation)) =
+    let Some((buffer, accepting_input, generation, rate_monitor)) =
         try_capture_buffer(recording, capture_generation, recording_state)
     else {
         return;
@@ -355,6 +476,8 @@ fn append_input_i16(
         && generation == capture_generation.load(Ordering::SeqCst)
     {
         buf.extend_from_slice(data);
+        drop(buf);
+        rate_monitor.lock().unwrap().observe(capture, data.len());
     }
 }
 
@@ -363,8 +486,9 @@ fn append_input_f32(
     capture_generation: &AtomicU64,
     recording_state: &Arc<Mutex<Option<MemoryRecordingState>>>,
     data: &[f32],
+    capture: StreamInstant,
 ) {
-    let Some((buffer, accepting_input, generation)) =
+    let Some((buffer, accepting_input, generation, rate_monitor)) =
         try_capture_buffer(recording, capture_generation, recording_state)
     else {
         return;
@@ -378,6 +502,8 @@ fn append_input_f32(
             data.iter()
                 .map(|&s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16),
         );
+        drop(buf);
+        rate_monitor.lock().unwrap().observe(capture, data.len());
     }
 }
 
@@ -536,6 +662,7 @@ impl CpalAudioBackend {
             channels,
             generation,
             accepting_input: Arc::new(AtomicBool::new(true)),
+            rate_monitor: Arc::new(Mutex::new(SampleRateMonitor::new(sample_rate, channels))),
         });
         self.recording.store(true, Ordering::SeqCst);
         generation
@@ -696,6 +823,11 @@ impl CpalAudioBackend {
     const NOISE_WINDOW_MS: u32 = 200;
     const MIN_SILENCE_DURATION_MS: u32 = 50;
     const MIN_RETAINED_FRAMES: usize = 1;
+    const SNR_WINDOW_MS: u32 = 20;
+    const SNR_MIN_WINDOWS: usize = 25;
+    const SPEECH_TAIL_MS: u32 = 300;
+    /// 大きい窓と静かな窓のエネルギー差がこれ未満なら一定の音とみなす
+    const TONAL_MAX_SPREAD_DB: f64 = 6.0;
 
     /// メモリバッファのサイズ見積もり
     /// 録音時間に基づいて必要なバッファサイズを計算
@@ -704,6 +836,92 @@ impl CpalAudioBackend {
         sample_rate as usize * channels as usize * duration_secs as usize
     }
 
+    /// 短時間窓のエネルギー分布から SNR（dB）を推定する
+    ///
+    /// 下位 10% の窓を背景雑音、上位 10% の窓を発話とみなす。
+    /// 判定に足りる長さがない場合は `None`。
+    fn estimate_snr_db(samples: &[i16], sample_rate: u32, channels: u16) -> Option<f32> {
+        let (noise, speech) = Self::energy_percentiles(samples, sample_rate, channels)?;
+        Some((10.0 * (speech.max(1.0) / noise.max(1.0)).log10()) as f32)
+    }
+
+    /// 短時間窓の平均エネルギーの下位 10%・上位 10% の値を返す
+    ///
+    /// 判定に足りる長さがない場合は `None`。
+    fn energy_percentiles(samples: &[i16], sample_rate: u32, channels: u16) -> Option<(f64, f64)> {
+        let window =
+            ((sample_rate as usize * channels.max(1) as usize * Self::SNR_WINDOW_MS as usize)
+                / 1000)
+                .max(1);
+        let mut energies: Vec<f64> = samples
+            .chunks_exact(window)
+            .map(|chunk| chunk.iter().map(|&s| (s as f64).powi(2)).sum::<f64>() / window as f64)
+            .collect();
+        if energies.len() < Self::SNR_MIN_WINDOWS {
+            return None;
+        }
+
+        energies.sort_by(f64::total_cmp);
+        Some((
+            energies[energies.len() / 10],
+            energies[energies.len() * 9 / 10],
+        ))
+    }
+
+    /// ハミングや歌声のように、録音全体で音量がほぼ一定の音かを判定する
+    ///
+    /// 冒頭から鳴り続ける音は冒頭の音量を背景雑音とみなす動的しきい値を超えられず、
+    /// 全体が無音として除去されてしまうため、無音除去を行わない判定に使う。
+    fn is_sustained_tone(samples: &[i16], sample_rate: u32, channels: u16) -> bool {
+        let Some((quiet, loud)) = Self::energy_percentiles(samples, sample_rate, channels) else {
+            return false;
+        };
+        quiet.sqrt() >= Self::MIN_SILENCE_THRESHOLD as f64
+            && 10.0 * (loud / quiet).log10() < Self::TONAL_MAX_SPREAD_DB
+    }
+
+    /// 直近 `SPEECH_TAIL_MS` に冒頭の背景雑音より大きい窓があれば発話中とみなす
+    fn has_recent_speech(samples: &[i16], sample_rate: u32, channels: u16) -> bool {
+        let window =
+            ((sample_rate as usize * channels.max(1) as usize * Self::SNR_WINDOW_MS as usize)
+                / 1000)
+                .max(1);
+        let tail_len =
+            (sample_rate as usize * channels.max(1) as usize * Self::SPEECH_TAIL_MS as usize)
+                / 1000;
+        if samples.len() < tail_len.max(window) {
+            return false;
+        }
+
+        let threshold = Self::calculate_dynamic_threshold(samples, sample_rate, channels) as i64;
+        samples[samples.len() - tail_len..]
+            .chunks_exact(window)
+            .any(|chunk| {
+                let avg_abs =
+                    chunk.iter().map(|&s| (s as i32).abs() as i64).sum::<i64>() / window as i64;
+                avg_abs >= threshold
+            })
+    }
+
+    /// 発話判定に必要な冒頭の背景雑音の窓と直近 `SPEECH_TAIL_MS` だけを写し取る
+    ///
+    /// 録音中の取り込みバッファのロックを短く保つため、全体ではなく判定に使う部分だけを複製する。
+    /// 返す列に対する [`has_recent_speech`](Self::has_recent_speech) の結果は全体に対するものと同じ。
+    fn speech_excerpt(samples: &[i16], sample_rate: u32, channels: u16) -> Vec<i16> {
+        let channels = channels.max(1) as usize;
+        let noise_len =
+            ((sample_rate as usize * Self::NOISE_WINDOW_MS as usize) / 1000).max(1) * channels;
+        let tail_len = (sample_rate as usize * channels * Self::SPEECH_TAIL_MS as usize) / 1000;
+        if samples.len() <= noise_len + tail_len {
+            return samples.to_vec();
+        }
+
+        let mut excerpt = Vec::with_capacity(noise_len + tail_len);
+        excerpt.extend_from_slice(&samples[..noise_len]);
+        excerpt.extend_from_slice(&samples[samples.len() - tail_len..]);
+        excerpt
+    }
+
     fn calculate_dynamic_threshold(samples: &[i16], sample_rate: u32, channels: u16) -> i16 {
         if samples.is_empty() {
             return Self::MIN_SILENCE_THRESHOLD as i16;
@@ -802,6 +1020,11 @@ impl CpalAudioBackend {
             return Cow::Borrowed(samples);
         }
 
+        if Self::is_sustained_tone(samples, sample_rate, channels) {
+            profiling::log_point("audio.trim_silence", "skipped=sustained_tone");
+            return Cow::Borrowed(samples);
+        }
+
         let threshold = Self::calculate_dynamic_threshold(samples, sample_rate, channels);
         let min_silence_frames = Self::min_silence_frames(sample_rate);
 
@@ -971,12 +1194,13 @@ impl CpalAudioBackend {
         let stream = match sample_format {
             SampleFormat::I16 => device.build_input_stream(
                 config,
-                move |data: &[i16], _| {
+                move |data: &[i16], info: &InputCallbackInfo| {
                     append_input_i16(
                         recording.as_ref(),
                         capture_generation.as_ref(),
                         &recording_state,
                         data,
+                        info.timestamp().capture,
                     );
                 },
                 move |e| {
@@ -987,12 +1211,13 @@ impl CpalAudioBackend {
             )?,
             SampleFormat::F32 => device.build_input_stream(
                 config,
-                move |data: &[f32], _| {
+                move |data: &[f32], info: &InputCallbackInfo| {
                     append_input_f32(
                         recording.as_ref(),
                         capture_generation.as_ref(),
                         &recording_state,
                         data,
+                        info.timestamp().capture,
                     );
                 },
                 move |e| {
@@ -1068,6 +1293,7 @@ impl AudioBackend for CpalAudioBackend {
             return Err(CpalBackendError::RecordingStateNotSet.into());
         }
         state.accepting_input.store(false, Ordering::SeqCst);
+        *self.last_snr_db.lock().unwrap() = None;
 
         // メモリモード: バッファからエンコード（既定: FLAC）
         let samples = state.buffer.lock().unwrap();
@@ -1092,14 +1318,37 @@ impl AudioBackend for CpalAudioBackend {
                     .to_string(),
             });
         }
+        let detected_rate = state.rate_monitor.lock().unwrap().detected_rate();
+        let sample_rate = match detected_rate {
+            Some(actual_rate) => {
+                eprintln!(
+                    "Audio device sample rate changed during capture; configured={} detected={} (samples={}). Correcting header and rebuilding input stream.",
+                    state.sample_rate, actual_rate, samples_len
+                );
+                profiling::log_point(
+                    "audio.sample_rate_mismatch",
+                    &format!("configured={} detected={}", state.sample_rate, actual_rate),
+                );
+                // 次回の録音ではデバイスの現在の設定でストリームを作り直す
+                self.stream_needs_rebuild.store(true, Ordering::SeqCst);
+                self.input_setup_cache.value.lock().unwrap().take();
+                actual_rate
+            }
+            None => state.sample_rate,
+        };
+
+        // 無音除去前の全体から雑音レベルを推定する
+        let snr_db = Self::estimate_snr_db(&samples, sample_rate, state.channels);
+        *self.last_snr_db.lock().unwrap() = snr_db;
+
         let trim_timer = profiling::Timer::start("audio.trim_silence");
-        let trimmed = Self::trim_silence(&samples, state.sample_rate, state.channels);
+        let trimmed = Self::trim_silence(&samples, sample_rate, state.channels);
         if profiling::enabled() {
             trim_timer.log_with(&format!(
                 "samples={} trimmed={} rate={} ch={}",
                 samples_len,
                 trimmed.len(),
-                state.sample_rate,
+                sample_rate,
                 state.channels
             ));
         } else {
@@ -1111,13 +1360,13 @@ impl AudioBackend for CpalAudioBackend {
             let mono = Self::downmix_to_mono(trimmed.as_ref(), state.channels);
             ProcessedAudio {
                 samples: Cow::Owned(mono),
-                sample_rate: state.sample_rate,
+                sample_rate,
                 channels: 1,
             }
         } else {
             ProcessedAudio {
                 samples: trimmed,
-                sample_rate: state.sample_rate,
+                sample_rate,
                 channels: state.channels,
             }
         };
@@ -1143,6 +1392,7 @@ impl AudioBackend for CpalAudioBackend {
                     &processed.samples,
                     processed.sample_rate,
                     processed.channels,
+                    EnvConfig::get().audio.flac_compression_level,
                 ) {
                     Ok(flac) => {
                         if profiling::enabled() {
@@ -1231,6 +1481,28 @@ impl AudioBackend for CpalAudioBackend {
         self.invalidate_input_stream();
         self.warm_up()
     }
+
+    fn last_capture_snr_db(&self) -> Option<f32> {
+        *self.last_snr_db.lock().unwrap()
+    }
+
+    fn is_speech_active(&self) -> bool {
+        if !self.is_recording() {
+            return false;
+        }
+        let Some((buffer, sample_rate, channels)) = self
+            .recording_state
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|state| (state.buffer.clone(), state.sample_rate, state.channels))
+        else {
+            return false;
+        };
+
+        let excerpt = Self::speech_excerpt(&buffer.lock().unwrap(), sample_rate, channels);
+        Self::has_recent_speech(&excerpt, sample_rate, channels)
+    }
 }
 
 // #[cfg(test)]
@@ -1618,6 +1890,61 @@ mod tests {
         );
     }
 
+    /// 10ms ごとの callback を `secs` 秒分、実際のレート `actual_rate` で流し込む
+    fn feed_callbacks(
+        monitor: &mut SampleRateMonitor,
+        start_ms: u64,
+        secs: u64,
+        actual_rate: usize,
+    ) -> u64 {
+        let callbacks = secs * 100;
+        for i in 0..callbacks {
+            let at = Duration::from_millis(start_ms + i * 10);
+            monitor.observe(
+                StreamInstant::new(at.as_secs() as i64, at.subsec_nanos()),
+                actual_rate / 100 * monitor.channels as usize,
+            );
+        }
+        start_ms + callbacks * 10
+    }
+
+    /// 実測レートが設定値の倍のまま続けば標準レートに丸めて報告する
+    #[test]
+    fn sample_rate_mismatch_is_detected_and_snapped_to_standard_rate() {
+        let mut monitor = SampleRateMonitor::new(48_000, 2);
+        feed_callbacks(&mut monitor, 0, 3, 96_000);
+        assert_eq!(monitor.detected_rate(), Some(96_000));
+
+        let mut monitor = SampleRateMonitor::new(48_000, 1);
+        feed_callbacks(&mut monitor, 0, 3, 44_100);
+        assert_eq!(
+            monitor.detected_rate(),
+            None,
+            "許容範囲内のずれは不一致とみなさない"
+        );
+    }
+
+    /// 1 つの窓だけのずれ（立ち上がり時のまとめ届き）では不一致とみなさない
+    #[test]
+    fn sample_rate_mismatch_requires_consecutive_windows() {
+        let mut monitor = SampleRateMonitor::new(48_000, 1);
+        let next = feed_callbacks(&mut monitor, 0, 1, 96_000);
+        feed_callbacks(&mut monitor, next, 2, 48_000);
+
+        assert_eq!(monitor.detected_rate(), None);
+    }
+
+    /// スリープ復帰などで callback が途切れても、その間を低いレートとみなさない
+    #[test]
+    fn sample_rate_mismatch_ignores_callback_gaps() {
+        let mut monitor = SampleRateMonitor::new(48_000, 1);
+        let next = feed_callbacks(&mut monitor, 0, 1, 48_000);
+        let next = feed_callbacks(&mut monitor, next + 30_000, 1, 48_000);
+        feed_callbacks(&mut monitor, next + 5_000, 1, 48_000);
+
+        assert_eq!(monitor.detected_rate(), None);
+    }
+
     /// 世代が切り替わった callback は停止後の buffer に追記しない
     #[test]
     fn stale_generation_does_not_append_after_stop() {
@@ -1631,9 +1958,16 @@ mod tests {
             channels: 1,
             generation: 1,
             accepting_input,
+            rate_monitor: Arc::new(Mutex::new(SampleRateMonitor::new(48_000, 1))),
         })));
 
-        append_input_i16(&recording, &capture_generation, &recording_state, &[20, 30]);
+        append_input_i16(
+            &recording,
+            &capture_generation,
+            &recording_state,
+            &[20, 30],
+            StreamInstant::new(0, 0),
+        );
 
         assert_eq!(*buffer.lock().unwrap(), vec![10i16]);
     }
@@ -2054,6 +2388,7 @@ mod tests {
             channels: 2,
             generation: 1,
             accepting_input: Arc::new(AtomicBool::new(true)),
+            rate_monitor: Arc::new(Mutex::new(SampleRateMonitor::new(48000, 2))),
         };
 
         // bufferが適切に初期化されているか確認
@@ -2134,6 +2469,7 @@ mod tests {
             channels: 1,
             generation: 1,
             accepting_input: Arc::new(AtomicBool::new(true)),
+            rate_monitor: Arc::new(Mutex::new(SampleRateMonitor::new(48000, 1))),
         });
         backend.capture_generation.store(1, Ordering::SeqCst);
 
@@ -2169,6 +2505,7 @@ mod tests {
             channels: 2,
             generation: 1,
             accepting_input: Arc::new(AtomicBool::new(true)),
+            rate_monitor: Arc::new(Mutex::new(SampleRateMonitor::new(44100, 2))),
         });
         backend.capture_generation.store(1, Ordering::SeqCst);
 
@@ -2288,6 +2625,93 @@ mod tests {
         }
     }
 
+    /// 静かな背景で話した音声はSNRが高く推定される
+    #[test]
+    fn snr_is_high_for_speech_over_quiet_background() {
+        let sample_rate = 16_000;
+        let mut samples = vec![10i16; sample_rate as usize / 2];
+        samples
+            .extend((0..sample_rate as usize / 2).map(|i| if i % 2 == 0 { 8000 } else { -8000 }));
+
+        let snr = CpalAudioBackend::estimate_snr_db(&samples, sample_rate, 1).unwrap();
+
+        assert!(snr > 40.0, "snr={}", snr);
+    }
+
+    /// 発話と背景雑音の差が小さい音声はSNRが低く推定される
+    #[test]
+    fn snr_is_low_when_background_is_as_loud_as_speech() {
+        let sample_rate = 16_000;
+        let mut samples = vec![3000i16; sample_rate as usize / 2];
+        samples.extend(vec![4000i16; sample_rate as usize / 2]);
+
+        let snr = CpalAudioBackend::estimate_snr_db(&samples, sample_rate, 1).unwrap();
+
+        assert!(snr < 5.0, "snr={}", snr);
+    }
+
+    /// 推定に足りない短い音声ではSNRを返さない
+    #[test]
+    fn snr_is_unavailable_for_very_short_audio() {
+        let samples = vec![1000i16; 160];
+
+        assert_eq!(CpalAudioBackend::estimate_snr_db(&samples, 16_000, 1), None);
+    }
+
+    /// 末尾で話し続けている録音は発話中と判定される
+    #[test]
+    fn recent_speech_is_detected_when_tail_is_loud() {
+        let sample_rate = 16_000;
+        let mut samples = vec![10i16; sample_rate as usize];
+        samples
+            .extend((0..sample_rate as usize / 2).map(|i| if i % 2 == 0 { 8000 } else { -8000 }));
+
+        assert!(CpalAudioBackend::has_recent_speech(
+            &samples,
+            sample_rate,
+            1
+        ));
+    }
+
+    /// 話し終えて背景雑音だけになった録音は発話中と判定されない
+    #[test]
+    fn recent_speech_is_not_detected_after_speaker_stops() {
+        let sample_rate = 16_000;
+        let mut samples = vec![10i16; sample_rate as usize / 2];
+        samples.extend(vec![8000i16; sample_rate as usize / 2]);
+        samples.extend(vec![10i16; sample_rate as usize / 2]);
+
+        assert!(!CpalAudioBackend::has_recent_speech(
+            &samples,
+            sample_rate,
+            1
+        ));
+    }
+
+    /// 冒頭の雑音窓と末尾だけの抜粋でも、全体と同じ発話判定になる
+    #[test]
+    fn speech_excerpt_keeps_recent_speech_decision() {
+        let sample_rate = 16_000;
+        let mut speaking = vec![10i16; sample_rate as usize * 60];
+        speaking.extend(vec![8000i16; sample_rate as usize / 2]);
+        let mut stopped = speaking.clone();
+        stopped.extend(vec![10i16; sample_rate as usize]);
+
+        // 2ch: 冒頭 200ms は 6,400 サンプル、末尾 300ms は 9,600 サンプル
+        for (samples, expected) in [(speaking, true), (stopped, false)] {
+            let excerpt = CpalAudioBackend::speech_excerpt(&samples, sample_rate, 2);
+            assert_eq!(excerpt.len(), 6_400 + 9_600);
+            assert_eq!(
+                CpalAudioBackend::has_recent_speech(&excerpt, sample_rate, 2),
+                expected
+            );
+            assert_eq!(
+                CpalAudioBackend::has_recent_speech(&samples, sample_rate, 2),
+                expected
+            );
+        }
+    }
+
     /// 先頭と末尾の無音が除去される
     #[test]
     fn trim_silence_removes_leading_and_trailing_silence() {
@@ -2347,6 +2771,39 @@ mod tests {
         assert!(trimmed.iter().all(|&s| s == 0));
     }
 
+    /// 冒頭から鳴り続けるハミングは無音として除去せずそのまま残す
+    #[test]
+    fn trim_silence_keeps_sustained_tone() {
+        let sample_rate = 16_000;
+        let samples: Vec<i16> = (0..sample_rate as usize)
+            .map(|i| ((i as f32 * 0.1).sin() * 3000.0) as i16)
+            .collect();
+
+        assert!(CpalAudioBackend::is_sustained_tone(
+            &samples,
+            sample_rate,
+            1
+        ));
+        let trimmed = CpalAudioBackend::trim_silence(&samples, sample_rate, 1);
+
+        assert_eq!(trimmed.len(), samples.len());
+    }
+
+    /// 静かな背景の上の発話は一定の音とみなさない
+    #[test]
+    fn speech_over_quiet_background_is_not_sustained_tone() {
+        let sample_rate = 16_000;
+        let mut samples = vec![10i16; sample_rate as usize / 2];
+        samples
+            .extend((0..sample_rate as usize / 2).map(|i| if i % 2 == 0 { 8000 } else { -8000 }));
+
+        assert!(!CpalAudioBackend::is_sustained_tone(
+            &samples,
+            sample_rate,
+            1
+        ));
+    }
+
     /// 48kHz の音声を 16kHz に変換するとサンプル数が 1/3 になる
     #[test]
     fn resample_to_16khz_downscales_frame_count() {
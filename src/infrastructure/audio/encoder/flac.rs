@@ -2,17 +2,43 @@ use super::AudioEncodeError;
 use flacenc::component::BitRepr;
 use flacenc::error::Verify;
 
+/// 指定できる最大の圧縮レベル
+pub const MAX_COMPRESSION_LEVEL: u8 = 8;
+
+/// LPC を使うレベル（2 以上）ごとの LPC 次数。5 が flacenc の既定設定と同じ
+const LPC_ORDER_BY_LEVEL: [usize; 7] = [4, 6, 8, 10, 12, 16, 24];
+
+/// 圧縮レベルからエンコーダ設定を作る
+///
+/// 0〜1 は固定予測のみで最速、2 以上は LPC 次数を段階的に上げる。
+/// `MAX_COMPRESSION_LEVEL` を超える値は最大として扱う。
+fn encoder_config(compression_level: u8) -> flacenc::config::Encoder {
+    let mut cfg = flacenc::config::Encoder::default();
+    match compression_level {
+        0 | 1 => {
+            cfg.subframe_coding.use_lpc = false;
+            cfg.subframe_coding.fixed.max_order = if compression_level == 0 { 2 } else { 4 };
+        }
+        level => {
+            let index = usize::from(level.min(MAX_COMPRESSION_LEVEL) - 2);
+            cfg.subframe_coding.qlpc.lpc_order = LPC_ORDER_BY_LEVEL[index];
+        }
+    }
+    cfg
+}
+
 /// 16bit PCM (interleaved) から FLAC を生成してバイト列を返す
 pub fn encode_flac_i16(
     samples: &[i16],
     sample_rate: u32,
     channels: u16,
+    compression_level: u8,
 ) -> Result<Vec<u8>, AudioEncodeError> {
     // flacenc は i32 サンプルを想定するため変換
     let mut pcm_i32 = Vec::with_capacity(samples.len());
     pcm_i32.extend(samples.iter().copied().map(|s| s as i32));
 
-    let cfg = flacenc::config::Encoder::default()
+    let cfg = encoder_config(compression_level)
         .into_verified()
         .map_err(|e| AudioEncodeError::Flac(format!("config verify failed: {e:?}")))?;
 
@@ -32,3 +58,19 @@ pub fn encode_flac_i16(
 
     Ok(sink.as_slice().to_vec())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// すべての圧縮レベルが検証を通る設定になる
+    #[test]
+    fn every_compression_level_yields_valid_config() {
+        for level in 0..=MAX_COMPRESSION_LEVEL + 1 {
+            assert!(
+                encoder_config(level).into_verified().is_ok(),
+                "level {level}"
+            );
+        }
+    }
+}
@@ -0,0 +1,176 @@
+//! エンコード済み音声のディスクキャッシュ
+//!
+//! # 責任
+//! - 直近の録音を上限件数までデータディレクトリへ保存
+//! - `voice_input retry-last` 向けに最新の録音を読み出す
+//!
+//! 転写に失敗した録音を録り直さずに再転写できるよう、転写の前に保存する。
+//! 録音内容をディスクに残すため既定では無効で、`VOICE_INPUT_AUDIO_CACHE_ENTRIES` で有効にする。
+//! ディレクトリは 0700・ファイルは 0600 で作成する。
+
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::application::AudioData;
+use crate::infrastructure::config::audio_cache_dir;
+use crate::infrastructure::external::temp_audio::ensure_private_dir;
+use crate::utils::config::EnvConfig;
+use crate::utils::user::current_uid;
+
+/// キャッシュから読み出した録音
+#[derive(Debug, Clone)]
+pub struct CachedAudio {
+    pub session_id: u64,
+    pub audio: AudioData,
+}
+
+/// 録音を新しい順に上限件数まで保持するキャッシュ
+///
+/// ファイル名は `<保存時刻(ns)>-<session_id>.<拡張子>` で、名前順が保存順になる。
+#[derive(Debug, Clone)]
+pub struct AudioCache {
+    dir: PathBuf,
+    capacity: usize,
+}
+
+impl AudioCache {
+    /// `dir` に最大 `capacity` 件を保持するキャッシュを作成する。0 なら保存しない
+    pub fn new(dir: PathBuf, capacity: usize) -> Self {
+        Self { dir, capacity }
+    }
+
+    /// データディレクトリ配下の既定のキャッシュを設定の件数で開く
+    pub fn open_default() -> Self {
+        Self::new(audio_cache_dir(), EnvConfig::get().audio.cache_entries)
+    }
+
+    /// 保存する設定か（件数が 0 なら保存しない）
+    pub fn is_enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    /// 録音を保存し、上限を超えた古いものを削除する
+    pub fn store(&self, session_id: u64, audio: &AudioData) -> io::Result<()> {
+        if self.capacity == 0 {
+            return Ok(());
+        }
+        ensure_private_dir(&self.dir, current_uid())?;
+
+        let stored_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let extension = Path::new(&audio.file_name)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("bin");
+        let path = self
+            .dir
+            .join(format!("{:020}-{}.{}", stored_at, session_id, extension));
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(0o600)
+            .open(&path)?;
+        file.write_all(&audio.bytes)?;
+
+        let entries = self.entries()?;
+        let excess = entries.len().saturating_sub(self.capacity);
+        for old in &entries[..excess] {
+            std::fs::remove_file(old)?;
+        }
+        Ok(())
+    }
+
+    /// 最後に保存した録音を返す。キャッシュが空なら `None`
+    pub fn latest(&self) -> io::Result<Option<CachedAudio>> {
+        for path in self.entries()?.iter().rev() {
+            let Some(session_id) = session_id_of(path) else {
+                continue;
+            };
+            let bytes = std::fs::read(path)?;
+            if let Some(audio) = AudioData::from_stream_bytes(bytes) {
+                return Ok(Some(CachedAudio { session_id, audio }));
+            }
+        }
+        Ok(None)
+    }
+
+    /// 保存済みのファイルを古い順に返す
+    fn entries(&self) -> io::Result<Vec<PathBuf>> {
+        let read_dir = match std::fs::read_dir(&self.dir) {
+            Ok(read_dir) => read_dir,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => return Err(error),
+        };
+        let mut entries = Vec::new();
+        for entry in read_dir {
+            let path = entry?.path();
+            if session_id_of(&path).is_some() {
+                entries.push(path);
+            }
+        }
+        entries.sort();
+        Ok(entries)
+    }
+}
+
+/// キャッシュのファイル名から session_id を取り出す
+fn session_id_of(path: &Path) -> Option<u64> {
+    let stem = path.file_stem()?.to_str()?;
+    let (stored_at, session_id) = stem.split_once('-')?;
+    stored_at.parse::<u128>().ok()?;
+    session_id.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::MetadataExt;
+    use tempfile::TempDir;
+
+    fn flac(marker: u8) -> AudioData {
+        AudioData::from_stream_bytes(vec![b'f', b'L', b'a', b'C', marker]).unwrap()
+    }
+
+    /// 上限を超えると古い録音から削除し、最新の録音を読み出せる
+    #[test]
+    fn oldest_recordings_are_evicted_and_latest_is_returned() {
+        let root = TempDir::new().unwrap();
+        let cache = AudioCache::new(root.path().join("audio_cache"), 2);
+        assert!(cache.latest().unwrap().is_none());
+
+        for session_id in 1..=3 {
+            cache.store(session_id, &flac(session_id as u8)).unwrap();
+        }
+
+        let entries = cache.entries().unwrap();
+        let sessions: Vec<_> = entries.iter().filter_map(|p| session_id_of(p)).collect();
+        assert_eq!(sessions, vec![2, 3]);
+        assert_eq!(
+            std::fs::metadata(&entries[0]).unwrap().mode() & 0o777,
+            0o600
+        );
+
+        let latest = cache.latest().unwrap().unwrap();
+        assert_eq!(latest.session_id, 3);
+        assert_eq!(latest.audio.bytes, b"fLaC\x03");
+        assert_eq!(latest.audio.mime_type, "audio/flac");
+    }
+
+    /// 上限 0 なら何も保存しない
+    #[test]
+    fn zero_capacity_disables_cache() {
+        let root = TempDir::new().unwrap();
+        let dir = root.path().join("audio_cache");
+        let cache = AudioCache::new(dir.clone(), 0);
+
+        cache.store(1, &flac(1)).unwrap();
+
+        assert!(!dir.exists());
+        assert!(cache.latest().unwrap().is_none());
+    }
+}
@@ -0,0 +1,284 @@
+//! 実機での性能計測
+//!
+//! # 責任
+//! - 録音開始から最初のサンプル到着までの時間の計測
+//! - FLAC エンコードのスループット計測
+//! - 短い音声での転写 API 往復時間の計測
+//! - テキスト入力（貼り付け）の所要時間の計測
+//!
+//! 「遅い」という報告を環境ごとに比較できるよう、共有しやすい 1 行ずつのレポートを返す。
+//! `voice_input bench` から実行する。
+
+use std::time::{Duration, Instant};
+
+use crate::application::{AudioBackend, AudioData, TranscriptionOptions};
+use crate::infrastructure::audio::CpalAudioBackend;
+use crate::infrastructure::audio::encoder::flac::encode_flac_i16;
+use crate::infrastructure::external::text_input;
+use crate::infrastructure::service_container::build_default_transcription_client;
+use crate::utils::config::EnvConfig;
+
+/// エンコード計測に使う音声の長さ（秒）
+const ENCODE_SAMPLE_SECS: u32 = 10;
+/// 計測用音声のサンプルレート（転写前の変換後と同じ 16kHz モノラル）
+const BENCH_SAMPLE_RATE: u32 = 16_000;
+/// API 往復計測に送る音声の長さ（ミリ秒）
+const API_SAMPLE_MS: u32 = 500;
+/// 録音開始計測で録音を続ける時間
+const CAPTURE_HOLD: Duration = Duration::from_millis(200);
+/// 貼り付け計測で入力するテキスト
+const PASTE_TEXT: &str = "voice_input bench";
+
+/// 計測の実行内容
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BenchOptions {
+    /// 転写 API の往復計測を省く（課金やオフライン環境向け）
+    pub skip_api: bool,
+    /// 前面アプリへのテキスト入力を伴う貼り付け計測を行う
+    pub paste: bool,
+}
+
+/// 1 項目の計測結果
+#[derive(Debug, Clone, PartialEq)]
+pub enum BenchMeasurement {
+    /// 計測できた。`detail` は補足（スループットなど）
+    Measured { elapsed: Duration, detail: String },
+    /// 実行しなかった
+    Skipped(String),
+    /// 計測中に失敗した
+    Failed(String),
+}
+
+impl BenchMeasurement {
+    fn measured(elapsed: Duration, detail: impl Into<String>) -> Self {
+        Self::Measured {
+            elapsed,
+            detail: detail.into(),
+        }
+    }
+
+    fn format(&self, name: &str) -> String {
+        match self {
+            Self::Measured { elapsed, detail } if detail.is_empty() => {
+                format!("{}: {:.1}ms", name, elapsed.as_secs_f64() * 1000.0)
+            }
+            Self::Measured { elapsed, detail } => {
+                format!(
+                    "{}: {:.1}ms ({})",
+                    name,
+                    elapsed.as_secs_f64() * 1000.0,
+                    detail
+                )
+            }
+            Self::Skipped(reason) => format!("{}: skipped ({})", name, reason),
+            Self::Failed(error) => format!("{}: failed ({})", name, error),
+        }
+    }
+}
+
+/// 計測レポート
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchReport {
+    /// 録音開始から最初のサンプル到着まで
+    pub capture: BenchMeasurement,
+    /// 16kHz モノラル音声の FLAC エンコード
+    pub encode: BenchMeasurement,
+    /// 短い音声の転写 API 往復
+    pub api: BenchMeasurement,
+    /// テキスト入力ワーカー経由の入力
+    pub paste: BenchMeasurement,
+}
+
+impl BenchReport {
+    /// 共有用に環境情報を含めて 1 行ずつ整形する
+    pub fn format_lines(&self, provider: &str) -> Vec<String> {
+        vec![
+            format!("voice_input {}", env!("CARGO_PKG_VERSION")),
+            format!(
+                "platform: {}-{}",
+                std::env::consts::OS,
+                std::env::consts::ARCH
+            ),
+            format!("provider: {}", provider),
+            self.capture.format("capture_latency"),
+            self.encode.format("encode_flac"),
+            self.api.format("api_roundtrip"),
+            self.paste.format("paste_latency"),
+        ]
+    }
+}
+
+/// すべての項目を計測する
+///
+/// 貼り付け計測はテキスト入力ワーカーを使うため、`LocalSet` 上で呼び出すこと。
+pub async fn run_bench(options: BenchOptions) -> BenchReport {
+    BenchReport {
+        capture: measure_capture(),
+        encode: measure_encode(ENCODE_SAMPLE_SECS),
+        api: if options.skip_api {
+            BenchMeasurement::Skipped("--skip-api".to_string())
+        } else {
+            measure_api().await
+        },
+        paste: if options.paste {
+            measure_paste().await
+        } else {
+            BenchMeasurement::Skipped("pass --paste to type into the focused app".to_string())
+        },
+    }
+}
+
+/// 録音開始（最初のサンプル到着を待つ）までの時間を計測する
+fn measure_capture() -> BenchMeasurement {
+    let backend = CpalAudioBackend::default();
+    if let Err(e) = backend.warm_up() {
+        return BenchMeasurement::Failed(e.to_string());
+    }
+
+    let started_at = Instant::now();
+    if let Err(e) = backend.start_recording() {
+        return BenchMeasurement::Failed(e.to_string());
+    }
+    let elapsed = started_at.elapsed();
+
+    std::thread::sleep(CAPTURE_HOLD);
+    match backend.stop_recording() {
+        Ok(audio) => {
+            BenchMeasurement::measured(elapsed, format!("captured {} bytes", audio.bytes.len()))
+        }
+        Err(e) => BenchMeasurement::Failed(e.to_string()),
+    }
+}
+
+/// `secs` 秒分の合成音声を FLAC エンコードし、実時間比を求める
+fn measure_encode(secs: u32) -> BenchMeasurement {
+    let samples = synthetic_samples(BENCH_SAMPLE_RATE * secs);
+
+    let started_at = Instant::now();
+    match encode_flac_i16(
+        &samples,
+        BENCH_SAMPLE_RATE,
+        1,
+        EnvConfig::get().audio.flac_compression_level,
+    ) {
+        Ok(encoded) => {
+            let elapsed = started_at.elapsed();
+            let realtime = f64::from(secs) / elapsed.as_secs_f64().max(f64::EPSILON);
+            BenchMeasurement::measured(
+                elapsed,
+                format!(
+                    "{}s audio, {} bytes, {:.0}x realtime",
+                    secs,
+                    encoded.len(),
+                    realtime
+                ),
+            )
+        }
+        Err(e) => BenchMeasurement::Failed(e.to_string()),
+    }
+}
+
+/// 短い無音 WAV を転写し、往復時間を計測する
+async fn measure_api() -> BenchMeasurement {
+    let client = match build_default_transcription_client(&EnvConfig::get()) {
+        Ok(client) => client,
+        Err(e) => return BenchMeasurement::Failed(e.to_string()),
+    };
+
+    let started_at = Instant::now();
+    let language = TranscriptionOptions::default().language;
+    match client.transcribe(tiny_wav(), &language, None).await {
+        Ok(_) => {
+            BenchMeasurement::measured(started_at.elapsed(), format!("{}ms sample", API_SAMPLE_MS))
+        }
+        Err(e) => BenchMeasurement::Failed(e.to_string()),
+    }
+}
+
+/// テキスト入力ワーカー経由で短い文字列を入力する時間を計測する
+async fn measure_paste() -> BenchMeasurement {
+    if let Err(e) = text_input::init_worker() {
+        return BenchMeasurement::Failed(e.to_string());
+    }
+
+    let started_at = Instant::now();
+    match text_input::type_text(PASTE_TEXT).await {
+        Ok(()) => BenchMeasurement::measured(
+            started_at.elapsed(),
+            format!("{} chars", PASTE_TEXT.chars().count()),
+        ),
+        Err(e) => BenchMeasurement::Failed(e.to_string()),
+    }
+}
+
+/// 無音ではエンコードが簡単になりすぎるため、正弦波を重ねた音声を作る
+fn synthetic_samples(len: u32) -> Vec<i16> {
+    (0..len)
+        .map(|i| {
+            let t = f64::from(i) / f64::from(BENCH_SAMPLE_RATE);
+            let wave = (t * 440.0 * std::f64::consts::TAU).sin() * 0.6
+                + (t * 1_250.0 * std::f64::consts::TAU).sin() * 0.3;
+            (wave * f64::from(i16::MAX) * 0.5) as i16
+        })
+        .collect()
+}
+
+/// API 往復計測用の短い無音 WAV
+fn tiny_wav() -> AudioData {
+    let data_len = BENCH_SAMPLE_RATE * API_SAMPLE_MS / 1000 * 2;
+    let mut bytes = CpalAudioBackend::create_wav_header(data_len, BENCH_SAMPLE_RATE, 1, 16);
+    bytes.resize(bytes.len() + data_len as usize, 0);
+    AudioData {
+        bytes,
+        mime_type: "audio/wav",
+        file_name: "audio.wav".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 合成音声のエンコードが計測され、実時間比が補足に含まれる
+    #[test]
+    fn encode_measurement_reports_realtime_ratio() {
+        match measure_encode(1) {
+            BenchMeasurement::Measured { detail, .. } => {
+                assert!(detail.starts_with("1s audio, "), "{}", detail);
+                assert!(detail.ends_with("x realtime"), "{}", detail);
+            }
+            other => panic!("unexpected measurement: {:?}", other),
+        }
+    }
+
+    /// レポートは環境情報と各項目を 1 行ずつ並べる
+    #[test]
+    fn report_lines_include_environment_and_each_measurement() {
+        let report = BenchReport {
+            capture: BenchMeasurement::measured(Duration::from_micros(12_340), ""),
+            encode: BenchMeasurement::measured(Duration::from_millis(5), "10s audio"),
+            api: BenchMeasurement::Skipped("--skip-api".to_string()),
+            paste: BenchMeasurement::Failed("worker unavailable".to_string()),
+        };
+
+        let lines = report.format_lines("openai");
+        assert_eq!(lines[2], "provider: openai");
+        assert_eq!(
+            &lines[3..],
+            [
+                "capture_latency: 12.3ms",
+                "encode_flac: 5.0ms (10s audio)",
+                "api_roundtrip: skipped (--skip-api)",
+                "paste_latency: failed (worker unavailable)",
+            ]
+        );
+    }
+
+    /// API 計測用の WAV はヘッダと指定長の無音からなる
+    #[test]
+    fn tiny_wav_has_header_and_silence() {
+        let audio = tiny_wav();
+        assert_eq!(audio.bytes.len(), 44 + 16_000);
+        assert_eq!(&audio.bytes[..4], b"RIFF");
+    }
+}
@@ -0,0 +1,72 @@
+//! ビルドに組み込まれたネイティブ連携
+//!
+//! # 責任
+//! - cargo feature で切り替えるネイティブ連携の有無を実行時に報告する
+//!
+//! ヘッドレス環境向けに機能を外したビルドでも、`voice_input health` から
+//! 何が使えるかを確認できるようにする。
+
+/// feature で切り替えるネイティブ連携
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capability {
+    /// feature 名
+    pub name: &'static str,
+    /// このビルドに組み込まれているか
+    pub enabled: bool,
+}
+
+/// このビルドのネイティブ連携の一覧
+pub const CAPABILITIES: [Capability; 3] = [
+    Capability {
+        name: "media-control",
+        enabled: cfg!(feature = "media-control"),
+    },
+    Capability {
+        name: "native-sounds",
+        enabled: cfg!(feature = "native-sounds"),
+    },
+    Capability {
+        name: "accessibility-input",
+        enabled: cfg!(feature = "accessibility-input"),
+    },
+];
+
+/// ヘルスチェック用の 1 行に整形する
+pub fn format_line(capabilities: &[Capability]) -> String {
+    let entries: Vec<String> = capabilities
+        .iter()
+        .map(|capability| {
+            format!(
+                "{}={}",
+                capability.name,
+                if capability.enabled { "on" } else { "off" }
+            )
+        })
+        .collect();
+    format!("Capabilities: {}", entries.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 組み込みの有無を feature 名ごとに並べる
+    #[test]
+    fn format_line_lists_each_capability() {
+        let capabilities = [
+            Capability {
+                name: "media-control",
+                enabled: true,
+            },
+            Capability {
+                name: "accessibility-input",
+                enabled: false,
+            },
+        ];
+
+        assert_eq!(
+            format_line(&capabilities),
+            "Capabilities: media-control=on accessibility-input=off"
+        );
+    }
+}
@@ -8,28 +8,53 @@
 #![allow(clippy::await_holding_refcell_ref)]
 
 use std::cell::RefCell;
+use std::path::Path;
 use std::rc::Rc;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, oneshot};
 use tokio::task::spawn_local;
-use tokio::time::Duration;
 
-use crate::application::{RecordedAudio, RecordingOptions, RecordingService, TranscriptionService};
+use crate::application::{
+    AudioData, DiffReply, RecordedAudio, RecordingConfig, RecordingOptions, RecordingService,
+    TranscriptionService,
+};
+use crate::domain::input::TrailingAction;
 use crate::error::{Result, VoiceInputError};
 use crate::infrastructure::{
     audio::{AudioBackend, CpalAudioBackend},
-    external::sound::{play_start_sound, play_stop_sound},
+    audio_cache::AudioCache,
+    capabilities,
+    external::{
+        openai::{OpenAiClient, OpenAiError},
+        sound::{play_start_sound, play_stop_sound},
+    },
+    ipc_audit,
+    last_error::{self, Subsystem},
     media_control_service::MediaControlService,
+    startup,
 };
-use crate::ipc::{IpcCmd, IpcResp};
-use crate::utils::config::EnvConfig;
+use crate::ipc::{IpcCmd, IpcResp, Verbosity};
+use crate::utils::config::{EnvConfig, ScreenLockAction};
+use crate::utils::env::read_env_file;
 use crate::utils::profiling;
 
 /// 転写メッセージ
-#[derive(Clone, Debug)]
+#[derive(Debug)]
 pub struct TranscriptionMessage {
     pub result: RecordedAudio,
     pub resume_music: bool,
     pub session_id: u64,
+    /// 入力前に転写結果を読み上げる
+    pub readback: bool,
+    /// 転写ログへ残すセッションラベル
+    pub label: Option<String>,
+    /// 入力後に続けて行う動作
+    pub then: TrailingAction,
+    /// 転写結果を入力せず音声コマンドとして解釈する
+    pub voice_command: bool,
+    /// `--show-diff` 指定時に後処理差分を返す先
+    pub diff_reply: Option<DiffReply>,
+    /// 転写を依頼したリクエストの出力の詳しさ（転写ワーカーで計測ログに適用する）
+    pub verbosity: Verbosity,
 }
 
 /// コマンドハンドラー
@@ -39,6 +64,8 @@ pub struct CommandHandler<T: AudioBackend> {
     transcription: Rc<RefCell<TranscriptionService>>,
     media_control: Rc<RefCell<MediaControlService>>,
     transcription_tx: mpsc::UnboundedSender<TranscriptionMessage>,
+    /// 画面ロックで止めた録音のうち、ロック解除後に転写するもの
+    held_until_unlock: RefCell<Option<TranscriptionMessage>>,
 }
 
 impl<T: AudioBackend + 'static> CommandHandler<T> {
@@ -54,38 +81,197 @@ impl<T: AudioBackend + 'static> CommandHandler<T> {
             transcription,
             media_control,
             transcription_tx,
+            held_until_unlock: RefCell::new(None),
+        }
+    }
+
+    /// 録音中に画面がロックされた場合の処理
+    ///
+    /// 離席中の音声を転写・入力しないよう、設定に応じて破棄するかロック解除まで保留する。
+    pub async fn handle_screen_locked(&self, action: ScreenLockAction) -> Result<()> {
+        if action == ScreenLockAction::Ignore || !self.recording.borrow().is_recording() {
+            return Ok(());
+        }
+
+        match action {
+            ScreenLockAction::Ignore => {}
+            ScreenLockAction::Discard => {
+                self.handle_cancel().await?;
+                println!("Screen locked while recording; audio discarded");
+            }
+            ScreenLockAction::TranscribeOnUnlock => {
+                let outcome = self
+                    .recording
+                    .borrow()
+                    .stop_recording()
+                    .await
+                    .inspect_err(record_audio_error)?;
+                *self.held_until_unlock.borrow_mut() = Some(TranscriptionMessage {
+                    result: outcome.result,
+                    resume_music: outcome.context.music_was_playing,
+                    session_id: outcome.context.session_id,
+                    readback: outcome.context.readback,
+                    label: outcome.context.label,
+                    then: outcome.context.then,
+                    voice_command: outcome.context.voice_command,
+                    diff_reply: None,
+                    verbosity: profiling::request_verbosity(),
+                });
+                println!("Screen locked while recording; transcription held until unlock");
+            }
         }
+        Ok(())
+    }
+
+    /// 画面ロック解除時に保留していた転写をキューへ送る
+    pub fn handle_screen_unlocked(&self) -> Result<()> {
+        let Some(message) = self.held_until_unlock.borrow_mut().take() else {
+            return Ok(());
+        };
+
+        self.transcription_tx.send(message).map_err(|e| {
+            VoiceInputError::SystemError(format!("Failed to send to transcription queue: {}", e))
+        })
+    }
+
+    /// ロック解除待ちの転写があるか
+    pub fn has_held_transcription(&self) -> bool {
+        self.held_until_unlock.borrow().is_some()
     }
 
     /// IPCコマンドを処理
     pub async fn handle(&self, cmd: IpcCmd) -> Result<IpcResp> {
         match cmd {
-            IpcCmd::Start { prompt } => self.handle_start(prompt).await,
-            IpcCmd::Stop => self.handle_stop().await,
-            IpcCmd::Toggle { prompt } => {
+            IpcCmd::Start {
+                prompt,
+                readback,
+                label,
+                then,
+            } => {
+                self.handle_start(RecordingOptions {
+                    prompt,
+                    readback,
+                    label,
+                    then,
+                    voice_command: false,
+                })
+                .await
+            }
+            IpcCmd::StartVoiceCommand => {
+                self.handle_start(RecordingOptions {
+                    voice_command: true,
+                    ..RecordingOptions::default()
+                })
+                .await
+            }
+            IpcCmd::Stop => self.handle_stop(None).await,
+            IpcCmd::CancelRecording => self.handle_cancel().await,
+            IpcCmd::Toggle {
+                prompt,
+                readback,
+                then,
+            } => {
                 if self.recording.borrow().is_recording() {
-                    self.handle_stop().await
+                    self.handle_stop(None).await
                 } else {
-                    self.handle_start(prompt).await
+                    self.handle_start(RecordingOptions {
+                        prompt,
+                        readback,
+                        then,
+                        ..RecordingOptions::default()
+                    })
+                    .await
                 }
             }
+            IpcCmd::TranscribeFile { path } => self.handle_transcribe_file(&path, None).await,
+            IpcCmd::TranscribeStream => self.handle_transcribe_stream(Vec::new(), None),
+            IpcCmd::RetryLast => self.handle_retry_last(None),
             IpcCmd::Status => self.handle_status(),
+            IpcCmd::StatusVerbose => self.handle_status_verbose(),
             IpcCmd::ListDevices => self.handle_list_devices(),
             IpcCmd::Health => self.handle_health().await,
+            IpcCmd::Audit => Ok(IpcResp {
+                ok: true,
+                msg: ipc_audit::snapshot().format_lines().join("\n"),
+            }),
+            IpcCmd::ReloadConfig => self.handle_reload_config(),
+            IpcCmd::WithVerbosity { verbosity, cmd } => {
+                Box::pin(profiling::scope_request(verbosity, async move {
+                    profiling::log_point("ipc.request", &format!("cmd={:?}", cmd));
+                    self.handle(*cmd).await
+                }))
+                .await
+            }
+            IpcCmd::WithDiff { cmd } => self.handle_with_diff(*cmd, Vec::new()).await,
+        }
+    }
+
+    /// コマンド行に続けて音声を受け取るコマンドを処理
+    ///
+    /// 本文を伴わないコマンドは `handle` と同じように処理する。
+    pub async fn handle_with_body(&self, cmd: IpcCmd, body: Vec<u8>) -> Result<IpcResp> {
+        match cmd {
+            IpcCmd::TranscribeStream => self.handle_transcribe_stream(body, None),
+            IpcCmd::WithVerbosity { verbosity, cmd } => {
+                Box::pin(profiling::scope_request(verbosity, async move {
+                    profiling::log_point("ipc.request", &format!("cmd={:?}", cmd));
+                    self.handle_with_body(*cmd, body).await
+                }))
+                .await
+            }
+            IpcCmd::WithDiff { cmd } => self.handle_with_diff(*cmd, body).await,
+            cmd => self.handle(cmd).await,
         }
     }
 
+    /// 転写を伴うコマンドを実行し、その転写の完了を待って後処理差分を応答に添える
+    ///
+    /// 差分は返信用チャネルで当該転写だけから受け取るため、同時に走る別の転写と混ざらない。
+    /// 短すぎる録音の破棄や転写失敗で差分がない場合は、その理由を添える。
+    async fn handle_with_diff(&self, cmd: IpcCmd, body: Vec<u8>) -> Result<IpcResp> {
+        let (diff_tx, diff_rx) = oneshot::channel();
+        let resp = match cmd {
+            IpcCmd::Stop => self.handle_stop(Some(diff_tx)).await?,
+            IpcCmd::TranscribeFile { path } => {
+                self.handle_transcribe_file(&path, Some(diff_tx)).await?
+            }
+            IpcCmd::TranscribeStream => self.handle_transcribe_stream(body, Some(diff_tx))?,
+            IpcCmd::RetryLast => self.handle_retry_last(Some(diff_tx))?,
+            cmd => {
+                return Ok(IpcResp {
+                    ok: false,
+                    msg: format!("{} does not start a transcription; no diff", cmd.name()),
+                });
+            }
+        };
+        if !resp.ok {
+            return Ok(resp);
+        }
+
+        let mut lines = vec![resp.msg];
+        match diff_rx.await {
+            Ok(Ok(diff)) => lines.extend(diff.format_lines()),
+            Ok(Err(reason)) => lines.push(format!("(no diff: {})", reason)),
+            Err(_) => lines.push("(no diff: no transcription was run)".to_string()),
+        }
+        Ok(IpcResp {
+            ok: true,
+            msg: lines.join("\n"),
+        })
+    }
+
     /// 録音開始処理
-    async fn handle_start(&self, prompt: Option<String>) -> Result<IpcResp> {
+    async fn handle_start(&self, options: RecordingOptions) -> Result<IpcResp> {
         // 体感開始時間を縮めるため、開始音は録音開始前に鳴らす
         play_start_sound();
 
-        // 録音オプションを構築
-        let options = RecordingOptions { prompt };
-
         // 録音を開始
         let recording = self.recording.clone();
-        let session_id = recording.borrow().start_recording(options).await?;
+        let session_id = recording
+            .borrow()
+            .start_recording(options)
+            .await
+            .inspect_err(record_audio_error)?;
 
         // Apple Music の pause は録音開始後に非同期で行う
         self.spawn_pause_if_needed(session_id);
@@ -146,21 +332,56 @@ impl<T: AudioBackend + 'static> CommandHandler<T> {
     }
 
     /// 録音停止処理
-    async fn handle_stop(&self) -> Result<IpcResp> {
+    async fn handle_stop(&self, diff_reply: Option<DiffReply>) -> Result<IpcResp> {
         // 停止音を再生
         play_stop_sound();
 
         // 録音を停止
         let recording = self.recording.clone();
-        let outcome = recording.borrow().stop_recording().await?;
+        let outcome = recording
+            .borrow()
+            .stop_recording()
+            .await
+            .inspect_err(record_audio_error)?;
         let audio_bytes = outcome.result.audio_data.bytes.len();
 
+        // 誤タップ由来の短い録音は API を呼ばずに破棄する
+        let duration_ms = outcome.result.duration_ms;
+        let recording_config = recording.borrow().config().clone();
+        if recording_config.is_too_short(duration_ms) {
+            let session_id = outcome.context.session_id;
+            let media_control = self.media_control.clone();
+            spawn_local(async move {
+                let _ = media_control
+                    .borrow()
+                    .resume_if_paused_for_session(session_id)
+                    .await;
+            });
+            eprintln!(
+                "Recording discarded as too short ({}ms < {}ms)",
+                duration_ms, recording_config.min_duration_ms
+            );
+            return Ok(IpcResp {
+                ok: true,
+                msg: format!(
+                    "recording too short ({}ms < {}ms); discarded",
+                    duration_ms, recording_config.min_duration_ms
+                ),
+            });
+        }
+
         // 転写キューに送信
         self.transcription_tx
             .send(TranscriptionMessage {
                 result: outcome.result,
                 resume_music: outcome.context.music_was_playing,
                 session_id: outcome.context.session_id,
+                readback: outcome.context.readback,
+                label: outcome.context.label,
+                then: outcome.context.then,
+                voice_command: outcome.context.voice_command,
+                diff_reply,
+                verbosity: profiling::request_verbosity(),
             })
             .map_err(|e| {
                 VoiceInputError::SystemError(format!(
@@ -179,17 +400,162 @@ impl<T: AudioBackend + 'static> CommandHandler<T> {
         })
     }
 
+    /// 音声ファイル転写処理（録音を経ずに転写キューへ送る）
+    async fn handle_transcribe_file(
+        &self,
+        path: &Path,
+        diff_reply: Option<DiffReply>,
+    ) -> Result<IpcResp> {
+        let bytes = tokio::fs::read(path).await.map_err(|e| {
+            VoiceInputError::SystemError(format!(
+                "Failed to read audio file {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        let audio_data = AudioData::from_file(path, bytes).ok_or_else(|| {
+            VoiceInputError::SystemError(format!("Unsupported audio file type: {}", path.display()))
+        })?;
+        self.queue_transcription(audio_data, diff_reply)?;
+
+        Ok(IpcResp {
+            ok: true,
+            msg: format!("transcribing {}; queued", path.display()),
+        })
+    }
+
+    /// コマンド行に続けて受け取った音声を転写キューへ送る
+    fn handle_transcribe_stream(
+        &self,
+        body: Vec<u8>,
+        diff_reply: Option<DiffReply>,
+    ) -> Result<IpcResp> {
+        let size = body.len();
+        let audio_data = AudioData::from_stream_bytes(body).ok_or_else(|| {
+            VoiceInputError::SystemError(
+                "Unsupported audio stream: expected WAV or FLAC data".to_string(),
+            )
+        })?;
+        self.queue_transcription(audio_data, diff_reply)?;
+
+        Ok(IpcResp {
+            ok: true,
+            msg: format!("transcribing {} bytes from stdin; queued", size),
+        })
+    }
+
+    /// キャッシュに残っている直近の録音を転写キューへ送る
+    fn handle_retry_last(&self, diff_reply: Option<DiffReply>) -> Result<IpcResp> {
+        let cached = AudioCache::open_default().latest().map_err(|e| {
+            VoiceInputError::SystemError(format!("Failed to read audio cache: {}", e))
+        })?;
+        let Some(cached) = cached else {
+            let msg = if EnvConfig::get().audio.cache_entries == 0 {
+                "no cached recording to retry; set VOICE_INPUT_AUDIO_CACHE_ENTRIES to keep recordings"
+            } else {
+                "no cached recording to retry"
+            };
+            return Ok(IpcResp {
+                ok: false,
+                msg: msg.to_string(),
+            });
+        };
+        let size = cached.audio.bytes.len();
+        self.queue_transcription(cached.audio, diff_reply)?;
+
+        Ok(IpcResp {
+            ok: true,
+            msg: format!(
+                "retrying recording from session {} ({} bytes); queued",
+                cached.session_id, size
+            ),
+        })
+    }
+
+    /// 録音を伴わない音声を転写キューへ送る
+    fn queue_transcription(
+        &self,
+        audio_data: AudioData,
+        diff_reply: Option<DiffReply>,
+    ) -> Result<()> {
+        // 後続の録音が始まった場合に低信頼語選択を抑止できるよう直近セッションへ紐付ける
+        let session_id = self.recording.borrow().latest_session_id()?;
+        self.transcription_tx
+            .send(TranscriptionMessage {
+                result: RecordedAudio {
+                    audio_data,
+                    duration_ms: 0,
+                    snr_db: None,
+                },
+                resume_music: false,
+                session_id,
+                readback: false,
+                label: None,
+                then: TrailingAction::None,
+                voice_command: false,
+                diff_reply,
+                verbosity: profiling::request_verbosity(),
+            })
+            .map_err(|e| {
+                VoiceInputError::SystemError(format!(
+                    "Failed to send to transcription queue: {}",
+                    e
+                ))
+            })
+    }
+
+    /// 録音中止処理（転写キューへは送らない）
+    async fn handle_cancel(&self) -> Result<IpcResp> {
+        let context = self
+            .recording
+            .borrow()
+            .cancel_recording()
+            .await
+            .inspect_err(record_audio_error)?;
+
+        let media_control = self.media_control.clone();
+        spawn_local(async move {
+            let _ = media_control
+                .borrow()
+                .resume_if_paused_for_session(context.session_id)
+                .await;
+        });
+
+        Ok(IpcResp {
+            ok: true,
+            msg: "recording cancelled; audio discarded".to_string(),
+        })
+    }
+
     /// ステータス取得
     fn handle_status(&self) -> Result<IpcResp> {
-        let state = if self.recording.borrow().is_recording() {
-            "Recording"
+        let recording = self.recording.borrow();
+        let msg = if recording.is_recording() {
+            let mut parts = vec!["state=Recording".to_string()];
+            if let Some(label) = recording.active_label()? {
+                parts.push(format!("label={:?}", label));
+            }
+            let extended_secs = recording.auto_stop_extended_secs()?;
+            if extended_secs > 0 {
+                parts.push(format!("auto_stop_extended={}s", extended_secs));
+            }
+            parts.join(" ")
         } else {
-            "Idle"
+            "state=Idle".to_string()
         };
 
+        Ok(IpcResp { ok: true, msg })
+    }
+
+    /// サブシステム別の直近エラーを含むステータス取得
+    fn handle_status_verbose(&self) -> Result<IpcResp> {
+        let status = self.handle_status()?;
+        let mut lines = vec![status.msg];
+        lines.extend(last_error::snapshot().format_lines());
+
         Ok(IpcResp {
             ok: true,
-            msg: format!("state={}", state),
+            msg: lines.join("\n"),
         })
     }
 
@@ -222,22 +588,23 @@ impl<T: AudioBackend + 'static> CommandHandler<T> {
         let transcription = &EnvConfig::get().transcription;
         match transcription.provider {
             crate::utils::config::TranscriptionProvider::OpenAi => {
-                match transcription.api_key.clone() {
-                    Some(key) => {
+                match transcription.api_key.as_ref() {
+                    Some(_) => {
                         lines.push("TRANSCRIPTION_PROVIDER: openai".to_string());
                         lines.push("TRANSCRIPTION_API_KEY: present".to_string());
-                        let client = reqwest::Client::new();
-                        match client
-                            .get("https://api.openai.com/v1/models")
-                            .bearer_auth(key)
-                            .send()
-                            .await
-                        {
-                            Ok(resp) if resp.status().is_success() => {
+                        // 転写と同じベースURL・組織ヘッダ・タイムアウトで疎通を確かめる
+                        let probe =
+                            match OpenAiClient::from_config(transcription, &EnvConfig::get().proxy)
+                            {
+                                Ok(client) => client.check_models().await,
+                                Err(e) => Err(e),
+                            };
+                        match probe {
+                            Ok(()) => {
                                 lines.push("OpenAI API: reachable".to_string());
                             }
-                            Ok(resp) => {
-                                lines.push(format!("OpenAI API: fail({})", resp.status()));
+                            Err(OpenAiError::ApiStatus { status, .. }) => {
+                                lines.push(format!("OpenAI API: fail({})", status));
                                 ok = false;
                             }
                             Err(e) => {
@@ -281,12 +648,63 @@ impl<T: AudioBackend + 'static> CommandHandler<T> {
             }
         }
 
+        if let Some(race) = transcription.race_provider {
+            lines.push(format!(
+                "Race mode: {} vs {} (experimental; API usage roughly doubles)",
+                transcription.provider.as_str(),
+                race.as_str()
+            ));
+        }
+
+        lines.push(capabilities::format_line(&capabilities::CAPABILITIES));
+        lines.extend(startup::snapshot().format_lines());
+
         Ok(IpcResp {
             ok,
             msg: lines.join("\n"),
         })
     }
 
+    /// 環境変数ファイルを読み直し、実行中に反映できる設定を差し替える
+    ///
+    /// グローバル設定と録音サービスの設定は両方まとめて差し替え、
+    /// 録音サービスが使用中なら何も変更しない。
+    fn handle_reload_config(&self) -> Result<IpcResp> {
+        let Ok(mut recording) = self.recording.try_borrow_mut() else {
+            return Ok(IpcResp {
+                ok: false,
+                msg: "config reload skipped: recording service busy; run reload again".to_string(),
+            });
+        };
+        let (config, changes) = match EnvConfig::prepare_reload(read_env_file()) {
+            Ok(prepared) => prepared,
+            Err(e) => {
+                return Ok(IpcResp {
+                    ok: false,
+                    msg: format!("config reload failed; keeping current settings: {}", e),
+                });
+            }
+        };
+
+        // 録音時間の上限・下限は録音サービスが保持しているため同時に差し替える
+        recording.config = RecordingConfig {
+            max_duration_secs: config.recording.max_duration_secs,
+            min_duration_ms: config.recording.min_duration_ms,
+            max_extension_secs: config.recording.max_extension_secs,
+        };
+        EnvConfig::replace(config);
+
+        let lines = changes.format_lines();
+        for line in &lines {
+            println!("Config reload: {}", line);
+        }
+
+        Ok(IpcResp {
+            ok: true,
+            msg: lines.join("\n"),
+        })
+    }
+
     /// 自動停止タイマーをセットアップ
     fn setup_auto_stop_timer(&self) {
         let recording = self.recording.clone();
@@ -299,18 +717,27 @@ impl<T: AudioBackend + 'static> CommandHandler<T> {
 
             if let Some(cancel_rx) = cancel_rx {
                 tokio::select! {
-                    _ = tokio::time::sleep(Duration::from_secs(max_secs)) => {
-                        // 30秒経過による自動停止
+                    _ = wait_for_auto_stop(&recording) => {
+                        // 最大録音時間（発話中の延長を含む）経過による自動停止
                         if recording.borrow().is_recording() {
                             println!("Auto-stop timer triggered after {}s", max_secs);
                             play_stop_sound();
 
-                            if let Ok(outcome) = recording.borrow().stop_recording().await {
-                                let _ = tx.send(TranscriptionMessage {
-                                    result: outcome.result,
-                                    resume_music: outcome.context.music_was_playing,
-                                    session_id: outcome.context.session_id,
-                                });
+                            match recording.borrow().stop_recording().await {
+                                Ok(outcome) => {
+                                    let _ = tx.send(TranscriptionMessage {
+                                        result: outcome.result,
+                                        resume_music: outcome.context.music_was_playing,
+                                        session_id: outcome.context.session_id,
+                                        readback: outcome.context.readback,
+                                        label: outcome.context.label,
+                                        then: outcome.context.then,
+                                        voice_command: outcome.context.voice_command,
+                                        diff_reply: None,
+                                        verbosity: Verbosity::Normal,
+                                    });
+                                }
+                                Err(err) => record_audio_error(&err),
                             }
                         }
                     }
@@ -326,12 +753,65 @@ impl<T: AudioBackend + 'static> CommandHandler<T> {
     }
 }
 
+/// 最大録音時間まで待ち、発話が続いている間は上限まで小刻みに延長する
+///
+/// 期限は `RecordingService` に注入した時計で判定する。既定の時計は tokio の単調時計
+/// （macOS では `CLOCK_UPTIME_RAW`）で、システムのスリープ中に進まないため、スリープして
+/// いる間は期限までの残り時間も減らず、復帰後に録音時間を使い切ったとみなして即座に止める
+/// ことはない。
+async fn wait_for_auto_stop<T: AudioBackend>(recording: &Rc<RefCell<RecordingService<T>>>) {
+    loop {
+        let Ok(remaining) = recording.borrow().auto_stop_remaining() else {
+            return;
+        };
+        if !remaining.is_zero() {
+            tokio::time::sleep(remaining).await;
+            continue;
+        }
+
+        let (step, total) = {
+            let service = recording.borrow();
+            if !service.is_speech_active() {
+                return;
+            }
+            let Ok(extended) = service.auto_stop_extended_secs() else {
+                return;
+            };
+            let Some(step) = service.config().next_extension_secs(extended) else {
+                return;
+            };
+            let Ok(total) = service.record_auto_stop_extension(step) else {
+                return;
+            };
+            (step, total)
+        };
+
+        println!(
+            "Speech still active; auto-stop extended by {}s (total {}s)",
+            step, total
+        );
+        profiling::log_point(
+            "recording.auto_stop_extended",
+            &format!("step_secs={} total_secs={}", step, total),
+        );
+    }
+}
+
+/// 録音状態の不一致ではなく音声取得側の失敗だけを直近エラーとして記録する
+fn record_audio_error(err: &VoiceInputError) {
+    if matches!(
+        err,
+        VoiceInputError::AudioBackendError(_) | VoiceInputError::NoAudioCaptured(_)
+    ) {
+        last_error::record(Subsystem::Audio, err.to_string());
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::application::RecordingConfig;
     use crate::application::TranscriptionClient;
-    use crate::application::{AudioData, DictRepository, Recorder};
+    use crate::application::{DictRepository, Recorder};
     use crate::domain::dict::WordEntry;
     use crate::domain::transcription::TranscriptionOutput;
     use crate::infrastructure::external::sound::{clear_test_sound_runner, set_test_sound_runner};
@@ -343,6 +823,7 @@ mod tests {
     use std::sync::Mutex as StdMutex;
     use std::sync::atomic::{AtomicBool, Ordering};
     use std::time::Instant;
+    use tokio::time::Duration;
 
     static SOUND_TEST_LOCK: StdMutex<()> = StdMutex::new(());
 
@@ -366,6 +847,7 @@ mod tests {
             &self,
             _audio: AudioData,
             _language: &str,
+            _prompt: Option<&str>,
         ) -> crate::error::Result<TranscriptionOutput> {
             Ok(TranscriptionOutput::from_text(String::new()))
         }
@@ -454,6 +936,34 @@ mod tests {
         }
     }
 
+    /// 常に発話中と報告するバックエンド
+    struct SpeakingBackend {
+        inner: RecordingOrderBackend,
+    }
+
+    impl AudioBackend for SpeakingBackend {
+        fn start_recording(
+            &self,
+        ) -> std::result::Result<(), crate::infrastructure::audio::AudioBackendError> {
+            self.inner.start_recording()
+        }
+
+        fn stop_recording(
+            &self,
+        ) -> std::result::Result<AudioData, crate::infrastructure::audio::AudioBackendError>
+        {
+            self.inner.stop_recording()
+        }
+
+        fn is_recording(&self) -> bool {
+            self.inner.is_recording()
+        }
+
+        fn is_speech_active(&self) -> bool {
+            true
+        }
+    }
+
     struct TimingObservedBackend<T: AudioBackend> {
         inner: T,
         started_at: Arc<StdMutex<Option<Instant>>>,
@@ -598,6 +1108,8 @@ mod tests {
             recorder,
             RecordingConfig {
                 max_duration_secs: 30,
+                min_duration_ms: 0,
+                max_extension_secs: 0,
             },
         )));
         let transcription = Rc::new(RefCell::new(TranscriptionService::new(
@@ -616,6 +1128,221 @@ mod tests {
         )
     }
 
+    /// 発話が続く間は自動停止を延長し、上限に達したら延長をやめてステータスに残す
+    #[tokio::test(flavor = "current_thread")]
+    async fn auto_stop_extends_while_speaking_up_to_cap() {
+        let recorder = Rc::new(RefCell::new(Recorder::new(SpeakingBackend {
+            inner: RecordingOrderBackend::new(Arc::new(StdMutex::new(Vec::new()))),
+        })));
+        let recording = Rc::new(RefCell::new(RecordingService::new(
+            recorder,
+            RecordingConfig {
+                max_duration_secs: 0,
+                min_duration_ms: 0,
+                max_extension_secs: 1,
+            },
+        )));
+        recording
+            .borrow()
+            .start_recording(RecordingOptions::default())
+            .await
+            .unwrap();
+
+        tokio::time::timeout(Duration::from_secs(5), wait_for_auto_stop(&recording))
+            .await
+            .expect("extension should stop at the cap");
+
+        assert_eq!(recording.borrow().auto_stop_extended_secs().unwrap(), 1);
+        let transcription = Rc::new(RefCell::new(TranscriptionService::new(
+            Box::new(NoopTranscriptionClient),
+            Box::new(NoopDictRepository),
+            1,
+        )));
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let handler = CommandHandler::new(
+            recording.clone(),
+            transcription,
+            Rc::new(RefCell::new(MediaControlService::new())),
+            tx,
+        );
+        let status = handler.handle(IpcCmd::Status).await.unwrap();
+        assert_eq!(status.msg, "state=Recording auto_stop_extended=1s");
+    }
+
+    /// 最大録音時間では発話中のため止めず、1 回分の延長を過ぎたところで自動停止する
+    #[tokio::test(flavor = "current_thread", start_paused = true)]
+    async fn auto_stop_fires_after_max_duration_plus_one_extension() {
+        EnvConfig::test_init();
+        let recorder = Rc::new(RefCell::new(Recorder::new(SpeakingBackend {
+            inner: RecordingOrderBackend::new(Arc::new(StdMutex::new(Vec::new()))),
+        })));
+        let recording = Rc::new(RefCell::new(RecordingService::new(
+            recorder,
+            RecordingConfig {
+                max_duration_secs: 5,
+                min_duration_ms: 0,
+                max_extension_secs: RecordingConfig::AUTO_STOP_EXTENSION_STEP_SECS,
+            },
+        )));
+        recording
+            .borrow()
+            .start_recording(RecordingOptions::default())
+            .await
+            .unwrap();
+
+        let local = tokio::task::LocalSet::new();
+        local
+            .run_until(async {
+                let waiter = {
+                    let recording = recording.clone();
+                    tokio::task::spawn_local(async move {
+                        wait_for_auto_stop(&recording).await;
+                    })
+                };
+                let settle = || async {
+                    for _ in 0..3 {
+                        tokio::task::yield_now().await;
+                    }
+                };
+                settle().await;
+
+                tokio::time::advance(Duration::from_millis(5_001)).await;
+                settle().await;
+                assert!(!waiter.is_finished(), "speech should extend past max_secs");
+                assert_eq!(recording.borrow().auto_stop_extended_secs().unwrap(), 2);
+
+                tokio::time::advance(Duration::from_millis(1_998)).await;
+                settle().await;
+                assert!(!waiter.is_finished(), "stop should wait for the extension");
+
+                tokio::time::advance(Duration::from_millis(2)).await;
+                settle().await;
+                assert!(
+                    waiter.is_finished(),
+                    "stop should fire once the extension ends"
+                );
+            })
+            .await;
+    }
+
+    /// 破棄設定では画面ロック時に録音を中止し、転写しない
+    #[tokio::test(flavor = "current_thread")]
+    async fn screen_lock_discards_recording_by_default() {
+        let local = tokio::task::LocalSet::new();
+        local
+            .run_until(async {
+                let backend = RecordingOrderBackend::new(Arc::new(StdMutex::new(Vec::new())));
+                let (handler, recording, _media_control, mut rx) =
+                    build_handler(backend, MediaControlService::new());
+                recording
+                    .borrow()
+                    .start_recording(RecordingOptions::default())
+                    .await
+                    .unwrap();
+
+                handler
+                    .handle_screen_locked(ScreenLockAction::Discard)
+                    .await
+                    .unwrap();
+                handler.handle_screen_unlocked().unwrap();
+
+                assert!(!recording.borrow().is_recording());
+                assert!(rx.try_recv().is_err());
+            })
+            .await;
+    }
+
+    /// 解除後転写の設定では画面ロック中は保留し、ロック解除で転写キューへ送る
+    #[tokio::test(flavor = "current_thread")]
+    async fn screen_lock_holds_transcription_until_unlock() {
+        let local = tokio::task::LocalSet::new();
+        local
+            .run_until(async {
+                let backend = RecordingOrderBackend::new(Arc::new(StdMutex::new(Vec::new())));
+                let (handler, recording, _media_control, mut rx) =
+                    build_handler(backend, MediaControlService::new());
+                recording
+                    .borrow()
+                    .start_recording(RecordingOptions {
+                        label: Some("離席前".to_string()),
+                        ..RecordingOptions::default()
+                    })
+                    .await
+                    .unwrap();
+
+                handler
+                    .handle_screen_locked(ScreenLockAction::TranscribeOnUnlock)
+                    .await
+                    .unwrap();
+
+                assert!(!recording.borrow().is_recording());
+                assert!(handler.has_held_transcription());
+                assert!(rx.try_recv().is_err());
+
+                handler.handle_screen_unlocked().unwrap();
+
+                let message = rx.try_recv().expect("held transcription should be queued");
+                assert_eq!(message.label.as_deref(), Some("離席前"));
+                assert!(!handler.has_held_transcription());
+            })
+            .await;
+    }
+
+    /// 転写を伴わないコマンドには差分を返さない
+    #[tokio::test(flavor = "current_thread")]
+    async fn with_diff_rejects_commands_without_transcription() {
+        let backend = RecordingOrderBackend::new(Arc::new(StdMutex::new(Vec::new())));
+        let (handler, ..) = build_handler(backend, MediaControlService::new());
+
+        let resp = handler
+            .handle(IpcCmd::Status.with_diff(true))
+            .await
+            .unwrap();
+
+        assert!(!resp.ok);
+        assert_eq!(resp.msg, "Status does not start a transcription; no diff");
+    }
+
+    /// 差分は停止した録音の転写から受け取り、返信を待って応答に添える
+    #[tokio::test(flavor = "current_thread")]
+    #[allow(clippy::await_holding_lock)]
+    async fn with_diff_waits_for_reply_of_stopped_session() {
+        let _sound_guard = SOUND_TEST_LOCK.lock().unwrap();
+        let local = tokio::task::LocalSet::new();
+        local
+            .run_until(async {
+                let backend = RecordingOrderBackend::new(Arc::new(StdMutex::new(Vec::new())));
+                let (handler, _recording, _media_control, mut rx) =
+                    build_handler(backend, MediaControlService::new());
+                handler
+                    .handle(IpcCmd::Start {
+                        prompt: None,
+                        readback: false,
+                        label: None,
+                        then: TrailingAction::None,
+                    })
+                    .await
+                    .unwrap();
+
+                let worker = spawn_local(async move {
+                    let message = rx.recv().await.expect("transcription should be queued");
+                    let _ = message
+                        .diff_reply
+                        .expect("diff reply should be attached")
+                        .send(Err("transcription failed: boom".to_string()));
+                });
+                let resp = handler.handle(IpcCmd::Stop.with_diff(true)).await.unwrap();
+                worker.await.unwrap();
+
+                assert!(resp.ok);
+                assert_eq!(
+                    resp.msg,
+                    "recording stopped; queued\n(no diff: transcription failed: boom)"
+                );
+            })
+            .await;
+    }
+
     /// 停止時に転写キューへsession_id付きで送信される
     #[tokio::test(flavor = "current_thread")]
     async fn stop_enqueues_transcription_message_with_session_id() {
@@ -631,7 +1358,12 @@ mod tests {
                     build_handler(backend, media_control);
 
                 handler
-                    .handle(IpcCmd::Start { prompt: None })
+                    .handle(IpcCmd::Start {
+                        prompt: None,
+                        readback: false,
+                        label: None,
+                        then: TrailingAction::None,
+                    })
                     .await
                     .unwrap();
                 handler.handle(IpcCmd::Stop).await.unwrap();
@@ -659,7 +1391,12 @@ mod tests {
 
                 let response = tokio::time::timeout(
                     Duration::from_millis(50),
-                    handler.handle(IpcCmd::Start { prompt: None }),
+                    handler.handle(IpcCmd::Start {
+                        prompt: None,
+                        readback: false,
+                        label: None,
+                        then: TrailingAction::None,
+                    }),
                 )
                 .await;
 
@@ -694,7 +1431,12 @@ mod tests {
                     build_handler(backend, media_control);
 
                 handler
-                    .handle(IpcCmd::Start { prompt: None })
+                    .handle(IpcCmd::Start {
+                        prompt: None,
+                        readback: false,
+                        label: None,
+                        then: TrailingAction::None,
+                    })
                     .await
                     .unwrap();
             })
@@ -733,7 +1475,12 @@ mod tests {
                     build_handler(backend, media_control);
 
                 handler
-                    .handle(IpcCmd::Start { prompt: None })
+                    .handle(IpcCmd::Start {
+                        prompt: None,
+                        readback: false,
+                        label: None,
+                        then: TrailingAction::None,
+                    })
                     .await
                     .unwrap();
             })
@@ -779,7 +1526,12 @@ mod tests {
                     build_handler(backend, media_control);
 
                 handler
-                    .handle(IpcCmd::Start { prompt: None })
+                    .handle(IpcCmd::Start {
+                        prompt: None,
+                        readback: false,
+                        label: None,
+                        then: TrailingAction::None,
+                    })
                     .await
                     .unwrap();
             })
@@ -810,7 +1562,12 @@ mod tests {
                     build_handler(backend, media_control);
 
                 handler
-                    .handle(IpcCmd::Start { prompt: None })
+                    .handle(IpcCmd::Start {
+                        prompt: None,
+                        readback: false,
+                        label: None,
+                        then: TrailingAction::None,
+                    })
                     .await
                     .unwrap();
                 handler.handle(IpcCmd::Stop).await.unwrap();
@@ -843,12 +1600,22 @@ mod tests {
                     build_handler(backend, media_control);
 
                 handler
-                    .handle(IpcCmd::Start { prompt: None })
+                    .handle(IpcCmd::Start {
+                        prompt: None,
+                        readback: false,
+                        label: None,
+                        then: TrailingAction::None,
+                    })
                     .await
                     .unwrap();
                 handler.handle(IpcCmd::Stop).await.unwrap();
                 handler
-                    .handle(IpcCmd::Start { prompt: None })
+                    .handle(IpcCmd::Start {
+                        prompt: None,
+                        readback: false,
+                        label: None,
+                        then: TrailingAction::None,
+                    })
                     .await
                     .unwrap();
                 tokio::time::sleep(Duration::from_millis(120)).await;
@@ -880,12 +1647,22 @@ mod tests {
                     build_handler(backend, media_control);
 
                 handler
-                    .handle(IpcCmd::Start { prompt: None })
+                    .handle(IpcCmd::Start {
+                        prompt: None,
+                        readback: false,
+                        label: None,
+                        then: TrailingAction::None,
+                    })
                     .await
                     .unwrap();
                 handler.handle(IpcCmd::Stop).await.unwrap();
                 handler
-                    .handle(IpcCmd::Start { prompt: None })
+                    .handle(IpcCmd::Start {
+                        prompt: None,
+                        readback: false,
+                        label: None,
+                        then: TrailingAction::None,
+                    })
                     .await
                     .unwrap();
                 tokio::time::sleep(Duration::from_millis(160)).await;
@@ -913,7 +1690,12 @@ mod tests {
                     build_handler(backend, media_control);
 
                 let response = handler
-                    .handle(IpcCmd::Start { prompt: None })
+                    .handle(IpcCmd::Start {
+                        prompt: None,
+                        readback: false,
+                        label: None,
+                        then: TrailingAction::None,
+                    })
                     .await
                     .unwrap();
                 tokio::time::sleep(Duration::from_millis(10)).await;
@@ -926,4 +1708,127 @@ mod tests {
             })
             .await;
     }
+
+    /// 詳細ステータスには状態とサブシステム別の直近エラーが含まれる
+    #[tokio::test(flavor = "current_thread")]
+    async fn verbose_status_includes_last_errors() {
+        let backend = RecordingOrderBackend::new(Arc::new(StdMutex::new(Vec::new())));
+        let (handler, _recording, _media_control, _rx) =
+            build_handler(backend, MediaControlService::new());
+        record_audio_error(&VoiceInputError::NoAudioCaptured(
+            "verbose status test".to_string(),
+        ));
+
+        let response = handler.handle(IpcCmd::StatusVerbose).await.unwrap();
+
+        assert!(response.ok);
+        assert!(response.msg.starts_with("state=Idle"));
+        assert!(
+            response
+                .msg
+                .lines()
+                .any(|line| line.starts_with("last_error.audio=")
+                    && line.ends_with("verbose status test"))
+        );
+    }
+
+    /// 録音状態の不一致は音声サブシステムの直近エラーとして扱わない
+    #[test]
+    fn recording_state_errors_are_not_recorded_as_audio_errors() {
+        record_audio_error(&VoiceInputError::RecordingAlreadyActive);
+
+        let after = last_error::snapshot()
+            .get(Subsystem::Audio)
+            .map(|error| error.message.clone());
+        assert_ne!(after.as_deref(), Some("Recording already active"));
+    }
+
+    /// 録音中止は転写キューへ送らず、一時停止した音楽を再開する
+    #[tokio::test(flavor = "current_thread")]
+    async fn cancel_discards_audio_and_resumes_music() {
+        let local = tokio::task::LocalSet::new();
+        local
+            .run_until(async {
+                let backend = RecordingOrderBackend::new(Arc::new(StdMutex::new(Vec::new())));
+                let controller = DelayedMediaController::new(true, Duration::from_millis(0));
+                let playing = controller.playing.clone();
+                let media_control = MediaControlService::with_controller(Box::new(controller));
+                let (handler, recording, media_control, mut rx) =
+                    build_handler(backend, media_control);
+
+                let session_id = recording
+                    .borrow()
+                    .start_recording(RecordingOptions::default())
+                    .await
+                    .unwrap();
+                media_control
+                    .borrow()
+                    .pause_if_playing_for_session(session_id)
+                    .await
+                    .unwrap();
+
+                let response = handler.handle(IpcCmd::CancelRecording).await.unwrap();
+                tokio::time::sleep(Duration::from_millis(10)).await;
+
+                assert!(response.ok);
+                assert!(!recording.borrow().is_recording());
+                assert!(
+                    rx.try_recv().is_err(),
+                    "cancel must not queue transcription"
+                );
+                assert!(playing.load(Ordering::SeqCst));
+            })
+            .await;
+    }
+
+    /// 音声ファイル転写は録音せずにファイル内容を転写キューへ送る
+    #[tokio::test(flavor = "current_thread")]
+    async fn transcribe_file_enqueues_file_audio() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("memo.wav");
+        std::fs::write(&path, [1u8, 2, 3]).unwrap();
+        let backend = RecordingOrderBackend::new(Arc::new(StdMutex::new(Vec::new())));
+        let (handler, recording, _media_control, mut rx) =
+            build_handler(backend, MediaControlService::new());
+
+        let response = handler
+            .handle(IpcCmd::TranscribeFile { path: path.clone() })
+            .await
+            .unwrap();
+
+        assert!(response.ok);
+        assert!(!recording.borrow().is_recording());
+        let message = rx.try_recv().expect("file audio should be queued");
+        assert_eq!(message.result.audio_data.bytes, vec![1, 2, 3]);
+        assert_eq!(message.result.audio_data.mime_type, "audio/wav");
+        assert!(!message.resume_music);
+    }
+
+    /// 音声でない拡張子のファイルは転写せずエラーにする
+    #[tokio::test(flavor = "current_thread")]
+    async fn transcribe_file_rejects_unsupported_extension() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, "hello").unwrap();
+        let backend = RecordingOrderBackend::new(Arc::new(StdMutex::new(Vec::new())));
+        let (handler, _recording, _media_control, mut rx) =
+            build_handler(backend, MediaControlService::new());
+
+        let result = handler.handle(IpcCmd::TranscribeFile { path }).await;
+
+        assert!(matches!(result, Err(VoiceInputError::SystemError(_))));
+        assert!(rx.try_recv().is_err());
+    }
+
+    /// 待機中の録音中止はエラーとして返る
+    #[tokio::test(flavor = "current_thread")]
+    async fn cancel_while_idle_returns_error() {
+        let backend = RecordingOrderBackend::new(Arc::new(StdMutex::new(Vec::new())));
+        let (handler, _recording, _media_control, _rx) =
+            build_handler(backend, MediaControlService::new());
+
+        let result = handler.handle(IpcCmd::CancelRecording).await;
+
+        assert!(matches!(result, Err(VoiceInputError::RecordingNotStarted)));
+    }
 }
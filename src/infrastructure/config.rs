@@ -35,6 +35,11 @@ pub fn default_dict_path() -> PathBuf {
     data_dir().join("dictionary.json")
 }
 
+/// 直近の録音を保存するディレクトリ
+pub fn audio_cache_dir() -> PathBuf {
+    data_dir().join("audio_cache")
+}
+
 fn copy_file_contents(source: &PathBuf, destination: &PathBuf) -> io::Result<()> {
     if let Some(parent) = destination.parent() {
         fs::create_dir_all(parent)?;
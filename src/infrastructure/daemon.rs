@@ -0,0 +1,566 @@
+//! デーモンの起動処理
+//!
+//! # 責任
+//! - ソケットの確保と待受
+//! - 監視タスク・転写ワーカーの起動とシグナル処理
+//! - クライアント接続ループと 1 接続分の IPC 処理
+//!
+//! バイナリ（voice_inputd）から切り出し、実際のソケット越しの振る舞いを
+//! バイナリを起動せずにテストから確かめられるようにする。
+
+#![allow(clippy::await_holding_refcell_ref)]
+
+use std::cell::RefCell;
+use std::future::Future;
+use std::path::Path;
+use std::process;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+use futures::{SinkExt, StreamExt};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::signal::unix::{SignalKind, signal};
+use tokio::task::spawn_local;
+use tokio_util::codec::{FramedRead, FramedWrite, LinesCodec};
+
+use crate::application::{RecordingConfig, RecordingService};
+use crate::error::{Result, VoiceInputError};
+use crate::infrastructure::{
+    audio::AudioBackend,
+    command_handler::CommandHandler,
+    external::{screen_lock, sound, temp_audio, text_input},
+    ipc_audit::{self, AuditEntry},
+    last_error::{self, Subsystem},
+    resource_limits::{RssWatchdog, RssWatchdogDecision, current_rss_bytes},
+    runtime_recovery::{SleepWakeDetector, StuckRecordingWatchdog, WakeRecoveryRetryPolicy},
+    service_container::ServiceContainer,
+    transcription_worker::{TranscriptionPermits, spawn_transcription_worker},
+};
+use crate::ipc::{
+    IpcCmd, IpcResp, claim_socket_path, decode_cmd_line, ipc_line_codec, read_cmd_body,
+};
+use crate::utils::config::{ResourceConfig, ScreenLockAction};
+
+/// 古いソケットだけを削除して待受を始める（他ユーザー・稼働中デーモンとは衝突させない）
+pub fn bind_socket(path: &Path) -> Result<UnixListener> {
+    claim_socket_path(path).map_err(|e| VoiceInputError::IpcConnectionFailed(e.to_string()))?;
+    UnixListener::bind(path).map_err(|e| VoiceInputError::IpcConnectionFailed(e.to_string()))
+}
+
+/// 転写ワーカーと監視タスクを起動する
+///
+/// 転写キューの受信側を取り出すため、一つのコンテナにつき一度だけ呼べる。
+pub fn spawn_workers<T: AudioBackend + 'static>(
+    container: &mut ServiceContainer<T>,
+    resources: &ResourceConfig,
+    screen_lock_action: ScreenLockAction,
+) -> Result<()> {
+    let transcription_rx = container.take_transcription_rx().ok_or_else(|| {
+        VoiceInputError::SystemError("Transcription workers already started".to_string())
+    })?;
+    let command_handler = container.command_handler.clone();
+    let recording_service = container.recording_service.clone();
+    let permits = container.transcription_permits.clone();
+
+    spawn_runtime_recovery_monitor(recording_service.clone());
+    spawn_stuck_recording_watchdog(command_handler.clone(), recording_service.clone());
+    spawn_screen_lock_monitor(
+        command_handler.clone(),
+        recording_service.clone(),
+        screen_lock_action,
+    );
+    if let Some(max_rss_mb) = resources.max_rss_mb {
+        spawn_resource_watchdog(
+            max_rss_mb,
+            command_handler.clone(),
+            recording_service.clone(),
+            permits.clone(),
+        );
+    }
+
+    container.transcription_worker = Some(spawn_transcription_worker(
+        permits,
+        transcription_rx,
+        container.transcription_service.clone(),
+        recording_service,
+    ));
+    Ok(())
+}
+
+/// SIGHUP を受けるたびに `voice_input reload-config` と同じく設定を読み直す
+pub fn spawn_config_reload_on_hangup<T: AudioBackend + 'static>(
+    command_handler: Rc<RefCell<CommandHandler<T>>>,
+) -> Result<()> {
+    let mut hangup =
+        signal(SignalKind::hangup()).map_err(|e| VoiceInputError::SystemError(e.to_string()))?;
+    spawn_local(async move {
+        while hangup.recv().await.is_some() {
+            if let Err(err) = command_handler.borrow().handle(IpcCmd::ReloadConfig).await {
+                eprintln!("Config reload failed: {}", err);
+            }
+        }
+    });
+    Ok(())
+}
+
+/// SIGTERM / SIGINT を受けるまで待つ
+pub async fn wait_for_shutdown_signal() -> Result<()> {
+    let mut terminate =
+        signal(SignalKind::terminate()).map_err(|e| VoiceInputError::SystemError(e.to_string()))?;
+    tokio::select! {
+        _ = terminate.recv() => {}
+        _ = tokio::signal::ctrl_c() => {}
+    }
+    Ok(())
+}
+
+/// `shutdown` が完了するまでクライアント接続を受け付け、接続ごとにコマンドを処理する
+pub async fn serve<T, F>(
+    listener: &UnixListener,
+    command_handler: Rc<RefCell<CommandHandler<T>>>,
+    shutdown: F,
+) -> Result<()>
+where
+    T: AudioBackend + 'static,
+    F: Future<Output = Result<()>>,
+{
+    tokio::pin!(shutdown);
+    loop {
+        tokio::select! {
+            result = &mut shutdown => return result,
+            accepted = listener.accept() => {
+                let (stream, _) =
+                    accepted.map_err(|e| VoiceInputError::IpcConnectionFailed(e.to_string()))?;
+                let handler = command_handler.clone();
+                spawn_local(async move {
+                    let _ = handle_client(stream, handler).await;
+                });
+            }
+        }
+    }
+}
+
+/// 録音と転写を片付け、ネイティブ資源とソケットを解放する
+pub async fn shutdown<T: AudioBackend + 'static>(
+    container: &mut ServiceContainer<T>,
+    socket: &Path,
+    drain_timeout: Duration,
+) {
+    let report = container.shutdown(drain_timeout).await;
+    for line in report.format_lines() {
+        println!("{}", line);
+    }
+    release_process_resources();
+    if let Err(err) = std::fs::remove_file(socket) {
+        eprintln!("Failed to remove socket {:?}: {}", socket, err);
+    }
+}
+
+/// プロセス終了前にネイティブ資源を解放する（再起動のための終了でも呼ぶ）
+pub fn release_process_resources() {
+    let stopped = sound::shutdown_sound_players();
+    if stopped > 0 {
+        println!("Stopped {} pending sound player(s)", stopped);
+    }
+    let removed = temp_audio::cleanup_temp_audio_files();
+    if removed > 0 {
+        println!("Removed {} temporary audio file(s)", removed);
+    }
+}
+
+/// 録音中（またはロック解除待ちの転写がある間）だけ画面ロック状態を監視する
+fn spawn_screen_lock_monitor<T: AudioBackend + 'static>(
+    command_handler: Rc<RefCell<CommandHandler<T>>>,
+    recording_service: Rc<RefCell<RecordingService<T>>>,
+    action: ScreenLockAction,
+) {
+    const CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+    if action == ScreenLockAction::Ignore {
+        return;
+    }
+
+    spawn_local(async move {
+        let mut ticker = tokio::time::interval(CHECK_INTERVAL);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            ticker.tick().await;
+            let holding = command_handler.borrow().has_held_transcription();
+            if !holding && !recording_service.borrow().is_recording() {
+                continue;
+            }
+
+            let Some(locked) = screen_lock::is_screen_locked().await else {
+                continue;
+            };
+            let result = if locked {
+                command_handler.borrow().handle_screen_locked(action).await
+            } else {
+                command_handler.borrow().handle_screen_unlocked()
+            };
+            if let Err(err) = result {
+                eprintln!("Screen lock handling failed: {}", err);
+                last_error::record(Subsystem::Audio, err.to_string());
+            }
+        }
+    });
+}
+
+fn spawn_runtime_recovery_monitor<T: AudioBackend + 'static>(
+    recording_service: Rc<RefCell<RecordingService<T>>>,
+) {
+    const CHECK_INTERVAL: Duration = Duration::from_secs(15);
+    const WAKE_THRESHOLD: Duration = Duration::from_secs(45);
+
+    spawn_local(async move {
+        let mut detector = SleepWakeDetector::new(SystemTime::now(), WAKE_THRESHOLD);
+        let retry_policy = WakeRecoveryRetryPolicy::after_wake();
+        let mut ticker = tokio::time::interval(CHECK_INTERVAL);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            ticker.tick().await;
+            if !detector.record_tick(SystemTime::now()) {
+                continue;
+            }
+
+            if recording_service.borrow().is_recording() {
+                eprintln!("Wake detected while recording; deferred runtime recovery.");
+                continue;
+            }
+
+            let mut recovered = false;
+            for attempt in 1..=retry_policy.max_attempts {
+                let audio_result = recording_service.borrow().recover_after_wake();
+                let text_result = text_input::recover_after_wake()
+                    .map_err(|e| VoiceInputError::SystemError(e.to_string()));
+
+                match (audio_result, text_result) {
+                    (Ok(()), Ok(())) => {
+                        recovered = true;
+                        println!("Recovered runtime resources after wake.");
+                        break;
+                    }
+                    (audio_result, text_result) => {
+                        if let Err(err) = audio_result {
+                            eprintln!(
+                                "Wake recovery attempt {} failed for audio backend: {}",
+                                attempt, err
+                            );
+                            last_error::record(Subsystem::Audio, err.to_string());
+                        }
+                        if let Err(err) = text_result {
+                            eprintln!(
+                                "Wake recovery attempt {} failed for text input worker: {}",
+                                attempt, err
+                            );
+                            last_error::record(Subsystem::TextInput, err.to_string());
+                        }
+                    }
+                }
+
+                tokio::time::sleep(retry_policy.retry_interval).await;
+            }
+
+            if recovered {
+                continue;
+            }
+
+            eprintln!("Wake recovery failed; exiting to let LaunchAgent restart the daemon.");
+            release_process_resources();
+            process::exit(75);
+        }
+    });
+}
+
+/// 最大録音時間（延長を含む）と猶予を過ぎても止まらない録音を強制停止する
+///
+/// 自動停止タイマーが失敗しても録音が何十分も続かないよう、録音済みの音声は
+/// 通常の停止と同じく転写し、インシデントとして直近エラーに残す。
+/// ランタイム自体が止まって強制停止もできない場合に備え、別スレッドからも期限を確認し、
+/// さらに猶予を過ぎたらプロセスを終了して LaunchAgent に再起動させる。
+fn spawn_stuck_recording_watchdog<T: AudioBackend + 'static>(
+    command_handler: Rc<RefCell<CommandHandler<T>>>,
+    recording_service: Rc<RefCell<RecordingService<T>>>,
+) {
+    const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+    const GRACE: Duration = Duration::from_secs(30);
+    const RUNTIME_STALL_GRACE: Duration = Duration::from_secs(60);
+
+    // 設定の再読み込みで録音時間の上限が変わっても追従できるよう、確認のたびに求める
+    let limit_for = |config: &RecordingConfig| {
+        Duration::from_secs(config.max_duration_secs + config.max_extension_secs) + GRACE
+    };
+
+    let watchdog = Arc::new(Mutex::new(StuckRecordingWatchdog::new(limit_for(
+        recording_service.borrow().config(),
+    ))));
+
+    let stalled = watchdog.clone();
+    if let Err(err) = std::thread::Builder::new()
+        .name("stuck-recording-watchdog".to_string())
+        .spawn(move || {
+            loop {
+                std::thread::sleep(CHECK_INTERVAL);
+                let overdue = stalled
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .overdue_session(Instant::now(), RUNTIME_STALL_GRACE);
+                let Some(session_id) = overdue else {
+                    continue;
+                };
+                let incident = format!(
+                    "dead-man switch: session {} was not stopped {}s past its limit; runtime unresponsive, exiting to let LaunchAgent restart the daemon",
+                    session_id,
+                    RUNTIME_STALL_GRACE.as_secs()
+                );
+                eprintln!("{}", incident);
+                last_error::record(Subsystem::Audio, incident);
+                release_process_resources();
+                process::exit(75);
+            }
+        })
+    {
+        eprintln!("Failed to start stuck recording watchdog thread: {}", err);
+    }
+
+    spawn_local(async move {
+        let mut ticker = tokio::time::interval(CHECK_INTERVAL);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            ticker.tick().await;
+            let (expired, active_session, limit) = {
+                let service = recording_service.borrow();
+                let active_session = if service.is_recording() {
+                    service.latest_session_id().ok()
+                } else {
+                    None
+                };
+                let mut watchdog = watchdog
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner());
+                watchdog.set_limit(limit_for(service.config()));
+                (
+                    watchdog.observe(active_session, Instant::now()),
+                    active_session,
+                    watchdog.limit(),
+                )
+            };
+            if !expired {
+                continue;
+            }
+
+            let incident = format!(
+                "dead-man switch: session {} still recording after {}s; force-stopping",
+                active_session.unwrap_or_default(),
+                limit.as_secs()
+            );
+            eprintln!("{}", incident);
+            last_error::record(Subsystem::Audio, incident);
+            if let Err(err) = command_handler.borrow().handle(IpcCmd::Stop).await {
+                eprintln!("Dead-man switch failed to stop recording: {}", err);
+                last_error::record(Subsystem::Audio, err.to_string());
+            }
+        }
+    });
+}
+
+/// RSS 上限を監視し、超過中は転写並列度を 1 に絞り、超過が続けば再起動させます。
+///
+/// 録音中・転写中・転写待ち（画面ロック解除待ちを含む）の間は、録音済みの音声を失わないよう
+/// 再起動を待機状態になるまで先送りします。
+fn spawn_resource_watchdog<T: AudioBackend + 'static>(
+    max_rss_mb: u64,
+    command_handler: Rc<RefCell<CommandHandler<T>>>,
+    recording_service: Rc<RefCell<RecordingService<T>>>,
+    permits: Rc<TranscriptionPermits>,
+) {
+    const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+    const RESTART_AFTER_CONSECUTIVE_EXCESS: u32 = 4;
+
+    spawn_local(async move {
+        let mut watchdog =
+            RssWatchdog::from_megabytes(max_rss_mb, RESTART_AFTER_CONSECUTIVE_EXCESS);
+        let mut ticker = tokio::time::interval(CHECK_INTERVAL);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            ticker.tick().await;
+            let rss_bytes = match current_rss_bytes() {
+                Ok(value) => value,
+                Err(err) => {
+                    eprintln!("Resource watchdog: {}", err);
+                    continue;
+                }
+            };
+
+            // 送信直後の転写を転写ワーカーが受け取ってから待機状態かを判断する
+            tokio::task::yield_now().await;
+            let busy = recording_service.borrow().is_recording()
+                || command_handler.borrow().has_held_transcription()
+                || !permits.is_idle();
+            match watchdog.record_sample(rss_bytes, busy) {
+                RssWatchdogDecision::WithinLimit => {
+                    if permits.lift_throttle() {
+                        println!(
+                            "RSS back under {} MB; transcription throttling lifted.",
+                            max_rss_mb
+                        );
+                    }
+                }
+                RssWatchdogDecision::Throttle => {
+                    if permits.throttle() {
+                        eprintln!(
+                            "RSS {} MB exceeds {} MB; throttling transcriptions to 1.",
+                            rss_bytes / (1024 * 1024),
+                            max_rss_mb
+                        );
+                    }
+                }
+                RssWatchdogDecision::Restart => {
+                    eprintln!(
+                        "RSS {} MB stayed above {} MB; exiting to let LaunchAgent restart the daemon.",
+                        rss_bytes / (1024 * 1024),
+                        max_rss_mb
+                    );
+                    release_process_resources();
+                    process::exit(75);
+                }
+            }
+        }
+    });
+}
+
+/// 1 クライアントとの IPC セッションを処理します。
+pub async fn handle_client<T: AudioBackend + 'static>(
+    stream: UnixStream,
+    command_handler: Rc<RefCell<CommandHandler<T>>>,
+) -> Result<()> {
+    let source = peer_source(&stream);
+    let (r, w) = stream.into_split();
+    let mut reader = FramedRead::new(r, ipc_line_codec());
+    let mut writer = FramedWrite::new(w, LinesCodec::new());
+
+    if let Some(line) = reader.next().await {
+        let received_at = chrono::Local::now();
+        let started_at = Instant::now();
+        // プロトコル違反は接続を切らずに理由を返す
+        let (command, resp) = match decode_cmd_line(line) {
+            Ok(cmd) if cmd.has_body() => {
+                let command = cmd.name();
+                let parts = reader.into_parts();
+                let resp = match read_cmd_body(&parts.read_buf, parts.io).await {
+                    Ok(body) => command_handler
+                        .borrow()
+                        .handle_with_body(cmd, body)
+                        .await
+                        .unwrap_or_else(|e| IpcResp {
+                            ok: false,
+                            msg: e.to_string(),
+                        }),
+                    Err(e) => {
+                        last_error::record(Subsystem::Ipc, e.to_string());
+                        e.to_resp()
+                    }
+                };
+                (command, resp)
+            }
+            Ok(cmd) => {
+                let command = cmd.name();
+                let resp = command_handler
+                    .borrow()
+                    .handle(cmd)
+                    .await
+                    .unwrap_or_else(|e| IpcResp {
+                        ok: false,
+                        msg: e.to_string(),
+                    });
+                (command, resp)
+            }
+            Err(e) => {
+                last_error::record(Subsystem::Ipc, e.to_string());
+                ("<invalid>", e.to_resp())
+            }
+        };
+        ipc_audit::record(AuditEntry {
+            command,
+            source,
+            received_at,
+            error: (!resp.ok).then(|| resp.msg.clone()),
+            duration: started_at.elapsed(),
+        });
+
+        writer
+            .send(
+                serde_json::to_string(&resp)
+                    .map_err(|e| VoiceInputError::IpcSerializationError(e.to_string()))?,
+            )
+            .await
+            .map_err(|e| VoiceInputError::IpcConnectionFailed(e.to_string()))?;
+    }
+    Ok(())
+}
+
+/// 接続元プロセスを監査ログ用の文字列にする
+fn peer_source(stream: &UnixStream) -> String {
+    match stream.peer_cred() {
+        Ok(cred) => match cred.pid() {
+            Some(pid) => format!("pid={} uid={}", pid, cred.uid()),
+            None => format!("uid={}", cred.uid()),
+        },
+        Err(_) => "unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::service_container::test_helpers::TestServiceContainerBuilder;
+    use tempfile::TempDir;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::task::LocalSet;
+
+    /// 実際のソケット越しにコマンドを処理し、終了の合図で待受を終える
+    #[tokio::test(flavor = "current_thread")]
+    async fn serve_handles_commands_over_socket_until_shutdown() {
+        let container = TestServiceContainerBuilder::new().build().await.unwrap();
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("voice_input.sock");
+        let listener = bind_socket(&path).unwrap();
+        let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+
+        LocalSet::new()
+            .run_until(async {
+                let handler = container.command_handler.clone();
+                let server = spawn_local(async move {
+                    serve(&listener, handler, async {
+                        let _ = shutdown_rx.await;
+                        Ok(())
+                    })
+                    .await
+                });
+
+                let mut stream = UnixStream::connect(&path).await.unwrap();
+                let line = serde_json::to_string(&IpcCmd::Status).unwrap();
+                stream
+                    .write_all(format!("{}\n", line).as_bytes())
+                    .await
+                    .unwrap();
+                let mut response = String::new();
+                BufReader::new(stream)
+                    .read_line(&mut response)
+                    .await
+                    .unwrap();
+                let resp: IpcResp = serde_json::from_str(&response).unwrap();
+                assert!(resp.ok);
+                assert_eq!(resp.msg, "state=Idle");
+
+                shutdown_tx.send(()).unwrap();
+                server.await.unwrap().unwrap();
+            })
+            .await;
+    }
+}
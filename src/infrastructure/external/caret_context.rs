@@ -0,0 +1,87 @@
+//! キャレット直前の文脈取得
+//!
+//! 書きかけの文に続けて話した場合の認識精度を上げるため、フォーカス中の入力欄から
+//! キャレット直前の文字列をアクセシビリティ属性経由で読み取る。入力欄の内容を読むため、
+//! `VOICE_INPUT_CARET_CONTEXT=true` のときだけ使う。パスワード欄は読み取らない。
+use std::process::Command;
+use tokio::task::spawn_blocking;
+
+/// 転写の文脈として使うキャレット直前の最大文字数
+pub const CARET_CONTEXT_MAX_CHARS: usize = 500;
+
+/// 1 行目にキャレット位置（1 始まり）、2 行目以降に入力欄の値を出力する
+const CARET_CONTEXT_SCRIPT: &str = r#"tell application "System Events"
+    set focusedElement to value of attribute "AXFocusedUIElement" of (first application process whose frontmost is true)
+    if (value of attribute "AXRole" of focusedElement) is "AXSecureTextField" then return ""
+    set caretRange to value of attribute "AXSelectedTextRange" of focusedElement
+    set fieldText to value of attribute "AXValue" of focusedElement
+end tell
+return ((item 1 of caretRange) as text) & linefeed & fieldText"#;
+
+/// フォーカス中の入力欄でキャレット直前の文字列を返す。取得できない場合は `None`
+pub async fn text_before_caret(max_chars: usize) -> Option<String> {
+    let output = spawn_blocking(|| {
+        Command::new("osascript")
+            .arg("-e")
+            .arg(CARET_CONTEXT_SCRIPT)
+            .output()
+    })
+    .await
+    .ok()?
+    .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    parse_caret_output(&String::from_utf8_lossy(&output.stdout), max_chars)
+}
+
+/// スクリプト出力からキャレット直前の `max_chars` 文字を取り出す
+///
+/// アクセシビリティ属性の位置は UTF-16 単位のため、文字境界へ変換してから切り出す。
+fn parse_caret_output(stdout: &str, max_chars: usize) -> Option<String> {
+    let stdout = stdout.strip_suffix('\n').unwrap_or(stdout);
+    let (position, value) = stdout.split_once('\n')?;
+    let caret_utf16 = position.trim().parse::<usize>().ok()?.checked_sub(1)?;
+
+    let mut utf16_offset = 0;
+    let caret_byte = value
+        .char_indices()
+        .find_map(|(byte_index, c)| {
+            let at_caret = utf16_offset >= caret_utf16;
+            utf16_offset += c.len_utf16();
+            at_caret.then_some(byte_index)
+        })
+        .unwrap_or(value.len());
+    let before = &value[..caret_byte];
+
+    let skip = before.chars().count().saturating_sub(max_chars);
+    let context: String = before.chars().skip(skip).collect();
+    (!context.trim().is_empty()).then_some(context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_caret_output;
+
+    /// キャレット位置までの末尾だけを文字単位で切り出す
+    #[test]
+    fn text_before_caret_is_cut_at_caret_and_limit() {
+        // 😀 はサロゲートペアのため UTF-16 では 2 単位
+        let stdout = "9\n書きかけ😀の文章|続き\n";
+
+        assert_eq!(
+            parse_caret_output(stdout, 500),
+            Some("書きかけ😀の文".to_string())
+        );
+        assert_eq!(parse_caret_output(stdout, 3), Some("😀の文".to_string()));
+    }
+
+    /// パスワード欄などで値が返らない場合は文脈なしとする
+    #[test]
+    fn empty_or_malformed_output_yields_none() {
+        assert_eq!(parse_caret_output("\n", 500), None);
+        assert_eq!(parse_caret_output("1\nabc\n", 500), None);
+        assert_eq!(parse_caret_output("x\nabc\n", 500), None);
+    }
+}
@@ -0,0 +1,103 @@
+//! クリップボード上の音声ファイル参照の取得
+//!
+//! Finder でコピーした音声ファイル（ファイル参照）か、パス文字列・`file://` URL を
+//! クリップボードから読み取り、転写対象の音声ファイルパスとして返す。
+
+use std::path::PathBuf;
+use std::process::Command;
+
+use crate::application::AudioData;
+
+/// クリップボードからの音声取得エラー
+#[derive(Debug, thiserror::Error)]
+pub enum ClipboardAudioError {
+    #[error("failed to read clipboard: {0}")]
+    Unavailable(#[source] std::io::Error),
+    #[error("clipboard does not contain an audio file reference")]
+    NoAudioReference,
+    #[error("audio file on clipboard does not exist: {0}")]
+    FileNotFound(PathBuf),
+}
+
+/// クリップボード上の音声ファイルパスを返す
+pub fn audio_path_from_clipboard() -> Result<PathBuf, ClipboardAudioError> {
+    // Finder でコピーしたファイルは «class furl» として取得できる
+    let file_reference = Command::new("osascript")
+        .args(["-e", "POSIX path of (the clipboard as «class furl»)"])
+        .output()
+        .map_err(ClipboardAudioError::Unavailable)?;
+    let candidate = if file_reference.status.success() {
+        String::from_utf8_lossy(&file_reference.stdout).to_string()
+    } else {
+        let text = Command::new("pbpaste")
+            .output()
+            .map_err(ClipboardAudioError::Unavailable)?;
+        String::from_utf8_lossy(&text.stdout).to_string()
+    };
+
+    let path = parse_audio_reference(&candidate).ok_or(ClipboardAudioError::NoAudioReference)?;
+    if !path.is_file() {
+        return Err(ClipboardAudioError::FileNotFound(path));
+    }
+    Ok(path)
+}
+
+/// クリップボード文字列から音声ファイルパスを取り出す
+fn parse_audio_reference(text: &str) -> Option<PathBuf> {
+    let line = text.lines().next()?.trim();
+    let path = match line.strip_prefix("file://") {
+        Some(url_path) => PathBuf::from(percent_decode(url_path)?),
+        None => PathBuf::from(line),
+    };
+
+    (path.is_absolute() && AudioData::mime_type_for_path(&path).is_some()).then_some(path)
+}
+
+fn percent_decode(input: &str) -> Option<String> {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%' {
+            let hex = input.get(index + 1..index + 3)?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_audio_reference;
+    use std::path::PathBuf;
+
+    /// 絶対パスの音声ファイルをそのまま受け付ける
+    #[test]
+    fn plain_audio_path_is_accepted() {
+        assert_eq!(
+            parse_audio_reference("/Users/me/memo.m4a\n"),
+            Some(PathBuf::from("/Users/me/memo.m4a"))
+        );
+    }
+
+    /// file URL はパーセントエンコードを戻してパスにする
+    #[test]
+    fn file_url_is_decoded() {
+        assert_eq!(
+            parse_audio_reference("file:///Users/me/Voice%20Memo.wav"),
+            Some(PathBuf::from("/Users/me/Voice Memo.wav"))
+        );
+    }
+
+    /// 音声以外や相対パスは対象外
+    #[test]
+    fn non_audio_or_relative_paths_are_rejected() {
+        assert_eq!(parse_audio_reference("/Users/me/notes.txt"), None);
+        assert_eq!(parse_audio_reference("memo.wav"), None);
+        assert_eq!(parse_audio_reference("ただのテキスト"), None);
+    }
+}
@@ -0,0 +1,27 @@
+//! 最前面アプリケーションの取得
+//!
+//! アプリごとに入力前の待機時間を変えるため、System Events から
+//! 最前面プロセス名を問い合わせる。
+use std::process::Command;
+use tokio::task::spawn_blocking;
+
+const FRONTMOST_APP_SCRIPT: &str = "tell application \"System Events\" to get name of first application process whose frontmost is true";
+
+/// 最前面アプリケーションの名前を返す。取得できない場合は `None`
+pub async fn frontmost_app_name() -> Option<String> {
+    let output = spawn_blocking(|| {
+        Command::new("osascript")
+            .arg("-e")
+            .arg(FRONTMOST_APP_SCRIPT)
+            .output()
+    })
+    .await
+    .ok()?
+    .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let name = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!name.is_empty()).then_some(name)
+}
@@ -5,10 +5,10 @@ use crate::application::AudioData;
 use crate::application::{TranscriptionClient, TranscriptionClientError};
 use crate::domain::transcription::TranscriptionOutput;
 use crate::error::Result;
+use crate::infrastructure::external::temp_audio::TempAudioFile;
 use crate::utils::config::{EnvConfig, TranscriptionConfig};
 use async_trait::async_trait;
-use std::path::{Path, PathBuf};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::path::Path;
 use tokio::process::Command;
 
 #[derive(Debug, thiserror::Error)]
@@ -44,7 +44,7 @@ impl MlxQwen3AsrTranscriptionAdapter {
     }
 
     async fn transcribe_audio(&self, audio: AudioData) -> Result<TranscriptionOutput> {
-        let temp_file = TempAudioFile::create(&audio)
+        let temp_file = TempAudioFile::create(&audio.bytes, file_extension(&audio))
             .map_err(|error| map_init_error(MlxQwen3AsrError::TempFileCreate(error)))?;
 
         let output = Command::new(&self.command)
@@ -53,6 +53,8 @@ impl MlxQwen3AsrTranscriptionAdapter {
             .arg(&self.model)
             .arg("--stdout-only")
             .arg("--no-progress")
+            // 競争モードで負けた場合などに future ごと破棄されたらプロセスも止める
+            .kill_on_drop(true)
             .output()
             .await
             .map_err(|error| map_request_error(MlxQwen3AsrError::CommandExecution(error)))?;
@@ -84,7 +86,12 @@ impl Default for MlxQwen3AsrTranscriptionAdapter {
 
 #[async_trait]
 impl TranscriptionClient for MlxQwen3AsrTranscriptionAdapter {
-    async fn transcribe(&self, audio: AudioData, _language: &str) -> Result<TranscriptionOutput> {
+    async fn transcribe(
+        &self,
+        audio: AudioData,
+        _language: &str,
+        _prompt: Option<&str>,
+    ) -> Result<TranscriptionOutput> {
         self.transcribe_audio(audio).await
     }
 }
@@ -101,38 +108,6 @@ fn map_request_error(error: MlxQwen3AsrError) -> crate::error::VoiceInputError {
     })
 }
 
-struct TempAudioFile {
-    path: PathBuf,
-}
-
-impl TempAudioFile {
-    fn create(audio: &AudioData) -> std::io::Result<Self> {
-        let extension = file_extension(audio);
-        let unique = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_nanos();
-        let path = std::env::temp_dir().join(format!(
-            "voice_input_mlx_{}_{}.{}",
-            std::process::id(),
-            unique,
-            extension
-        ));
-        std::fs::write(&path, &audio.bytes)?;
-        Ok(Self { path })
-    }
-
-    fn path(&self) -> &Path {
-        &self.path
-    }
-}
-
-impl Drop for TempAudioFile {
-    fn drop(&mut self) {
-        let _ = std::fs::remove_file(&self.path);
-    }
-}
-
 fn file_extension(audio: &AudioData) -> &'static str {
     match Path::new(&audio.file_name)
         .extension()
@@ -152,6 +127,7 @@ mod tests {
     use super::*;
     use std::fs;
     use std::os::unix::fs::PermissionsExt;
+    use std::path::PathBuf;
     use tempfile::TempDir;
 
     struct Fixture {
@@ -203,7 +179,7 @@ printf "音声テキスト"
 
         let result = fixture
             .adapter()
-            .transcribe(sample_audio_data(), "ja")
+            .transcribe(sample_audio_data(), "ja", None)
             .await
             .expect("transcription should succeed");
 
@@ -229,7 +205,7 @@ exit 0
 
         let result = fixture
             .adapter()
-            .transcribe(sample_audio_data(), "ja")
+            .transcribe(sample_audio_data(), "ja", None)
             .await
             .expect("transcription should succeed");
 
@@ -248,7 +224,7 @@ exit 1
 
         let error = fixture
             .adapter()
-            .transcribe(sample_audio_data(), "ja")
+            .transcribe(sample_audio_data(), "ja", None)
             .await
             .expect_err("transcription should fail");
 
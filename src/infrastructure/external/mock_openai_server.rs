@@ -0,0 +1,292 @@
+//! テスト専用の OpenAI 互換モックサーバー
+//!
+//! `/v1/audio/transcriptions` を模倣し、成功・429・500・遅延応答を
+//! 事前に積んだ順番で返す。`OPENAI_BASE_URL` 相当の上書きで
+//! `OpenAiClient` の向き先をこのサーバーへ切り替えて使う。
+
+use std::collections::VecDeque;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+use crate::utils::config::{OpenAiRequestConfig, TranscriptionConfig, TranscriptionProvider};
+
+/// モックサーバーが返す応答
+#[derive(Debug, Clone)]
+pub(crate) enum MockResponse {
+    /// 200 で転写結果 JSON を返す
+    Success { text: String },
+    /// 200 で SSE ストリームを返す
+    Streaming { events: Vec<String> },
+    /// 任意のステータスとボディを返す
+    Status { code: u16, body: String },
+    /// 指定時間待ってから内側の応答を返す
+    Delayed {
+        delay: Duration,
+        response: Box<MockResponse>,
+    },
+}
+
+impl MockResponse {
+    /// 転写成功応答
+    pub(crate) fn success(text: &str) -> Self {
+        Self::Success {
+            text: text.to_string(),
+        }
+    }
+
+    /// レート制限応答
+    pub(crate) fn rate_limited() -> Self {
+        Self::Status {
+            code: 429,
+            body: r#"{"error":{"message":"Rate limit reached","type":"requests"}}"#.to_string(),
+        }
+    }
+
+    /// サーバーエラー応答
+    pub(crate) fn server_error() -> Self {
+        Self::Status {
+            code: 500,
+            body: r#"{"error":{"message":"internal error","type":"server_error"}}"#.to_string(),
+        }
+    }
+
+    /// 遅延させた応答
+    pub(crate) fn delayed(delay: Duration, response: MockResponse) -> Self {
+        Self::Delayed {
+            delay,
+            response: Box::new(response),
+        }
+    }
+}
+
+/// `base_url` のモックサーバーへ向けた OpenAI 転写設定
+pub(crate) fn mock_transcription_config(
+    base_url: String,
+    request: OpenAiRequestConfig,
+) -> TranscriptionConfig {
+    TranscriptionConfig {
+        provider: TranscriptionProvider::OpenAi,
+        api_key: Some("test-key".to_string()),
+        model: "gpt-4o-mini-transcribe".to_string(),
+        streaming_enabled: false,
+        log_path: None,
+        low_confidence_selection_enabled: false,
+        caret_context_enabled: false,
+        timestamp_format: None,
+        mlx_qwen3_asr_command: "mlx-qwen3-asr".to_string(),
+        openai_base_url: Some(base_url),
+        openai_request: request,
+        race_provider: None,
+    }
+}
+
+/// モックサーバーが受け取ったリクエストの記録
+#[derive(Debug, Clone)]
+pub(crate) struct RecordedRequest {
+    pub(crate) path: String,
+    pub(crate) headers: Vec<(String, String)>,
+    pub(crate) body: Vec<u8>,
+}
+
+impl RecordedRequest {
+    /// ヘッダー値を大文字小文字を区別せずに取得する
+    pub(crate) fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// multipart ボディ内に文字列が含まれるか
+    pub(crate) fn body_contains(&self, needle: &str) -> bool {
+        String::from_utf8_lossy(&self.body).contains(needle)
+    }
+}
+
+/// OpenAI 互換モックサーバー
+pub(crate) struct MockOpenAiServer {
+    addr: SocketAddr,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+    task: JoinHandle<()>,
+}
+
+impl MockOpenAiServer {
+    /// 応答キューを指定して起動する。キューが尽きた後は 500 を返す
+    pub(crate) async fn start(responses: Vec<MockResponse>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("mock server should bind");
+        let addr = listener.local_addr().expect("mock server address");
+        let queue = Arc::new(Mutex::new(VecDeque::from(responses)));
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let task_requests = requests.clone();
+        let task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let response = queue
+                    .lock()
+                    .unwrap()
+                    .pop_front()
+                    .unwrap_or_else(MockResponse::server_error);
+                let requests = task_requests.clone();
+                tokio::spawn(async move {
+                    let _ = serve_connection(stream, response, requests).await;
+                });
+            }
+        });
+
+        Self {
+            addr,
+            requests,
+            task,
+        }
+    }
+
+    /// `OpenAiClient` に渡すベース URL
+    pub(crate) fn base_url(&self) -> String {
+        format!("http://{}/v1", self.addr)
+    }
+
+    /// 受信済みリクエストの一覧
+    pub(crate) fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl Drop for MockOpenAiServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn serve_connection(
+    mut stream: TcpStream,
+    response: MockResponse,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+) -> std::io::Result<()> {
+    let request = read_request(&mut stream).await?;
+    requests.lock().unwrap().push(request);
+
+    let mut response = response;
+    while let MockResponse::Delayed {
+        delay,
+        response: inner,
+    } = response
+    {
+        tokio::time::sleep(delay).await;
+        response = *inner;
+    }
+
+    let (code, content_type, body) = match response {
+        MockResponse::Success { text } => (
+            200,
+            "application/json",
+            serde_json::json!({ "text": text }).to_string(),
+        ),
+        MockResponse::Streaming { events } => (
+            200,
+            "text/event-stream",
+            events
+                .iter()
+                .map(|event| format!("data: {}\n\n", event))
+                .collect::<String>(),
+        ),
+        MockResponse::Status { code, body } => (code, "application/json", body),
+        MockResponse::Delayed { .. } => unreachable!("delays are unwrapped above"),
+    };
+
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        code,
+        reason_phrase(code),
+        content_type,
+        body.len()
+    );
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(body.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+async fn read_request(stream: &mut TcpStream) -> std::io::Result<RecordedRequest> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let header_end = loop {
+        if let Some(index) = find_subslice(&buffer, b"\r\n\r\n") {
+            break index;
+        }
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            return Err(std::io::ErrorKind::UnexpectedEof.into());
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    };
+
+    let head = String::from_utf8_lossy(&buffer[..header_end]).to_string();
+    let mut lines = head.split("\r\n");
+    let request_line = lines.next().unwrap_or_default();
+    let path = request_line
+        .split_whitespace()
+        .nth(1)
+        .unwrap_or_default()
+        .to_string();
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect();
+
+    let mut body = buffer[header_end + 4..].to_vec();
+    let content_length = headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.parse::<usize>().ok());
+    let chunked = headers.iter().any(|(key, value)| {
+        key.eq_ignore_ascii_case("transfer-encoding") && value.eq_ignore_ascii_case("chunked")
+    });
+
+    if let Some(length) = content_length {
+        while body.len() < length {
+            let read = stream.read(&mut chunk).await?;
+            if read == 0 {
+                break;
+            }
+            body.extend_from_slice(&chunk[..read]);
+        }
+    } else if chunked {
+        while find_subslice(&body, b"0\r\n\r\n").is_none() {
+            let read = stream.read(&mut chunk).await?;
+            if read == 0 {
+                break;
+            }
+            body.extend_from_slice(&chunk[..read]);
+        }
+    }
+
+    Ok(RecordedRequest {
+        path,
+        headers,
+        body,
+    })
+}
+
+fn find_subslice(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+fn reason_phrase(code: u16) -> &'static str {
+    match code {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        503 => "Service Unavailable",
+        _ => "Unknown",
+    }
+}
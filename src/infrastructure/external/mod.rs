@@ -1,7 +1,17 @@
+pub mod caret_context;
+pub mod clipboard_audio;
+pub mod frontmost_app;
 pub mod mlx_qwen3_asr_adapter;
+#[cfg(test)]
+pub(crate) mod mock_openai_server;
+pub mod notification;
 pub mod openai;
 pub mod openai_adapter;
+pub mod racing_adapter;
+pub mod screen_lock;
 pub mod sound;
+pub mod speech;
+pub mod temp_audio;
 pub mod text_input;
 pub mod text_input_worker;
 pub mod transcription_log;
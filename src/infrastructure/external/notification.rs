@@ -0,0 +1,48 @@
+//! macOS 通知センターへの通知
+//!
+//! ターミナルを見なくても結果を確認できるよう、`osascript` の
+//! `display notification` で通知を表示する。
+use std::process::Command;
+use tokio::task::spawn_blocking;
+
+/// 通知のタイトル
+const NOTIFICATION_TITLE: &str = "voice_input";
+
+/// AppleScript の文字列リテラルとして埋め込めるようにエスケープする
+fn escape_applescript(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// 通知を表示する。失敗しても呼び出し元の処理は続ける
+pub async fn notify(message: &str) {
+    let script = format!(
+        "display notification \"{}\" with title \"{}\"",
+        escape_applescript(message),
+        NOTIFICATION_TITLE
+    );
+    let result =
+        spawn_blocking(move || Command::new("osascript").arg("-e").arg(script).output()).await;
+    match result {
+        Ok(Ok(output)) if output.status.success() => {}
+        Ok(Ok(output)) => eprintln!(
+            "Notification failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ),
+        Ok(Err(e)) => eprintln!("Notification failed: {}", e),
+        Err(e) => eprintln!("Notification task failed: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::escape_applescript;
+
+    /// 引用符とバックスラッシュは AppleScript 用にエスケープする
+    #[test]
+    fn quotes_and_backslashes_are_escaped() {
+        assert_eq!(
+            escape_applescript(r#"say "hi" \ bye"#),
+            r#"say \"hi\" \\ bye"#
+        );
+    }
+}
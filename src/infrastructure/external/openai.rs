@@ -4,10 +4,11 @@
 use crate::application::AudioData;
 use crate::application::TranscriptionEvent;
 use crate::domain::transcription::{TranscriptionOutput, TranscriptionToken};
-use crate::utils::config::EnvConfig;
+use crate::utils::config::{EnvConfig, OpenAiRequestConfig, ProxyConfig, TranscriptionConfig};
 use crate::utils::profiling;
-use reqwest::{Client, Proxy, multipart};
+use reqwest::{Client, Proxy, RequestBuilder, multipart};
 use serde::Deserialize;
+use std::time::Duration;
 use tokio::sync::mpsc;
 
 #[derive(Debug, thiserror::Error)]
@@ -89,10 +90,15 @@ pub struct WordSuggestion {
     pub replacement: String,
 }
 
+/// OpenAI API の既定ベース URL
+const DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
+
 /// OpenAI API client
 pub struct OpenAiClient {
     api_key: String,
     model: String,
+    base_url: String,
+    request: OpenAiRequestConfig,
     client: reqwest::Client,
 }
 
@@ -100,27 +106,88 @@ impl OpenAiClient {
     /// Create a new OpenAI client
     pub fn new() -> Result<Self, OpenAiError> {
         let config = EnvConfig::get();
-        let api_key = config
-            .transcription
+        Self::from_config(&config.transcription, &config.proxy)
+    }
+
+    /// 転写設定とプロキシ設定から OpenAI クライアントを作成
+    pub fn from_config(
+        transcription: &TranscriptionConfig,
+        proxy: &ProxyConfig,
+    ) -> Result<Self, OpenAiError> {
+        let api_key = transcription
             .api_key
             .clone()
             .ok_or(OpenAiError::MissingApiKey)?;
+        let base_url = transcription
+            .openai_base_url
+            .clone()
+            .unwrap_or_else(|| DEFAULT_BASE_URL.to_string());
 
-        let model = config.transcription.model.clone();
-
-        let client = build_http_client().map_err(OpenAiError::HttpClientBuild)?;
+        let request = transcription.openai_request.clone();
+        let client = build_http_client(proxy, request.timeout_secs.map(Duration::from_secs))
+            .map_err(OpenAiError::HttpClientBuild)?;
 
         Ok(Self {
             api_key,
-            model,
+            model: transcription.model.clone(),
+            base_url,
+            request,
             client,
         })
     }
 
+    /// 認証ヘッダと組織・プロジェクトヘッダを付与する
+    fn authorize(&self, builder: RequestBuilder) -> RequestBuilder {
+        let mut builder = builder.header("Authorization", format!("Bearer {}", self.api_key));
+        if let Some(organization) = self.request.organization.as_deref() {
+            builder = builder.header("OpenAI-Organization", organization);
+        }
+        if let Some(project) = self.request.project.as_deref() {
+            builder = builder.header("OpenAI-Project", project);
+        }
+        builder
+    }
+
+    /// 利用者タグが設定されていればフォームに付与する
+    fn with_user(&self, form: multipart::Form) -> multipart::Form {
+        match self.request.user.clone() {
+            Some(user) => form.text("user", user),
+            None => form,
+        }
+    }
+
+    fn transcriptions_url(&self) -> String {
+        format!(
+            "{}/audio/transcriptions",
+            self.base_url.trim_end_matches('/')
+        )
+    }
+
+    /// ヘルスチェックで疎通を確かめるモデル一覧のURL
+    pub fn models_url(&self) -> String {
+        format!("{}/models", self.base_url.trim_end_matches('/'))
+    }
+
+    /// 転写と同じ向き先・ヘッダ・タイムアウトでモデル一覧を取得し、API に届くかを確かめる
+    pub async fn check_models(&self) -> Result<(), OpenAiError> {
+        let response = self
+            .authorize(self.client.get(self.models_url()))
+            .send()
+            .await
+            .map_err(OpenAiError::Request)?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        let body = response.text().await.map_err(OpenAiError::ResponseBody)?;
+        Err(OpenAiError::ApiStatus { status, body })
+    }
+
     /// AudioDataから直接転写を実行
     pub async fn transcribe_audio(
         &self,
         audio_data: AudioData,
+        prompt: Option<&str>,
     ) -> Result<TranscriptionOutput, OpenAiError> {
         if profiling::enabled() {
             profiling::log_point(
@@ -140,13 +207,14 @@ impl OpenAiClient {
             .map_err(OpenAiError::Multipart)?;
 
         // 既存の転写処理を実行
-        self.transcribe_with_part(part, None).await
+        self.transcribe_with_part(part, prompt).await
     }
 
     /// AudioDataから直接ストリーミング転写を実行
     pub async fn transcribe_audio_streaming(
         &self,
         audio_data: AudioData,
+        prompt: Option<&str>,
         event_tx: mpsc::UnboundedSender<TranscriptionEvent>,
     ) -> Result<TranscriptionOutput, OpenAiError> {
         if profiling::enabled() {
@@ -166,7 +234,7 @@ impl OpenAiClient {
             .mime_str(audio_data.mime_type)
             .map_err(OpenAiError::Multipart)?;
 
-        self.transcribe_streaming_with_part(part, None, event_tx)
+        self.transcribe_streaming_with_part(part, prompt, event_tx)
             .await
     }
 
@@ -177,7 +245,7 @@ impl OpenAiClient {
         prompt: Option<&str>,
     ) -> Result<TranscriptionOutput, OpenAiError> {
         let overall_timer = profiling::Timer::start("openai.transcribe_total");
-        let url = "https://api.openai.com/v1/audio/transcriptions";
+        let url = self.transcriptions_url();
 
         // multipart/form-data
         let mut form = multipart::Form::new()
@@ -196,10 +264,8 @@ impl OpenAiClient {
 
         // 送信
         let request = self
-            .client
-            .post(url)
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .multipart(form);
+            .authorize(self.client.post(url))
+            .multipart(self.with_user(form));
 
         let send_timer = profiling::Timer::start("openai.send");
         let response = request.send().await.map_err(OpenAiError::Request)?;
@@ -249,7 +315,7 @@ impl OpenAiClient {
         event_tx: mpsc::UnboundedSender<TranscriptionEvent>,
     ) -> Result<TranscriptionOutput, OpenAiError> {
         let overall_timer = profiling::Timer::start("openai.streaming_transcribe_total");
-        let url = "https://api.openai.com/v1/audio/transcriptions";
+        let url = self.transcriptions_url();
 
         let mut form = multipart::Form::new()
             .part("file", file_part)
@@ -268,10 +334,8 @@ impl OpenAiClient {
 
         let send_timer = profiling::Timer::start("openai.streaming_send");
         let mut response = self
-            .client
-            .post(url)
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .multipart(form)
+            .authorize(self.client.post(url))
+            .multipart(self.with_user(form))
             .send()
             .await
             .map_err(OpenAiError::Request)?;
@@ -457,10 +521,14 @@ fn find_frame_separator(buffer: &[u8]) -> Option<(usize, usize)> {
     None
 }
 
-fn build_http_client() -> Result<Client, reqwest::Error> {
+fn build_http_client(
+    proxy: &ProxyConfig,
+    timeout: Option<Duration>,
+) -> Result<Client, reqwest::Error> {
     let mut builder = Client::builder().no_proxy();
-    let config = EnvConfig::get();
-    let proxy = &config.proxy;
+    if let Some(timeout) = timeout {
+        builder = builder.timeout(timeout);
+    }
 
     if let Some(all_proxy) = proxy.all.as_ref() {
         builder = builder.proxy(Proxy::all(all_proxy)?);
@@ -482,6 +550,248 @@ fn build_http_client() -> Result<Client, reqwest::Error> {
 mod tests {
     use super::*;
     use crate::application::AudioData;
+    use crate::infrastructure::external::mock_openai_server::{
+        MockOpenAiServer, MockResponse, mock_transcription_config,
+    };
+    use std::time::{Duration, Instant};
+
+    fn mock_client(base_url: String) -> OpenAiClient {
+        mock_client_with_request(base_url, OpenAiRequestConfig::default())
+    }
+
+    fn mock_client_with_request(base_url: String, request: OpenAiRequestConfig) -> OpenAiClient {
+        // 計測ログの有無を決めるため転写経路は EnvConfig を参照する
+        EnvConfig::test_init();
+        let proxy = ProxyConfig {
+            all: None,
+            https: None,
+            http: None,
+        };
+        OpenAiClient::from_config(&mock_transcription_config(base_url, request), &proxy)
+            .expect("client should build")
+    }
+
+    fn sample_audio() -> AudioData {
+        AudioData {
+            bytes: vec![0u8; 64],
+            mime_type: "audio/flac",
+            file_name: "audio.flac".to_string(),
+        }
+    }
+
+    /// ベースURL上書き先のモックサーバーへ転写リクエストを送れる
+    #[tokio::test]
+    async fn transcribe_audio_uses_base_url_override() {
+        let server = MockOpenAiServer::start(vec![MockResponse::success("こんにちは")]).await;
+        let client = mock_client(server.base_url());
+
+        let output = client.transcribe_audio(sample_audio(), None).await.unwrap();
+
+        assert_eq!(output.text, "こんにちは");
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].path, "/v1/audio/transcriptions");
+        assert_eq!(requests[0].header("authorization"), Some("Bearer test-key"));
+        assert!(requests[0].body_contains("gpt-4o-mini-transcribe"));
+    }
+
+    /// 組織・プロジェクトはヘッダ、利用者タグはフォーム項目として送る
+    #[tokio::test]
+    async fn transcribe_audio_sends_organization_project_and_user() {
+        let server = MockOpenAiServer::start(vec![MockResponse::success("ok")]).await;
+        let client = mock_client_with_request(
+            server.base_url(),
+            OpenAiRequestConfig {
+                organization: Some("org-123".to_string()),
+                project: Some("proj_456".to_string()),
+                timeout_secs: None,
+                user: Some("voice-input-alice".to_string()),
+            },
+        );
+
+        client.transcribe_audio(sample_audio(), None).await.unwrap();
+
+        let requests = server.requests();
+        assert_eq!(requests[0].header("openai-organization"), Some("org-123"));
+        assert_eq!(requests[0].header("openai-project"), Some("proj_456"));
+        assert!(requests[0].body_contains("name=\"user\""));
+        assert!(requests[0].body_contains("voice-input-alice"));
+    }
+
+    /// 付加設定がなければ組織・プロジェクトヘッダと利用者タグを送らない
+    #[tokio::test]
+    async fn transcribe_audio_omits_unset_request_options() {
+        let server = MockOpenAiServer::start(vec![MockResponse::success("ok")]).await;
+        let client = mock_client(server.base_url());
+
+        client.transcribe_audio(sample_audio(), None).await.unwrap();
+
+        let requests = server.requests();
+        assert_eq!(requests[0].header("openai-organization"), None);
+        assert_eq!(requests[0].header("openai-project"), None);
+        assert!(!requests[0].body_contains("name=\"user\""));
+    }
+
+    /// プロンプト指定時は文脈としてリクエストに含める
+    #[tokio::test]
+    async fn transcribe_audio_sends_prompt_as_context() {
+        let server = MockOpenAiServer::start(vec![MockResponse::success("ok")]).await;
+        let client = mock_client(server.base_url());
+
+        client
+            .transcribe_audio(sample_audio(), Some("noisy cafe"))
+            .await
+            .unwrap();
+
+        let requests = server.requests();
+        assert!(requests[0].body_contains("name=\"prompt\""));
+        assert!(requests[0].body_contains("noisy cafe"));
+    }
+
+    /// ベースURL末尾のスラッシュは重複させない
+    #[test]
+    fn transcriptions_url_trims_trailing_slash() {
+        let client = mock_client("http://127.0.0.1:9/v1/".to_string());
+
+        assert_eq!(
+            client.transcriptions_url(),
+            "http://127.0.0.1:9/v1/audio/transcriptions"
+        );
+    }
+
+    /// 429応答はステータス付きのAPIエラーになる
+    #[tokio::test]
+    async fn rate_limited_response_is_reported_as_api_status() {
+        let server = MockOpenAiServer::start(vec![MockResponse::rate_limited()]).await;
+        let client = mock_client(server.base_url());
+
+        let result = client.transcribe_audio(sample_audio(), None).await;
+
+        match result {
+            Err(OpenAiError::ApiStatus { status, body }) => {
+                assert_eq!(status, reqwest::StatusCode::TOO_MANY_REQUESTS);
+                assert!(body.contains("Rate limit"));
+            }
+            other => panic!("unexpected result: {:?}", other.map(|o| o.text)),
+        }
+    }
+
+    /// 500応答はステータス付きのAPIエラーになる
+    #[tokio::test]
+    async fn server_error_response_is_reported_as_api_status() {
+        let server = MockOpenAiServer::start(vec![MockResponse::server_error()]).await;
+        let client = mock_client(server.base_url());
+
+        let result = client.transcribe_audio(sample_audio(), None).await;
+
+        assert!(matches!(
+            result,
+            Err(OpenAiError::ApiStatus { status, .. })
+                if status == reqwest::StatusCode::INTERNAL_SERVER_ERROR
+        ));
+    }
+
+    /// 遅延応答でも応答到着まで待って結果を返す
+    #[tokio::test]
+    async fn slow_response_is_awaited_until_arrival() {
+        let delay = Duration::from_millis(200);
+        let server = MockOpenAiServer::start(vec![MockResponse::delayed(
+            delay,
+            MockResponse::success("遅延"),
+        )])
+        .await;
+        let client = mock_client(server.base_url());
+        let started = Instant::now();
+
+        let output = client.transcribe_audio(sample_audio(), None).await.unwrap();
+
+        assert_eq!(output.text, "遅延");
+        assert!(started.elapsed() >= delay);
+    }
+
+    /// タイムアウト秒数を過ぎても応答がなければ待ち続けずに送信エラーにする
+    #[tokio::test]
+    async fn request_timeout_cuts_off_delayed_response() {
+        let server = MockOpenAiServer::start(vec![MockResponse::delayed(
+            Duration::from_secs(5),
+            MockResponse::success("遅すぎる"),
+        )])
+        .await;
+        let client = mock_client_with_request(
+            server.base_url(),
+            OpenAiRequestConfig {
+                timeout_secs: Some(1),
+                ..OpenAiRequestConfig::default()
+            },
+        );
+        let started = Instant::now();
+
+        let result = client.transcribe_audio(sample_audio(), None).await;
+
+        match result {
+            Err(OpenAiError::Request(error)) => assert!(error.is_timeout(), "{}", error),
+            other => panic!("unexpected result: {:?}", other.map(|o| o.text)),
+        }
+        assert!(started.elapsed() < Duration::from_secs(4));
+    }
+
+    /// 疎通確認は転写と同じベースURL・組織ヘッダでモデル一覧を取得する
+    #[tokio::test]
+    async fn check_models_uses_base_url_and_request_headers() {
+        let server = MockOpenAiServer::start(vec![
+            MockResponse::success("models"),
+            MockResponse::Status {
+                code: 401,
+                body: r#"{"error":{"message":"invalid key"}}"#.to_string(),
+            },
+        ])
+        .await;
+        let client = mock_client_with_request(
+            server.base_url(),
+            OpenAiRequestConfig {
+                organization: Some("org-123".to_string()),
+                ..OpenAiRequestConfig::default()
+            },
+        );
+
+        client.check_models().await.unwrap();
+        let error = client.check_models().await.unwrap_err();
+
+        assert!(matches!(
+            error,
+            OpenAiError::ApiStatus { status, .. } if status == reqwest::StatusCode::UNAUTHORIZED
+        ));
+        let requests = server.requests();
+        assert_eq!(requests[0].path, "/v1/models");
+        assert_eq!(requests[0].header("authorization"), Some("Bearer test-key"));
+        assert_eq!(requests[0].header("openai-organization"), Some("org-123"));
+    }
+
+    /// モックサーバーのSSE応答からストリーミング転写結果を受け取れる
+    #[tokio::test]
+    async fn streaming_transcription_reads_events_from_mock_server() {
+        let server = MockOpenAiServer::start(vec![MockResponse::Streaming {
+            events: vec![
+                r#"{"type":"transcript.text.delta","delta":"こん"}"#.to_string(),
+                r#"{"type":"transcript.text.done","text":"こんにちは"}"#.to_string(),
+            ],
+        }])
+        .await;
+        let client = mock_client(server.base_url());
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+
+        let output = client
+            .transcribe_audio_streaming(sample_audio(), None, event_tx)
+            .await
+            .unwrap();
+
+        assert_eq!(output.text, "こんにちは");
+        assert!(matches!(
+            event_rx.recv().await,
+            Some(TranscriptionEvent::Delta(delta)) if delta == "こん"
+        ));
+        assert!(server.requests()[0].body_contains("stream"));
+    }
 
     /// 転写レスポンスのJSONをパースできる
     #[test]
@@ -547,7 +857,7 @@ mod tests {
         };
 
         // This will fail with the actual API, but we're testing the method exists
-        let result = client.transcribe_audio(audio_data).await;
+        let result = client.transcribe_audio(audio_data, None).await;
 
         // We expect an error since we're using a test API key
         assert!(result.is_err());
@@ -575,7 +885,7 @@ mod tests {
         };
 
         // This will fail because the file doesn't exist, but we're testing the method exists
-        let result = client.transcribe_audio(audio_data).await;
+        let result = client.transcribe_audio(audio_data, None).await;
 
         // We expect an error since the file doesn't exist
         assert!(result.is_err());
@@ -6,6 +6,7 @@ use crate::application::{TranscriptionClient, TranscriptionClientError, Transcri
 use crate::domain::transcription::TranscriptionOutput;
 use crate::error::Result;
 use crate::infrastructure::external::openai::OpenAiClient;
+use crate::utils::config::{EnvConfig, ProxyConfig, TranscriptionConfig};
 use async_trait::async_trait;
 use tokio::sync::mpsc;
 
@@ -17,8 +18,14 @@ pub struct OpenAiTranscriptionAdapter {
 impl OpenAiTranscriptionAdapter {
     /// 新しいアダプターを作成
     pub fn new() -> Result<Self> {
+        let config = EnvConfig::get();
+        Self::from_config(&config.transcription, &config.proxy)
+    }
+
+    /// 転写設定とプロキシ設定から作成
+    pub fn from_config(transcription: &TranscriptionConfig, proxy: &ProxyConfig) -> Result<Self> {
         Ok(Self {
-            client: OpenAiClient::new().map_err(|error| {
+            client: OpenAiClient::from_config(transcription, proxy).map_err(|error| {
                 crate::error::VoiceInputError::from(TranscriptionClientError::Initialization {
                     message: error.to_string(),
                 })
@@ -29,22 +36,31 @@ impl OpenAiTranscriptionAdapter {
 
 #[async_trait]
 impl TranscriptionClient for OpenAiTranscriptionAdapter {
-    async fn transcribe(&self, audio: AudioData, _language: &str) -> Result<TranscriptionOutput> {
-        self.client.transcribe_audio(audio).await.map_err(|error| {
-            crate::error::VoiceInputError::from(TranscriptionClientError::Request {
-                message: error.to_string(),
+    async fn transcribe(
+        &self,
+        audio: AudioData,
+        _language: &str,
+        prompt: Option<&str>,
+    ) -> Result<TranscriptionOutput> {
+        self.client
+            .transcribe_audio(audio, prompt)
+            .await
+            .map_err(|error| {
+                crate::error::VoiceInputError::from(TranscriptionClientError::Request {
+                    message: error.to_string(),
+                })
             })
-        })
     }
 
     async fn transcribe_streaming(
         &self,
         audio: AudioData,
         _language: &str,
+        prompt: Option<&str>,
         event_tx: mpsc::UnboundedSender<TranscriptionEvent>,
     ) -> Result<TranscriptionOutput> {
         self.client
-            .transcribe_audio_streaming(audio, event_tx)
+            .transcribe_audio_streaming(audio, prompt, event_tx)
             .await
             .map_err(|error| {
                 crate::error::VoiceInputError::from(TranscriptionClientError::Request {
@@ -0,0 +1,218 @@
+//! 競争モードのアダプター実装
+//! Application層のTranscriptionClientトレイトを実装
+//!
+//! 短い発話での待ち時間のばらつきを抑えるため、同じ音声を 2 つのバックエンドへ同時に送り、
+//! 先に成功した結果を使う。負けた側は future を破棄して中止するが、送信済みのリクエストは
+//! 課金されるため、利用量はおよそ 2 倍になる。
+
+use crate::application::AudioData;
+use crate::application::TranscriptionClient;
+use crate::domain::transcription::TranscriptionOutput;
+use crate::error::Result;
+use crate::utils::profiling;
+use async_trait::async_trait;
+
+/// 2 つのバックエンドを競わせるアダプター
+pub struct RacingTranscriptionAdapter {
+    primary: Box<dyn TranscriptionClient>,
+    secondary: Box<dyn TranscriptionClient>,
+}
+
+impl RacingTranscriptionAdapter {
+    /// 新しいアダプターを作成。両方失敗した場合は `primary` のエラーを返す
+    pub fn new(
+        primary: Box<dyn TranscriptionClient>,
+        secondary: Box<dyn TranscriptionClient>,
+    ) -> Self {
+        Self { primary, secondary }
+    }
+}
+
+/// ストリーミングの増分は 2 つのバックエンドで食い違うため、`transcribe_streaming` は
+/// 既定実装（全文確定後に返す）のままにする。
+#[async_trait]
+impl TranscriptionClient for RacingTranscriptionAdapter {
+    async fn transcribe(
+        &self,
+        audio: AudioData,
+        language: &str,
+        prompt: Option<&str>,
+    ) -> Result<TranscriptionOutput> {
+        let primary = self.primary.transcribe(audio.clone(), language, prompt);
+        let secondary = self.secondary.transcribe(audio, language, prompt);
+        tokio::pin!(primary, secondary);
+
+        tokio::select! {
+            result = &mut primary => match result {
+                Ok(output) => {
+                    profiling::log_point("transcription.race", "winner=primary");
+                    Ok(output)
+                }
+                Err(primary_error) => {
+                    eprintln!("Race: primary provider failed: {}", primary_error);
+                    secondary.await.inspect(|_| {
+                        profiling::log_point("transcription.race", "winner=secondary");
+                    }).map_err(|_| primary_error)
+                }
+            },
+            result = &mut secondary => match result {
+                Ok(output) => {
+                    profiling::log_point("transcription.race", "winner=secondary");
+                    Ok(output)
+                }
+                Err(secondary_error) => {
+                    eprintln!("Race: secondary provider failed: {}", secondary_error);
+                    primary.await
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::TranscriptionClientError;
+    use crate::error::VoiceInputError;
+    use crate::infrastructure::external::mock_openai_server::{
+        MockOpenAiServer, MockResponse, mock_transcription_config,
+    };
+    use crate::infrastructure::external::openai_adapter::OpenAiTranscriptionAdapter;
+    use crate::utils::config::{EnvConfig, OpenAiRequestConfig, ProxyConfig};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::Duration;
+
+    /// 指定時間後に結果を返し、途中で破棄されたかを記録するクライアント
+    struct DelayedClient {
+        delay: Duration,
+        text: Option<&'static str>,
+        cancelled: Arc<AtomicBool>,
+    }
+
+    impl DelayedClient {
+        fn boxed(delay_ms: u64, text: Option<&'static str>) -> (Box<Self>, Arc<AtomicBool>) {
+            let cancelled = Arc::new(AtomicBool::new(false));
+            let client = Box::new(Self {
+                delay: Duration::from_millis(delay_ms),
+                text,
+                cancelled: cancelled.clone(),
+            });
+            (client, cancelled)
+        }
+    }
+
+    /// 完了前に破棄されたら `cancelled` を立てる
+    struct CancelFlag(Arc<AtomicBool>);
+
+    impl Drop for CancelFlag {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    #[async_trait]
+    impl TranscriptionClient for DelayedClient {
+        async fn transcribe(
+            &self,
+            _audio: AudioData,
+            _language: &str,
+            _prompt: Option<&str>,
+        ) -> Result<TranscriptionOutput> {
+            let flag = CancelFlag(self.cancelled.clone());
+            tokio::time::sleep(self.delay).await;
+            std::mem::forget(flag);
+            match self.text {
+                Some(text) => Ok(TranscriptionOutput::from_text(text)),
+                None => Err(VoiceInputError::from(TranscriptionClientError::Request {
+                    message: format!("failed after {:?}", self.delay),
+                })),
+            }
+        }
+    }
+
+    fn audio() -> AudioData {
+        AudioData {
+            bytes: vec![0; 4],
+            mime_type: "audio/wav",
+            file_name: "audio.wav".to_string(),
+        }
+    }
+
+    /// 先に成功した結果を使い、負けた側は中止する
+    #[tokio::test]
+    async fn first_success_wins_and_cancels_the_other() {
+        let (primary, primary_cancelled) = DelayedClient::boxed(500, Some("slow"));
+        let (secondary, secondary_cancelled) = DelayedClient::boxed(10, Some("fast"));
+        let racing = RacingTranscriptionAdapter::new(primary, secondary);
+
+        let output = racing.transcribe(audio(), "ja", None).await.unwrap();
+
+        assert_eq!(output.text, "fast");
+        assert!(primary_cancelled.load(Ordering::SeqCst));
+        assert!(!secondary_cancelled.load(Ordering::SeqCst));
+    }
+
+    /// 先に失敗した側は無視して、もう一方の結果を待つ
+    #[tokio::test]
+    async fn early_failure_falls_back_to_the_other_provider() {
+        let (primary, _) = DelayedClient::boxed(1, None);
+        let (secondary, _) = DelayedClient::boxed(30, Some("secondary"));
+        let racing = RacingTranscriptionAdapter::new(primary, secondary);
+
+        let output = racing.transcribe(audio(), "ja", None).await.unwrap();
+
+        assert_eq!(output.text, "secondary");
+    }
+
+    /// 両方失敗した場合は主バックエンドのエラーを返す
+    #[tokio::test]
+    async fn both_failures_report_primary_error() {
+        let (primary, _) = DelayedClient::boxed(30, None);
+        let (secondary, _) = DelayedClient::boxed(1, None);
+        let racing = RacingTranscriptionAdapter::new(primary, secondary);
+
+        let error = racing.transcribe(audio(), "ja", None).await.unwrap_err();
+
+        assert!(error.to_string().contains("failed after 30ms"), "{}", error);
+    }
+
+    /// モックサーバーへ向けた OpenAI アダプター
+    fn openai_client(server: &MockOpenAiServer) -> Box<dyn TranscriptionClient> {
+        // 計測ログの有無を決めるため転写経路は EnvConfig を参照する
+        EnvConfig::test_init();
+        let proxy = ProxyConfig {
+            all: None,
+            https: None,
+            http: None,
+        };
+        Box::new(
+            OpenAiTranscriptionAdapter::from_config(
+                &mock_transcription_config(server.base_url(), OpenAiRequestConfig::default()),
+                &proxy,
+            )
+            .unwrap(),
+        )
+    }
+
+    /// 主バックエンドが 429・500 を返しても、もう一方の API の転写結果を使う
+    #[tokio::test]
+    async fn rate_limited_or_failing_api_falls_over_to_the_other_api() {
+        for failure in [MockResponse::rate_limited(), MockResponse::server_error()] {
+            let primary = MockOpenAiServer::start(vec![failure]).await;
+            let secondary = MockOpenAiServer::start(vec![MockResponse::delayed(
+                Duration::from_millis(50),
+                MockResponse::success("予備"),
+            )])
+            .await;
+            let racing =
+                RacingTranscriptionAdapter::new(openai_client(&primary), openai_client(&secondary));
+
+            let output = racing.transcribe(audio(), "ja", None).await.unwrap();
+
+            assert_eq!(output.text, "予備");
+            assert_eq!(primary.requests().len(), 1);
+            assert_eq!(secondary.requests().len(), 1);
+        }
+    }
+}
@@ -0,0 +1,47 @@
+//! 画面ロック状態の取得
+//!
+//! `ioreg` が出力するコンソールセッション情報の `CGSSessionScreenIsLocked` を読み、
+//! Mac から離れた後の録音を検知できるようにする。
+use std::process::Command;
+use tokio::task::spawn_blocking;
+
+/// 画面がロックされていれば `true`。状態を取得できない場合は `None`
+pub async fn is_screen_locked() -> Option<bool> {
+    let output = spawn_blocking(|| Command::new("ioreg").args(["-n", "Root", "-d1"]).output())
+        .await
+        .ok()?
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    Some(parse_screen_locked(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// `ioreg -n Root -d1` の出力からロック状態を判定する
+fn parse_screen_locked(output: &str) -> bool {
+    output.contains("\"CGSSessionScreenIsLocked\"=Yes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_screen_locked;
+
+    /// ロック中のセッション情報を含む出力はロック中と判定する
+    #[test]
+    fn locked_session_is_detected() {
+        let output = r#"  | "IOConsoleUsers" = ({"kCGSSessionOnConsoleKey"=Yes,"CGSSessionScreenIsLocked"=Yes,"kCGSSessionUserNameKey"="user"})"#;
+
+        assert!(parse_screen_locked(output));
+    }
+
+    /// ロックキーがない出力はロックされていないと判定する
+    #[test]
+    fn unlocked_session_is_not_detected_as_locked() {
+        let output = r#"  | "IOConsoleUsers" = ({"kCGSSessionOnConsoleKey"=Yes,"kCGSSessionUserNameKey"="user"})"#;
+
+        assert!(!parse_screen_locked(output));
+    }
+}
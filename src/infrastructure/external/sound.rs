@@ -1,7 +1,11 @@
 //! 効果音および Apple Music 制御ユーティリティ。
-use std::process::{Command, Output};
+//!
+//! `native-sounds` / `media-control` feature を外したビルドでは何もしない。
+use std::process::{Child, Command, Output};
+use std::sync::Mutex;
 #[cfg(test)]
-use std::sync::{Mutex, OnceLock};
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
 use tokio::task::spawn_blocking;
 
 #[cfg(test)]
@@ -14,7 +18,7 @@ static TEST_OSASCRIPT_RUNNER: OnceLock<OsaScriptRunner> = OnceLock::new();
 #[cfg(test)]
 static TEST_SOUND_RUNNER: OnceLock<Mutex<Option<SoundRunner>>> = OnceLock::new();
 
-#[cfg(test)]
+#[cfg(all(test, feature = "media-control"))]
 fn set_test_osascript_runner(
     runner: impl Fn(String) -> std::io::Result<Output> + Send + Sync + 'static,
 ) {
@@ -55,15 +59,71 @@ fn run_sound(path: &'static str) -> bool {
     false
 }
 
+/// 再生中の afplay 子プロセス。終了済みのものは次の再生時に回収する
+static PLAYING_SOUNDS: Mutex<Vec<Child>> = Mutex::new(Vec::new());
+
+/// 効果音と Apple Music の操作を止めているか
+static SILENCED: AtomicBool = AtomicBool::new(false);
+
+/// 効果音の再生と Apple Music の操作をプロセス全体で止めます。
+///
+/// ソークテストのように、録音から入力までの実際の経路を模擬入力で繰り返すときに使います。
+pub fn silence() {
+    SILENCED.store(true, Ordering::Relaxed);
+}
+
+fn is_silenced() -> bool {
+    SILENCED.load(Ordering::Relaxed)
+}
+
+fn spawn_afplay(path: &'static str) {
+    if !cfg!(feature = "native-sounds") || is_silenced() {
+        return;
+    }
+    if let Ok(child) = Command::new("afplay").arg(path).spawn() {
+        track_sound_child(&PLAYING_SOUNDS, child);
+    }
+}
+
+fn track_sound_child(children: &Mutex<Vec<Child>>, child: Child) {
+    let mut children = children
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    // 再生を終えたプロセスをここで回収し、ゾンビとして残さない
+    children.retain_mut(|child| matches!(child.try_wait(), Ok(None)));
+    children.push(child);
+}
+
+/// 再生中の効果音を止めて子プロセスを回収し、停止した再生数を返します。
+///
+/// デーモンの終了処理から呼び出します。
+pub fn shutdown_sound_players() -> usize {
+    stop_sound_children(&PLAYING_SOUNDS)
+}
+
+fn stop_sound_children(children: &Mutex<Vec<Child>>) -> usize {
+    let pending = std::mem::take(
+        &mut *children
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()),
+    );
+    let mut stopped = 0;
+    for mut child in pending {
+        if matches!(child.try_wait(), Ok(None)) && child.kill().is_ok() {
+            stopped += 1;
+        }
+        let _ = child.wait();
+    }
+    stopped
+}
+
 /// 録音開始を示すサウンドを再生します。
 pub fn play_start_sound() {
     #[cfg(test)]
     if run_sound("/System/Library/Sounds/Ping.aiff") {
         return;
     }
-    let _ = Command::new("afplay")
-        .arg("/System/Library/Sounds/Ping.aiff")
-        .spawn();
+    spawn_afplay("/System/Library/Sounds/Ping.aiff");
 }
 
 /// 録音停止を示すサウンドを再生します。
@@ -72,9 +132,7 @@ pub fn play_stop_sound() {
     if run_sound("/System/Library/Sounds/Purr.aiff") {
         return;
     }
-    let _ = Command::new("afplay")
-        .arg("/System/Library/Sounds/Purr.aiff")
-        .spawn();
+    spawn_afplay("/System/Library/Sounds/Purr.aiff");
 }
 
 /// 転写完了を示すサウンドを再生します。
@@ -83,13 +141,14 @@ pub fn play_transcription_complete_sound() {
     if run_sound("/System/Library/Sounds/Glass.aiff") {
         return;
     }
-    let _ = Command::new("afplay")
-        .arg("/System/Library/Sounds/Glass.aiff")
-        .spawn();
+    spawn_afplay("/System/Library/Sounds/Glass.aiff");
 }
 
 /// Apple Music を一時停止し、元々再生中だったかを返します。
 pub async fn pause_apple_music() -> bool {
+    if !cfg!(feature = "media-control") || is_silenced() {
+        return false;
+    }
     // 直接 Music アプリを操作する - プロセスチェックをバイパス
     let playing_script = r#"
         try
@@ -136,6 +195,9 @@ pub async fn pause_apple_music() -> bool {
 
 /// Apple Music を再開します。
 pub fn resume_apple_music() {
+    if !cfg!(feature = "media-control") || is_silenced() {
+        return;
+    }
     // 直接 Music アプリを操作する - プロセスチェックをバイパス
     let play_script = r#"
         try
@@ -178,13 +240,16 @@ pub fn resume_apple_music() {
 
 #[cfg(all(test, unix))]
 mod tests {
-    use super::{pause_apple_music, set_test_osascript_runner};
-    use std::time::Duration;
-    use std::{os::unix::process::ExitStatusExt, process::Output};
+    use super::{stop_sound_children, track_sound_child};
 
     /// osascript 待機中もランタイムが停止しない
+    #[cfg(feature = "media-control")]
     #[tokio::test(flavor = "current_thread")]
     async fn pause_apple_music_yields_while_waiting() {
+        use super::{pause_apple_music, set_test_osascript_runner};
+        use std::time::Duration;
+        use std::{os::unix::process::ExitStatusExt, process::Output};
+
         set_test_osascript_runner(|_script| {
             std::thread::sleep(Duration::from_millis(100));
             Ok(Output {
@@ -213,4 +278,19 @@ mod tests {
         );
         assert!(pause_task.await);
     }
+
+    /// 終了処理で再生中の子プロセスを停止して回収する
+    #[test]
+    fn shutdown_stops_pending_sound_processes() {
+        // 他のテストが鳴らす効果音と数が混ざらないよう、テスト専用の一覧で確かめる
+        let children = std::sync::Mutex::new(Vec::new());
+        let child = std::process::Command::new("sleep")
+            .arg("5")
+            .spawn()
+            .expect("sleep should spawn");
+        track_sound_child(&children, child);
+
+        assert_eq!(stop_sound_children(&children), 1);
+        assert_eq!(stop_sound_children(&children), 0);
+    }
 }
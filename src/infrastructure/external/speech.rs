@@ -0,0 +1,34 @@
+//! 転写結果の読み上げ
+//!
+//! macOS の `say` コマンド（AVSpeechSynthesizer と同じシステム音声）を使い、
+//! 画面を見なくても入力内容を確認できるようにする。
+use std::process::Command;
+use tokio::task::spawn_blocking;
+
+/// 読み上げエラー
+#[derive(Debug, thiserror::Error)]
+pub enum SpeechError {
+    #[error("failed to run say: {0}")]
+    Spawn(#[source] std::io::Error),
+    #[error("say exited with status {0}")]
+    Failed(std::process::ExitStatus),
+    #[error("speech task panicked")]
+    Join,
+}
+
+/// テキストを読み上げ、読み終わるまで待つ
+pub async fn read_back(text: &str) -> Result<(), SpeechError> {
+    if text.trim().is_empty() {
+        return Ok(());
+    }
+
+    let text = text.to_string();
+    let status = spawn_blocking(move || Command::new("say").arg("--").arg(text).status())
+        .await
+        .map_err(|_| SpeechError::Join)?
+        .map_err(SpeechError::Spawn)?;
+    if !status.success() {
+        return Err(SpeechError::Failed(status));
+    }
+    Ok(())
+}
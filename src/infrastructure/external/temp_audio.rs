@@ -0,0 +1,184 @@
+//! 外部コマンドへ渡す一時音声ファイル
+//!
+//! 録音内容を他ユーザーから読めないよう、ユーザーごとのディレクトリ（0700）に
+//! 推測できない名前・0600 の権限で作成する。破棄時に削除し、デーモン終了時には
+//! 残っているファイルもまとめて削除する。
+
+use std::fs::DirBuilder;
+use std::io::{self, Write};
+use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use tempfile::TempPath;
+
+use crate::utils::user::current_uid;
+
+/// デーモン全体で作成済みかつ未削除の一時音声ファイル
+static LIVE_TEMP_AUDIO: TempAudioRegistry = TempAudioRegistry::new();
+
+/// 作成済みで未削除の一時音声ファイルの一覧
+#[derive(Debug)]
+struct TempAudioRegistry {
+    paths: Mutex<Vec<PathBuf>>,
+}
+
+impl TempAudioRegistry {
+    const fn new() -> Self {
+        Self {
+            paths: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn paths(&self) -> std::sync::MutexGuard<'_, Vec<PathBuf>> {
+        self.paths
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// 残っている一時音声ファイルを削除し、削除した数を返す
+    fn cleanup(&self) -> usize {
+        let pending = std::mem::take(&mut *self.paths());
+        pending
+            .iter()
+            .filter(|path| std::fs::remove_file(path).is_ok())
+            .count()
+    }
+}
+
+/// 破棄時に削除される一時音声ファイル
+#[derive(Debug)]
+pub struct TempAudioFile {
+    path: TempPath,
+    registry: &'static TempAudioRegistry,
+}
+
+impl TempAudioFile {
+    /// ユーザーごとの一時ディレクトリへ `bytes` を書き込んで作成する
+    pub fn create(bytes: &[u8], extension: &str) -> io::Result<Self> {
+        Self::create_in(&user_temp_dir()?, bytes, extension, &LIVE_TEMP_AUDIO)
+    }
+
+    fn create_in(
+        dir: &Path,
+        bytes: &[u8],
+        extension: &str,
+        registry: &'static TempAudioRegistry,
+    ) -> io::Result<Self> {
+        // NamedTempFile は O_EXCL・0600・ランダムな名前で作成する
+        let mut file = tempfile::Builder::new()
+            .prefix("audio_")
+            .suffix(&format!(".{}", extension))
+            .tempfile_in(dir)?;
+        file.write_all(bytes)?;
+        file.flush()?;
+
+        let path = file.into_temp_path();
+        registry.paths().push(path.to_path_buf());
+        Ok(Self { path, registry })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempAudioFile {
+    fn drop(&mut self) {
+        // ファイル自体は TempPath の破棄で削除される
+        self.registry
+            .paths()
+            .retain(|path| path.as_path() != &*self.path);
+    }
+}
+
+/// 残っている一時音声ファイルを削除し、削除した数を返す
+///
+/// デーモンの終了処理から呼び出す。
+pub fn cleanup_temp_audio_files() -> usize {
+    LIVE_TEMP_AUDIO.cleanup()
+}
+
+/// `$TMPDIR/voice_input-<uid>` を 0700 で用意して返す
+fn user_temp_dir() -> io::Result<PathBuf> {
+    let uid = current_uid();
+    let dir = std::env::temp_dir().join(format!("voice_input-{}", uid));
+    ensure_private_dir(&dir, uid)?;
+    Ok(dir)
+}
+
+/// 自分が所有するディレクトリ（シンボリックリンク不可）を 0700 にして用意する
+pub(crate) fn ensure_private_dir(dir: &Path, uid: u32) -> io::Result<()> {
+    match DirBuilder::new().mode(0o700).create(dir) {
+        Ok(()) => return Ok(()),
+        Err(error) if error.kind() == io::ErrorKind::AlreadyExists => {}
+        Err(error) => return Err(error),
+    }
+
+    let metadata = std::fs::symlink_metadata(dir)?;
+    if !metadata.is_dir() || metadata.uid() != uid {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("{} is not a directory owned by uid {}", dir.display(), uid),
+        ));
+    }
+    if metadata.mode() & 0o077 != 0 {
+        std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// 一時ファイルは 0600 で作成され、破棄時に削除される
+    #[test]
+    fn temp_audio_is_private_and_removed_on_drop() {
+        static REGISTRY: TempAudioRegistry = TempAudioRegistry::new();
+        let dir = TempDir::new().unwrap();
+        let file = TempAudioFile::create_in(dir.path(), b"RIFF", "wav", &REGISTRY).unwrap();
+        let path = file.path().to_path_buf();
+
+        let metadata = std::fs::metadata(&path).unwrap();
+        assert_eq!(metadata.mode() & 0o777, 0o600);
+        assert_eq!(std::fs::read(&path).unwrap(), b"RIFF");
+        assert_eq!(path.extension().unwrap(), "wav");
+
+        drop(file);
+        assert!(!path.exists());
+        assert!(REGISTRY.paths().is_empty());
+    }
+
+    /// 終了処理で残っている一時ファイルをまとめて削除する
+    #[test]
+    fn cleanup_removes_live_temp_files() {
+        // デーモン全体の一覧を空にしないよう、テスト専用の一覧で確かめる
+        static REGISTRY: TempAudioRegistry = TempAudioRegistry::new();
+        let dir = TempDir::new().unwrap();
+        let file = TempAudioFile::create_in(dir.path(), b"fLaC", "flac", &REGISTRY).unwrap();
+        let path = file.path().to_path_buf();
+
+        assert_eq!(REGISTRY.cleanup(), 1);
+        assert!(!path.exists());
+        drop(file);
+    }
+
+    /// 既存のディレクトリは権限を 0700 に絞り、他人のものやリンクは拒否する
+    #[test]
+    fn private_dir_is_tightened_and_links_are_rejected() {
+        let uid = current_uid();
+        let root = TempDir::new().unwrap();
+        let dir = root.path().join("voice_input");
+        std::fs::create_dir(&dir).unwrap();
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        ensure_private_dir(&dir, uid).unwrap();
+        assert_eq!(std::fs::metadata(&dir).unwrap().mode() & 0o777, 0o700);
+
+        let link = root.path().join("link");
+        std::os::unix::fs::symlink(&dir, &link).unwrap();
+        assert!(ensure_private_dir(&link, uid).is_err());
+    }
+}
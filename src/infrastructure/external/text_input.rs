@@ -3,18 +3,18 @@
 //! 常駐ワーカーを使用してテキストを入力する機能を提供
 
 use crate::infrastructure::external::text_input_worker::{
-    TextInputEngine, TextInputWorkerError, TextInputWorkerHandle, start_text_input_worker,
+    TextInputEngine, TextInputWorkerError, start_text_input_worker,
 };
 use crate::utils::profiling;
-use std::sync::{Mutex, OnceLock};
+use std::sync::{Arc, Mutex, OnceLock};
 
-static TEXT_INPUT_WORKER: OnceLock<Mutex<Option<TextInputWorkerHandle>>> = OnceLock::new();
+static TEXT_INPUT_WORKER: OnceLock<Mutex<Option<Arc<dyn TextInputEngine>>>> = OnceLock::new();
 
-fn worker_slot() -> &'static Mutex<Option<TextInputWorkerHandle>> {
+fn worker_slot() -> &'static Mutex<Option<Arc<dyn TextInputEngine>>> {
     TEXT_INPUT_WORKER.get_or_init(|| Mutex::new(None))
 }
 
-fn current_worker_handle() -> Result<TextInputWorkerHandle, TextInputWorkerError> {
+fn current_worker_handle() -> Result<Arc<dyn TextInputEngine>, TextInputWorkerError> {
     worker_slot()
         .lock()
         .map_err(|e| TextInputWorkerError::ChannelClosed(format!("worker lock poisoned: {}", e)))?
@@ -24,13 +24,21 @@ fn current_worker_handle() -> Result<TextInputWorkerHandle, TextInputWorkerError
         })
 }
 
-fn replace_worker_handle() -> Result<TextInputWorkerHandle, TextInputWorkerError> {
-    let handle = start_text_input_worker()?;
+fn replace_worker_handle() -> Result<Arc<dyn TextInputEngine>, TextInputWorkerError> {
+    let handle: Arc<dyn TextInputEngine> = Arc::new(start_text_input_worker()?);
+    install_engine(handle.clone())?;
+    Ok(handle)
+}
+
+/// 以降の入力に使うエンジンを差し替える
+///
+/// 常駐ワーカーの代わりに模擬エンジンを使い、実際のキー入力を行わずに転写経路を通すためにも使う。
+pub fn install_engine(engine: Arc<dyn TextInputEngine>) -> Result<(), TextInputWorkerError> {
     let mut worker = worker_slot()
         .lock()
         .map_err(|e| TextInputWorkerError::ChannelClosed(format!("worker lock poisoned: {}", e)))?;
-    *worker = Some(handle.clone());
-    Ok(handle)
+    *worker = Some(engine);
+    Ok(())
 }
 
 async fn run_with_recovery<F, Fut>(
@@ -39,7 +47,7 @@ async fn run_with_recovery<F, Fut>(
     f: F,
 ) -> Result<(), TextInputWorkerError>
 where
-    F: Fn(TextInputWorkerHandle) -> Fut,
+    F: Fn(Arc<dyn TextInputEngine>) -> Fut,
     Fut: std::future::Future<Output = Result<(), TextInputWorkerError>>,
 {
     let timer = profiling::Timer::start(metric_name);
@@ -160,3 +168,13 @@ pub async fn select_recent_range(
     )
     .await
 }
+
+/// Return キーを押す（`with_shift` なら Shift+Return）
+pub async fn press_return(with_shift: bool) -> Result<(), TextInputWorkerError> {
+    run_with_recovery(
+        "text_input.worker_press_return",
+        format!("with_shift={}", with_shift),
+        |handle| async move { handle.press_return(with_shift).await },
+    )
+    .await
+}
@@ -1,8 +1,10 @@
 //! 常駐ワーカー向けのテキスト入力インターフェース定義
 //!
 //! enigo を同一プロセスの別スレッドで常駐させる前提の型を提供する。
+//! `accessibility-input` feature を外したビルドでは、ワーカーはすべての入力要求を失敗として返す。
 
 use async_trait::async_trait;
+#[cfg(feature = "accessibility-input")]
 use enigo::{
     Direction::{Click, Press, Release},
     Enigo, Key, Keyboard, Settings,
@@ -26,6 +28,9 @@ pub enum TextInputWorkerError {
     /// ワーカーとのチャネルが切断された
     #[error("Text input channel closed: {0}")]
     ChannelClosed(String),
+    /// 直接入力を組み込まずにビルドされている
+    #[error("Text input is not available: {0}")]
+    Unavailable(String),
 }
 
 impl From<TextInputWorkerError> for VoiceInputError {
@@ -43,6 +48,9 @@ impl From<TextInputWorkerError> for VoiceInputError {
             TextInputWorkerError::ChannelClosed(msg) => {
                 VoiceInputError::TextInputWorkerChannelClosed(msg)
             }
+            TextInputWorkerError::Unavailable(msg) => {
+                VoiceInputError::TextInputWorkerInitFailed(msg)
+            }
         }
     }
 }
@@ -78,6 +86,13 @@ pub enum TextInputRequest {
         /// 完了通知用のチャネル
         completion: oneshot::Sender<Result<(), TextInputWorkerError>>,
     },
+    /// Return キーを押す
+    PressReturn {
+        /// Shift を押しながら押す（チャットアプリで送信せずに改行する）
+        with_shift: bool,
+        /// 完了通知用のチャネル
+        completion: oneshot::Sender<Result<(), TextInputWorkerError>>,
+    },
 }
 
 impl TextInputRequest {
@@ -86,7 +101,8 @@ impl TextInputRequest {
         match self {
             TextInputRequest::TypeText { completion, .. }
             | TextInputRequest::ReplaceSuffix { completion, .. }
-            | TextInputRequest::SelectRecentRange { completion, .. } => completion,
+            | TextInputRequest::SelectRecentRange { completion, .. }
+            | TextInputRequest::PressReturn { completion, .. } => completion,
         }
     }
 }
@@ -129,6 +145,9 @@ pub trait TextInputEngine: Send + Sync {
         trailing_char_count: usize,
         char_count: usize,
     ) -> Result<(), TextInputWorkerError>;
+
+    /// Return キーを押す
+    async fn press_return(&self, with_shift: bool) -> Result<(), TextInputWorkerError>;
 }
 
 /// ワーカーへの送信ハンドル
@@ -227,6 +246,21 @@ impl TextInputWorkerHandle {
             .map_err(|e| TextInputWorkerError::ChannelClosed(format!("send failed: {}", e)))?;
         Ok(rx)
     }
+
+    /// Return キーの押下をリクエストする
+    pub fn send_press_return(
+        &self,
+        with_shift: bool,
+    ) -> Result<oneshot::Receiver<Result<(), TextInputWorkerError>>, TextInputWorkerError> {
+        let (tx, rx) = oneshot::channel();
+        self.sender
+            .send(TextInputRequest::PressReturn {
+                with_shift,
+                completion: tx,
+            })
+            .map_err(|e| TextInputWorkerError::ChannelClosed(format!("send failed: {}", e)))?;
+        Ok(rx)
+    }
 }
 
 #[async_trait]
@@ -277,6 +311,13 @@ impl TextInputEngine for TextInputWorkerHandle {
             TextInputWorkerError::ChannelClosed("completion channel dropped".to_string())
         })?
     }
+
+    async fn press_return(&self, with_shift: bool) -> Result<(), TextInputWorkerError> {
+        let receiver = self.send_press_return(with_shift)?;
+        receiver.await.map_err(|_| {
+            TextInputWorkerError::ChannelClosed("completion channel dropped".to_string())
+        })?
+    }
 }
 
 /// テキスト入力ワーカーを起動し、送信ハンドルを返す
@@ -294,6 +335,17 @@ pub fn start_text_input_worker() -> Result<TextInputWorkerHandle, TextInputWorke
     Ok(handle)
 }
 
+/// 直接入力を組み込まないビルドでは、要求を受けるたびに理由を添えて失敗を返す
+#[cfg(not(feature = "accessibility-input"))]
+fn run_worker(mut rx: mpsc::UnboundedReceiver<TextInputRequest>) {
+    while let Some(req) = rx.blocking_recv() {
+        let _ = req.completion().send(Err(TextInputWorkerError::Unavailable(
+            "built without the accessibility-input feature".to_string(),
+        )));
+    }
+}
+
+#[cfg(feature = "accessibility-input")]
 fn run_worker(mut rx: mpsc::UnboundedReceiver<TextInputRequest>) {
     let settings = Settings::default();
 
@@ -338,10 +390,18 @@ fn run_worker(mut rx: mpsc::UnboundedReceiver<TextInputRequest>) {
                     select_recent_range_with_enigo(&mut enigo, trailing_char_count, char_count);
                 let _ = completion.send(result);
             }
+            TextInputRequest::PressReturn {
+                with_shift,
+                completion,
+            } => {
+                let result = press_return_with_enigo(&mut enigo, with_shift);
+                let _ = completion.send(result);
+            }
         }
     }
 }
 
+#[cfg(feature = "accessibility-input")]
 fn type_text_with_enigo(
     enigo: &mut Enigo,
     text: &str,
@@ -353,6 +413,7 @@ fn type_text_with_enigo(
     input_text(enigo, text, mode)
 }
 
+#[cfg(feature = "accessibility-input")]
 fn replace_suffix_with_enigo(
     enigo: &mut Enigo,
     delete_count: usize,
@@ -372,6 +433,7 @@ fn replace_suffix_with_enigo(
     input_text(enigo, text, mode)
 }
 
+#[cfg(feature = "accessibility-input")]
 fn prepare_input(enigo: &mut Enigo) -> Result<(), TextInputWorkerError> {
     std::thread::sleep(std::time::Duration::from_millis(50));
     enigo
@@ -381,6 +443,7 @@ fn prepare_input(enigo: &mut Enigo) -> Result<(), TextInputWorkerError> {
     Ok(())
 }
 
+#[cfg(feature = "accessibility-input")]
 fn input_text(
     enigo: &mut Enigo,
     text: &str,
@@ -396,6 +459,7 @@ fn input_text(
     Ok(())
 }
 
+#[cfg(feature = "accessibility-input")]
 fn select_recent_range_with_enigo(
     enigo: &mut Enigo,
     trailing_char_count: usize,
@@ -434,6 +498,33 @@ fn select_recent_range_with_enigo(
     Ok(())
 }
 
+#[cfg(feature = "accessibility-input")]
+fn press_return_with_enigo(
+    enigo: &mut Enigo,
+    with_shift: bool,
+) -> Result<(), TextInputWorkerError> {
+    prepare_input(enigo)?;
+
+    if !with_shift {
+        enigo
+            .key(Key::Return, Click)
+            .map_err(|e| TextInputWorkerError::InputFailed(e.to_string()))?;
+        return Ok(());
+    }
+
+    enigo
+        .key(Key::Shift, Press)
+        .map_err(|e| TextInputWorkerError::InputFailed(e.to_string()))?;
+    let press_result = enigo
+        .key(Key::Return, Click)
+        .map_err(|e| TextInputWorkerError::InputFailed(e.to_string()));
+    let release_result = enigo
+        .key(Key::Shift, Release)
+        .map_err(|e| TextInputWorkerError::InputFailed(e.to_string()));
+    press_result?;
+    release_result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -453,9 +544,9 @@ mod tests {
                 assert_eq!(text, "hello");
                 assert_eq!(mode, TextInputExecutionMode::Standalone);
             }
-            TextInputRequest::ReplaceSuffix { .. } | TextInputRequest::SelectRecentRange { .. } => {
-                panic!("unexpected request")
-            }
+            TextInputRequest::ReplaceSuffix { .. }
+            | TextInputRequest::SelectRecentRange { .. }
+            | TextInputRequest::PressReturn { .. } => panic!("unexpected request"),
         }
     }
 
@@ -474,9 +565,9 @@ mod tests {
                 assert_eq!(text, "hello");
                 assert_eq!(mode, TextInputExecutionMode::Continuous);
             }
-            TextInputRequest::ReplaceSuffix { .. } | TextInputRequest::SelectRecentRange { .. } => {
-                panic!("unexpected request")
-            }
+            TextInputRequest::ReplaceSuffix { .. }
+            | TextInputRequest::SelectRecentRange { .. }
+            | TextInputRequest::PressReturn { .. } => panic!("unexpected request"),
         }
     }
 
@@ -516,9 +607,9 @@ mod tests {
                 assert_eq!(text, "world");
                 assert_eq!(mode, TextInputExecutionMode::Standalone);
             }
-            TextInputRequest::TypeText { .. } | TextInputRequest::SelectRecentRange { .. } => {
-                panic!("unexpected request")
-            }
+            TextInputRequest::TypeText { .. }
+            | TextInputRequest::SelectRecentRange { .. }
+            | TextInputRequest::PressReturn { .. } => panic!("unexpected request"),
         }
     }
 
@@ -543,9 +634,9 @@ mod tests {
                 assert_eq!(text, "world");
                 assert_eq!(mode, TextInputExecutionMode::Continuous);
             }
-            TextInputRequest::TypeText { .. } | TextInputRequest::SelectRecentRange { .. } => {
-                panic!("unexpected request")
-            }
+            TextInputRequest::TypeText { .. }
+            | TextInputRequest::SelectRecentRange { .. }
+            | TextInputRequest::PressReturn { .. } => panic!("unexpected request"),
         }
     }
 
@@ -568,9 +659,27 @@ mod tests {
                 assert_eq!(trailing_char_count, 2);
                 assert_eq!(char_count, 4);
             }
-            TextInputRequest::TypeText { .. } | TextInputRequest::ReplaceSuffix { .. } => {
-                panic!("unexpected request")
-            }
+            TextInputRequest::TypeText { .. }
+            | TextInputRequest::ReplaceSuffix { .. }
+            | TextInputRequest::PressReturn { .. } => panic!("unexpected request"),
+        }
+    }
+
+    /// Return 押下リクエストは Shift 併用の有無を保持できる
+    #[test]
+    fn press_return_request_holds_shift_flag() {
+        let (tx, mut rx) = mpsc::unbounded_channel::<TextInputRequest>();
+        let handle = TextInputWorkerHandle::new(tx);
+
+        let receiver = handle.send_press_return(true);
+
+        assert!(receiver.is_ok());
+        let request = rx.try_recv().expect("request should be sent");
+        match request {
+            TextInputRequest::PressReturn { with_shift, .. } => assert!(with_shift),
+            TextInputRequest::TypeText { .. }
+            | TextInputRequest::ReplaceSuffix { .. }
+            | TextInputRequest::SelectRecentRange { .. } => panic!("unexpected request"),
         }
     }
 }
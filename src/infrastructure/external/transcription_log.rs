@@ -95,6 +95,7 @@ mod tests {
                 tokens: vec![crate::domain::transcription::TranscriptionToken::new(
                     "生", -0.4,
                 )],
+                label: None,
             })
             .unwrap();
 
@@ -134,6 +135,7 @@ mod tests {
                 tokens: vec![crate::domain::transcription::TranscriptionToken::new(
                     "追加", -0.2,
                 )],
+                label: None,
             })
             .unwrap();
 
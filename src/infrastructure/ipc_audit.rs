@@ -0,0 +1,151 @@
+//! IPC コマンドの監査ログ
+//!
+//! # 責任
+//! - 直近の IPC コマンド（種別・送信元・時刻・結果・所要時間）を上限付きで保持
+//! - `voice_input audit` 向けの整形
+//!
+//! 自動化スクリプトが意図しないコマンドを送っている場合に追跡できるよう、
+//! プロセス全体で共有するメモリ上のリングバッファとして提供する。
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Local};
+
+/// 保持するコマンド数の上限
+pub const AUDIT_CAPACITY: usize = 200;
+
+/// 1 コマンドの記録
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditEntry {
+    /// コマンド種別
+    pub command: &'static str,
+    /// 送信元（接続元プロセスの pid など）
+    pub source: String,
+    pub received_at: DateTime<Local>,
+    /// 失敗時の理由。成功時は `None`
+    pub error: Option<String>,
+    pub duration: Duration,
+}
+
+/// 直近のコマンドを古い順に保持するリングバッファ
+#[derive(Debug, Clone)]
+pub struct IpcAuditLog {
+    entries: VecDeque<AuditEntry>,
+    capacity: usize,
+}
+
+impl IpcAuditLog {
+    /// 上限 `capacity` 件の空の記録を作成する
+    pub const fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    /// 記録を追加し、上限を超えた分は古いものから捨てる
+    pub fn record(&mut self, entry: AuditEntry) {
+        if self.capacity == 0 {
+            return;
+        }
+        while self.entries.len() >= self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+
+    /// 古い順に記録を返す
+    pub fn entries(&self) -> impl Iterator<Item = &AuditEntry> {
+        self.entries.iter()
+    }
+
+    /// 表示用の行に整形する
+    pub fn format_lines(&self) -> Vec<String> {
+        if self.entries.is_empty() {
+            return vec!["audit: no commands recorded".to_string()];
+        }
+
+        self.entries
+            .iter()
+            .map(|entry| {
+                let outcome = match &entry.error {
+                    None => "ok".to_string(),
+                    Some(error) => format!("error: {}", error),
+                };
+                format!(
+                    "[{}] {} from {} {}ms {}",
+                    entry.received_at.format("%Y-%m-%d %H:%M:%S%.3f"),
+                    entry.command,
+                    entry.source,
+                    entry.duration.as_millis(),
+                    outcome
+                )
+            })
+            .collect()
+    }
+}
+
+static LOG: Mutex<IpcAuditLog> = Mutex::new(IpcAuditLog::new(AUDIT_CAPACITY));
+
+/// コマンドの処理結果を記録する
+pub fn record(entry: AuditEntry) {
+    LOG.lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .record(entry);
+}
+
+/// 現在の記録の複製を返す
+pub fn snapshot() -> IpcAuditLog {
+    LOG.lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AuditEntry, IpcAuditLog};
+    use chrono::{Local, TimeZone};
+    use std::time::Duration;
+
+    fn entry(command: &'static str, error: Option<&str>) -> AuditEntry {
+        AuditEntry {
+            command,
+            source: "pid=42".to_string(),
+            received_at: Local.with_ymd_and_hms(2026, 1, 1, 9, 0, 0).unwrap(),
+            error: error.map(str::to_string),
+            duration: Duration::from_millis(12),
+        }
+    }
+
+    /// 上限を超えると古い記録から捨てる
+    #[test]
+    fn oldest_entries_are_evicted_at_capacity() {
+        let mut log = IpcAuditLog::new(2);
+        log.record(entry("Start", None));
+        log.record(entry("Stop", None));
+        log.record(entry("Status", None));
+
+        let commands: Vec<_> = log.entries().map(|e| e.command).collect();
+        assert_eq!(commands, vec!["Stop", "Status"]);
+    }
+
+    /// 送信元・所要時間・結果を一行ずつ整形する
+    #[test]
+    fn entries_are_formatted_with_source_duration_and_outcome() {
+        let mut log = IpcAuditLog::new(10);
+        assert_eq!(log.format_lines(), vec!["audit: no commands recorded"]);
+
+        log.record(entry("Start", None));
+        log.record(entry("Stop", Some("recording not started")));
+
+        assert_eq!(
+            log.format_lines(),
+            vec![
+                "[2026-01-01 09:00:00.000] Start from pid=42 12ms ok",
+                "[2026-01-01 09:00:00.000] Stop from pid=42 12ms error: recording not started",
+            ]
+        );
+    }
+}
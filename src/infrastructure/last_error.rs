@@ -0,0 +1,166 @@
+//! サブシステム別の直近エラー記録
+//!
+//! # 責任
+//! - サブシステムごとに最後に発生したエラーと発生時刻を保持
+//! - `voice_input status --verbose` 向けの整形
+//!
+//! デーモン標準出力を追わなくても断続的な失敗を確認できるようにするため、
+//! プロセス全体で共有するレジストリとして提供する。
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Local};
+
+/// エラー発生元のサブシステム
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Subsystem {
+    Audio,
+    Transcription,
+    TextInput,
+    Ipc,
+}
+
+impl Subsystem {
+    /// 表示用の名前を返す
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Audio => "audio",
+            Self::Transcription => "transcription",
+            Self::TextInput => "text_input",
+            Self::Ipc => "ipc",
+        }
+    }
+}
+
+/// 直近のエラー内容
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LastError {
+    pub message: String,
+    pub occurred_at: DateTime<Local>,
+}
+
+/// サブシステムごとの直近エラーを保持するレジストリ
+#[derive(Debug, Clone, Default)]
+pub struct LastErrorRegistry {
+    entries: BTreeMap<Subsystem, LastError>,
+}
+
+impl LastErrorRegistry {
+    /// 空のレジストリを作成する
+    pub const fn new() -> Self {
+        Self {
+            entries: BTreeMap::new(),
+        }
+    }
+
+    /// エラーを記録し、同じサブシステムの古い記録を置き換える
+    pub fn record(
+        &mut self,
+        subsystem: Subsystem,
+        message: impl Into<String>,
+        occurred_at: DateTime<Local>,
+    ) {
+        self.entries.insert(
+            subsystem,
+            LastError {
+                message: message.into(),
+                occurred_at,
+            },
+        );
+    }
+
+    /// 指定サブシステムの直近エラーを返す
+    pub fn get(&self, subsystem: Subsystem) -> Option<&LastError> {
+        self.entries.get(&subsystem)
+    }
+
+    /// 記録が一件もないか
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// ステータス表示用の行に整形する
+    pub fn format_lines(&self) -> Vec<String> {
+        if self.entries.is_empty() {
+            return vec!["last_errors=none".to_string()];
+        }
+
+        self.entries
+            .iter()
+            .map(|(subsystem, error)| {
+                format!(
+                    "last_error.{}=[{}] {}",
+                    subsystem.as_str(),
+                    error.occurred_at.format("%Y-%m-%d %H:%M:%S"),
+                    error.message
+                )
+            })
+            .collect()
+    }
+}
+
+static REGISTRY: Mutex<LastErrorRegistry> = Mutex::new(LastErrorRegistry::new());
+
+/// 現在時刻でエラーを記録する
+pub fn record(subsystem: Subsystem, message: impl Into<String>) {
+    let mut registry = REGISTRY
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    registry.record(subsystem, message, Local::now());
+}
+
+/// 現在のレジストリの複製を返す
+pub fn snapshot() -> LastErrorRegistry {
+    REGISTRY
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LastErrorRegistry, Subsystem};
+    use chrono::{Local, TimeZone};
+
+    /// 記録がない場合は none と表示する
+    #[test]
+    fn empty_registry_formats_as_none() {
+        let registry = LastErrorRegistry::new();
+
+        assert_eq!(registry.format_lines(), vec!["last_errors=none"]);
+    }
+
+    /// 同じサブシステムの記録は最新のもので置き換わる
+    #[test]
+    fn newer_error_replaces_previous_one() {
+        let mut registry = LastErrorRegistry::new();
+        let first = Local.with_ymd_and_hms(2026, 1, 1, 9, 0, 0).unwrap();
+        let second = Local.with_ymd_and_hms(2026, 1, 1, 9, 5, 0).unwrap();
+
+        registry.record(Subsystem::Audio, "device lost", first);
+        registry.record(Subsystem::Audio, "stream error", second);
+
+        let error = registry.get(Subsystem::Audio).unwrap();
+        assert_eq!(error.message, "stream error");
+        assert_eq!(error.occurred_at, second);
+    }
+
+    /// サブシステムごとに時刻付きで一行ずつ整形する
+    #[test]
+    fn entries_are_formatted_per_subsystem_with_timestamp() {
+        let mut registry = LastErrorRegistry::new();
+        let at = Local.with_ymd_and_hms(2026, 1, 1, 9, 0, 0).unwrap();
+
+        registry.record(Subsystem::TextInput, "enigo failed", at);
+        registry.record(Subsystem::Audio, "device lost", at);
+
+        assert_eq!(
+            registry.format_lines(),
+            vec![
+                "last_error.audio=[2026-01-01 09:00:00] device lost",
+                "last_error.text_input=[2026-01-01 09:00:00] enigo failed",
+            ]
+        );
+    }
+}
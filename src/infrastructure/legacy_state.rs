@@ -0,0 +1,204 @@
+//! 旧バージョンが残したファイルの掃除
+//!
+//! # 責任
+//! - UID で分ける前の既定ソケット・録音状態ファイル・転写用一時音声の検出
+//! - 自分が所有し、使われていないものだけを削除して結果をまとめる
+//!
+//! 長く使っている環境に古いファイルが溜まらないよう、デーモン起動時に一度だけ実行する。
+//! どれも現在のバージョンでは参照しないため、移行はせずに削除する。
+
+use std::io;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+
+use crate::utils::user::current_uid;
+
+/// UID で分ける前の既定ソケット名（`/tmp` 直下）
+const LEGACY_SOCKET_NAME: &str = "voice_input.sock";
+/// 旧バージョンの録音状態ファイル名（`/tmp` 直下）
+const LEGACY_STATUS_FILE_NAME: &str = "voice_input_recording_status.txt";
+/// ユーザーごとのディレクトリへ移す前の転写用一時音声の接頭辞（一時ディレクトリ直下）
+const LEGACY_TEMP_AUDIO_PREFIX: &str = "voice_input_mlx_";
+
+/// 掃除の結果
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct LegacyCleanupReport {
+    /// 削除したファイル
+    pub removed: Vec<PathBuf>,
+    /// 見つけたが残したファイルと理由
+    pub kept: Vec<(PathBuf, String)>,
+}
+
+impl LegacyCleanupReport {
+    /// 何も見つからなかったか
+    pub fn is_empty(&self) -> bool {
+        self.removed.is_empty() && self.kept.is_empty()
+    }
+
+    /// ログ用の行に整形する
+    pub fn format_lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if !self.removed.is_empty() {
+            lines.push(format!(
+                "Removed {} legacy file(s): {}",
+                self.removed.len(),
+                self.removed
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        for (path, reason) in &self.kept {
+            lines.push(format!("Kept legacy file {}: {}", path.display(), reason));
+        }
+        lines
+    }
+}
+
+/// 旧バージョンのファイルを掃除する。`current_socket` は使用中のため対象外にする
+pub fn cleanup_legacy_state(current_socket: &Path) -> LegacyCleanupReport {
+    let uid = current_uid();
+    cleanup_in(
+        Path::new("/tmp"),
+        &std::env::temp_dir(),
+        current_socket,
+        uid,
+    )
+}
+
+fn cleanup_in(
+    tmp_root: &Path,
+    temp_dir: &Path,
+    current_socket: &Path,
+    uid: u32,
+) -> LegacyCleanupReport {
+    let mut report = LegacyCleanupReport::default();
+
+    let socket = tmp_root.join(LEGACY_SOCKET_NAME);
+    if socket != current_socket {
+        clean_socket(&socket, uid, &mut report);
+    }
+    clean_file(&tmp_root.join(LEGACY_STATUS_FILE_NAME), uid, &mut report);
+
+    if let Ok(entries) = std::fs::read_dir(temp_dir) {
+        let mut temp_audio: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with(LEGACY_TEMP_AUDIO_PREFIX))
+            })
+            .collect();
+        temp_audio.sort();
+        for path in temp_audio {
+            clean_file(&path, uid, &mut report);
+        }
+    }
+
+    report
+}
+
+/// 応答のない自分のソケットだけを削除する
+fn clean_socket(path: &Path, uid: u32, report: &mut LegacyCleanupReport) {
+    let Ok(metadata) = std::fs::symlink_metadata(path) else {
+        return;
+    };
+    if metadata.uid() != uid {
+        return;
+    }
+    if std::os::unix::net::UnixStream::connect(path).is_ok() {
+        report.kept.push((
+            path.to_path_buf(),
+            "an older daemon is still listening; stop it to remove the socket".to_string(),
+        ));
+        return;
+    }
+    record_removal(path, std::fs::remove_file(path), report);
+}
+
+/// 自分が所有する通常ファイルだけを削除する（リンクや他ユーザーのファイルは触らない）
+fn clean_file(path: &Path, uid: u32, report: &mut LegacyCleanupReport) {
+    let Ok(metadata) = std::fs::symlink_metadata(path) else {
+        return;
+    };
+    if !metadata.is_file() || metadata.uid() != uid {
+        return;
+    }
+    record_removal(path, std::fs::remove_file(path), report);
+}
+
+fn record_removal(path: &Path, result: io::Result<()>, report: &mut LegacyCleanupReport) {
+    match result {
+        Ok(()) => report.removed.push(path.to_path_buf()),
+        Err(error) => report.kept.push((path.to_path_buf(), error.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::net::UnixListener;
+    use tempfile::TempDir;
+
+    /// 使われていない旧ソケット・状態ファイル・一時音声を削除し、無関係なファイルは残す
+    #[test]
+    fn stale_legacy_files_are_removed() {
+        let tmp_root = TempDir::new().unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        let socket = tmp_root.path().join(LEGACY_SOCKET_NAME);
+        drop(UnixListener::bind(&socket).unwrap());
+        let status = tmp_root.path().join(LEGACY_STATUS_FILE_NAME);
+        std::fs::write(&status, "recording").unwrap();
+        let audio = temp_dir.path().join("voice_input_mlx_42_1700000000.wav");
+        std::fs::write(&audio, b"RIFF").unwrap();
+        let unrelated = temp_dir.path().join("notes.txt");
+        std::fs::write(&unrelated, "keep").unwrap();
+
+        let report = cleanup_in(
+            tmp_root.path(),
+            temp_dir.path(),
+            Path::new("/nonexistent.sock"),
+            current_uid(),
+        );
+
+        assert_eq!(report.removed, vec![socket.clone(), status.clone(), audio]);
+        assert!(report.kept.is_empty());
+        assert!(!socket.exists() && !status.exists());
+        assert!(unrelated.exists());
+        assert!(
+            cleanup_in(
+                tmp_root.path(),
+                temp_dir.path(),
+                Path::new("/nonexistent.sock"),
+                current_uid()
+            )
+            .is_empty()
+        );
+    }
+
+    /// 稼働中の旧デーモンのソケットと、現在使用中のソケットは削除しない
+    #[test]
+    fn live_or_current_sockets_are_kept() {
+        let tmp_root = TempDir::new().unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        let socket = tmp_root.path().join(LEGACY_SOCKET_NAME);
+        let listener = UnixListener::bind(&socket).unwrap();
+
+        let report = cleanup_in(
+            tmp_root.path(),
+            temp_dir.path(),
+            Path::new("/x"),
+            current_uid(),
+        );
+        assert!(report.removed.is_empty());
+        assert_eq!(report.kept.len(), 1);
+        assert!(socket.exists());
+
+        drop(listener);
+        let report = cleanup_in(tmp_root.path(), temp_dir.path(), &socket, current_uid());
+        assert!(report.is_empty());
+        assert!(socket.exists());
+    }
+}
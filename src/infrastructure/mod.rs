@@ -1,9 +1,19 @@
 pub mod audio;
+pub mod audio_cache;
+pub mod bench;
+pub mod capabilities;
 pub mod command_handler;
 pub mod config;
+pub mod daemon;
 pub mod dict;
 pub mod external;
+pub mod ipc_audit;
+pub mod last_error;
+pub mod legacy_state;
 pub mod media_control_service;
+pub mod resource_limits;
 pub mod runtime_recovery;
 pub mod service_container;
+pub mod soak;
+pub mod startup;
 pub mod transcription_worker;
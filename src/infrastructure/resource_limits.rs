@@ -0,0 +1,184 @@
+//! デーモン自身のリソース制限
+//!
+//! # 責任
+//! - 起動時の nice 値適用
+//! - RSS 監視による段階的なデグレード判定
+
+use std::process::Command;
+
+/// リソース制限の適用エラー
+#[derive(Debug, thiserror::Error)]
+pub enum ResourceLimitError {
+    #[error("failed to apply nice level {level}: {source}")]
+    NiceLevel {
+        level: i32,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("failed to read process RSS: {0}")]
+    RssUnavailable(String),
+}
+
+/// 自プロセスへ nice 値を適用する
+pub fn apply_nice_level(level: i32) -> Result<(), ResourceLimitError> {
+    // SAFETY: setpriority は自プロセス（who = 0）の優先度のみを変更する
+    let result = unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, level) };
+    if result != 0 {
+        return Err(ResourceLimitError::NiceLevel {
+            level,
+            source: std::io::Error::last_os_error(),
+        });
+    }
+    Ok(())
+}
+
+/// 自プロセスの現在の RSS をバイト単位で取得する
+pub fn current_rss_bytes() -> Result<u64, ResourceLimitError> {
+    let output = Command::new("ps")
+        .args(["-o", "rss=", "-p", &std::process::id().to_string()])
+        .output()
+        .map_err(|e| ResourceLimitError::RssUnavailable(e.to_string()))?;
+    if !output.status.success() {
+        return Err(ResourceLimitError::RssUnavailable(format!(
+            "ps exited with status {}",
+            output.status
+        )));
+    }
+
+    parse_ps_rss_kib(&String::from_utf8_lossy(&output.stdout))
+        .map(|kib| kib * 1024)
+        .ok_or_else(|| ResourceLimitError::RssUnavailable("unexpected ps output".to_string()))
+}
+
+fn parse_ps_rss_kib(output: &str) -> Option<u64> {
+    output.trim().parse().ok()
+}
+
+/// RSS 監視の判定結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RssWatchdogDecision {
+    /// 上限内
+    WithinLimit,
+    /// 上限超過中のため並列度を絞る
+    Throttle,
+    /// 上限超過が継続し、録音中や転写中でもないため再起動する
+    Restart,
+}
+
+/// RSS 上限の超過状態を追跡する監視器
+#[derive(Debug)]
+pub struct RssWatchdog {
+    limit_bytes: u64,
+    restart_after: u32,
+    exceeded_streak: u32,
+}
+
+impl RssWatchdog {
+    /// 上限と再起動までの連続超過回数を指定して作成する
+    pub fn new(limit_bytes: u64, restart_after: u32) -> Self {
+        Self {
+            limit_bytes,
+            restart_after,
+            exceeded_streak: 0,
+        }
+    }
+
+    /// MB 単位の上限から作成する
+    pub fn from_megabytes(limit_mb: u64, restart_after: u32) -> Self {
+        Self::new(limit_mb.saturating_mul(1024 * 1024), restart_after)
+    }
+
+    /// RSS の計測値を記録し、取るべき対応を返す
+    ///
+    /// `busy` は録音中・転写中・転写待ちのいずれかを表す。その間は音声を失わないよう
+    /// 再起動せず、スロットリングに留めて待機状態になるまで再起動を先送りする。
+    pub fn record_sample(&mut self, rss_bytes: u64, busy: bool) -> RssWatchdogDecision {
+        if rss_bytes <= self.limit_bytes {
+            self.exceeded_streak = 0;
+            return RssWatchdogDecision::WithinLimit;
+        }
+
+        self.exceeded_streak = self.exceeded_streak.saturating_add(1);
+        if !busy && self.exceeded_streak >= self.restart_after {
+            return RssWatchdogDecision::Restart;
+        }
+        RssWatchdogDecision::Throttle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RssWatchdog, RssWatchdogDecision, parse_ps_rss_kib};
+
+    /// 上限内の計測値では何もしない
+    #[test]
+    fn watchdog_stays_idle_within_limit() {
+        let mut watchdog = RssWatchdog::new(1000, 3);
+
+        assert_eq!(
+            watchdog.record_sample(1000, false),
+            RssWatchdogDecision::WithinLimit
+        );
+    }
+
+    /// 初回の上限超過ではスロットリングに留める
+    #[test]
+    fn watchdog_throttles_on_first_excess() {
+        let mut watchdog = RssWatchdog::new(1000, 3);
+
+        assert_eq!(
+            watchdog.record_sample(1001, false),
+            RssWatchdogDecision::Throttle
+        );
+    }
+
+    /// 上限超過が連続すると再起動を要求する
+    #[test]
+    fn watchdog_requests_restart_after_sustained_excess() {
+        let mut watchdog = RssWatchdog::new(1000, 3);
+
+        watchdog.record_sample(2000, false);
+        watchdog.record_sample(2000, false);
+
+        assert_eq!(
+            watchdog.record_sample(2000, false),
+            RssWatchdogDecision::Restart
+        );
+    }
+
+    /// 録音中や転写中は上限超過が続いても再起動せず、待機状態になってから再起動する
+    #[test]
+    fn watchdog_defers_restart_while_busy() {
+        let mut watchdog = RssWatchdog::new(1000, 1);
+
+        assert_eq!(
+            watchdog.record_sample(2000, true),
+            RssWatchdogDecision::Throttle
+        );
+        assert_eq!(
+            watchdog.record_sample(2000, false),
+            RssWatchdogDecision::Restart
+        );
+    }
+
+    /// 上限内へ戻ると連続超過回数がリセットされる
+    #[test]
+    fn watchdog_resets_streak_after_recovery() {
+        let mut watchdog = RssWatchdog::new(1000, 2);
+
+        watchdog.record_sample(2000, false);
+        watchdog.record_sample(500, false);
+
+        assert_eq!(
+            watchdog.record_sample(2000, false),
+            RssWatchdogDecision::Throttle
+        );
+    }
+
+    /// ps の RSS 出力は前後の空白を除いて KiB として解釈する
+    #[test]
+    fn ps_rss_output_is_parsed_as_kib() {
+        assert_eq!(parse_ps_rss_kib("  20480\n"), Some(20480));
+        assert_eq!(parse_ps_rss_kib(""), None);
+    }
+}
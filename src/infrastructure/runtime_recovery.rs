@@ -1,4 +1,4 @@
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, Instant, SystemTime};
 
 /// wake 復旧の再試行方針
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -43,10 +43,65 @@ impl SleepWakeDetector {
     }
 }
 
+/// 最大録音時間と猶予を過ぎても止まらない録音の検出器（デッドマンスイッチ）
+///
+/// 自動停止タイマーの失敗やタスクの異常終了で録音が続き続けるのを防ぐ。
+/// 期限はセッションを最初に観測した時刻からの絶対時刻で、確認の間隔や遅れには左右されない。
+/// ランタイムが止まっていても別スレッドから期限切れを判定できるよう、スレッド間で共有して使う。
+#[derive(Debug)]
+pub struct StuckRecordingWatchdog {
+    limit: Duration,
+    session: Option<(u64, Instant)>,
+}
+
+impl StuckRecordingWatchdog {
+    /// 録音開始から `limit` を超えたら検出する監視を作成する
+    pub fn new(limit: Duration) -> Self {
+        Self {
+            limit,
+            session: None,
+        }
+    }
+
+    /// 録音中のセッションを観測し、期限を過ぎて録音が続いていれば true を返す
+    pub fn observe(&mut self, active_session: Option<u64>, now: Instant) -> bool {
+        let Some(session_id) = active_session else {
+            self.session = None;
+            return false;
+        };
+        match self.session {
+            Some((observed, deadline)) if observed == session_id => now >= deadline,
+            _ => {
+                self.session = Some((session_id, now + self.limit));
+                false
+            }
+        }
+    }
+
+    /// 期限から `grace` を過ぎても観測中のままのセッションを返す
+    ///
+    /// 録音の停止を観測できていない（ランタイムが応答しない）ことを別スレッドから判定する。
+    pub fn overdue_session(&self, now: Instant, grace: Duration) -> Option<u64> {
+        self.session
+            .filter(|(_, deadline)| now >= *deadline + grace)
+            .map(|(session_id, _)| session_id)
+    }
+
+    /// 検出の上限
+    pub fn limit(&self) -> Duration {
+        self.limit
+    }
+
+    /// 検出の上限を変更する。観測中の録音には次の録音から適用する
+    pub fn set_limit(&mut self, limit: Duration) {
+        self.limit = limit;
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{SleepWakeDetector, WakeRecoveryRetryPolicy};
-    use std::time::{Duration, SystemTime};
+    use super::{SleepWakeDetector, StuckRecordingWatchdog, WakeRecoveryRetryPolicy};
+    use std::time::{Duration, Instant, SystemTime};
 
     /// 間隔内の tick では wake を検知しない
     #[test]
@@ -83,4 +138,58 @@ mod tests {
         assert_eq!(policy.max_attempts, 30);
         assert_eq!(policy.retry_interval, Duration::from_secs(2));
     }
+
+    /// 同じセッションが上限を超えて続いた場合だけ検出し、新しいセッションは数え直す
+    #[test]
+    fn watchdog_flags_session_running_past_limit() {
+        let start = Instant::now();
+        let mut watchdog = StuckRecordingWatchdog::new(Duration::from_secs(60));
+
+        assert!(!watchdog.observe(Some(1), start));
+        assert!(!watchdog.observe(Some(1), start + Duration::from_secs(10)));
+        assert!(!watchdog.observe(Some(2), start + Duration::from_secs(55)));
+        assert!(!watchdog.observe(None, start + Duration::from_secs(60)));
+
+        let restart = start + Duration::from_secs(70);
+        assert!(!watchdog.observe(Some(3), restart));
+        for secs in (10..60).step_by(10) {
+            assert!(!watchdog.observe(Some(3), restart + Duration::from_secs(secs)));
+        }
+        assert!(watchdog.observe(Some(3), restart + Duration::from_secs(60)));
+    }
+
+    /// 確認が大きく遅れても期限は延びず、次の確認で即座に検出する
+    #[test]
+    fn watchdog_deadline_is_absolute_per_session() {
+        let start = Instant::now();
+        let mut watchdog = StuckRecordingWatchdog::new(Duration::from_secs(60));
+
+        assert!(!watchdog.observe(Some(1), start));
+        assert!(watchdog.observe(Some(1), start + Duration::from_secs(3_600)));
+    }
+
+    /// 期限と猶予を過ぎても観測中のままのセッションだけを別スレッド向けに報告する
+    #[test]
+    fn watchdog_reports_session_overdue_past_grace() {
+        let start = Instant::now();
+        let grace = Duration::from_secs(30);
+        let mut watchdog = StuckRecordingWatchdog::new(Duration::from_secs(60));
+        assert_eq!(watchdog.overdue_session(start, grace), None);
+
+        watchdog.observe(Some(7), start);
+        assert_eq!(
+            watchdog.overdue_session(start + Duration::from_secs(89), grace),
+            None
+        );
+        assert_eq!(
+            watchdog.overdue_session(start + Duration::from_secs(90), grace),
+            Some(7)
+        );
+
+        watchdog.observe(None, start + Duration::from_secs(91));
+        assert_eq!(
+            watchdog.overdue_session(start + Duration::from_secs(120), grace),
+            None
+        );
+    }
 }
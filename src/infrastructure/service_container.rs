@@ -4,9 +4,13 @@
 //! - 全ての依存関係の構築と管理
 //! - サービス間の依存関係の解決
 //! - テスト時のモック注入サポート
+//! - 依存順でのサービス停止
+
+#![allow(clippy::await_holding_refcell_ref)]
 
 use std::cell::RefCell;
 use std::rc::Rc;
+use std::time::Duration;
 use tokio::sync::mpsc;
 
 use crate::application::{
@@ -19,13 +23,15 @@ use crate::infrastructure::{
     dict::JsonFileDictRepo,
     external::{
         mlx_qwen3_asr_adapter::MlxQwen3AsrTranscriptionAdapter,
-        openai_adapter::OpenAiTranscriptionAdapter,
+        openai_adapter::OpenAiTranscriptionAdapter, racing_adapter::RacingTranscriptionAdapter,
         transcription_log::NonBlockingTranscriptionLogWriter,
     },
     media_control_service::MediaControlService,
+    startup::{self, StartupStage},
+    transcription_worker::{TranscriptionPermits, TranscriptionWorkerHandle, close_and_drain},
 };
 use crate::utils::config::EnvConfig;
-use crate::utils::config::TranscriptionProvider;
+use crate::utils::config::{ProxyConfig, TranscriptionConfig, TranscriptionProvider};
 
 /// アプリケーション設定
 #[derive(Clone, Debug)]
@@ -44,6 +50,8 @@ impl AppConfig {
         Ok(Self {
             recording: RecordingConfig {
                 max_duration_secs: env_config.recording.max_duration_secs,
+                min_duration_ms: env_config.recording.min_duration_ms,
+                max_extension_secs: env_config.recording.max_extension_secs,
             },
             max_concurrent_transcriptions: env_config.recommended_transcription_parallelism(),
         })
@@ -69,6 +77,48 @@ pub struct ServiceContainer<T: AudioBackend + 'static> {
     pub transcription_tx: mpsc::UnboundedSender<TranscriptionMessage>,
     /// 転写メッセージ受信チャンネル
     pub transcription_rx: Option<mpsc::UnboundedReceiver<TranscriptionMessage>>,
+    /// 音楽の一時停止・再開
+    pub media_control: Rc<RefCell<MediaControlService>>,
+    /// 同時転写数を制限する許可（転写ワーカー・リソース監視と共有）
+    pub transcription_permits: Rc<TranscriptionPermits>,
+    /// 起動済みの転写ワーカー
+    pub transcription_worker: Option<TranscriptionWorkerHandle>,
+}
+
+/// [`ServiceContainer::shutdown`] の各段階の結果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShutdownReport {
+    /// 録音中だったため破棄したセッション
+    pub cancelled_session: Option<u64>,
+    /// 終了期限までに始められずに捨てた転写の件数
+    pub dropped_transcriptions: usize,
+    /// 実行中の転写が期限内に完了したか
+    pub transcriptions_drained: bool,
+    /// 録音のために一時停止していた音楽を再開したか
+    pub media_resumed: bool,
+}
+
+impl ShutdownReport {
+    /// ログ表示用の行に整形する
+    pub fn format_lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if let Some(session_id) = self.cancelled_session {
+            lines.push(format!("Discarded recording session {}", session_id));
+        }
+        if self.dropped_transcriptions > 0 {
+            lines.push(format!(
+                "Dropped {} queued transcription(s)",
+                self.dropped_transcriptions
+            ));
+        }
+        if !self.transcriptions_drained {
+            lines.push("Transcriptions still running at shutdown were abandoned".to_string());
+        }
+        if self.media_resumed {
+            lines.push("Resumed music paused for recording".to_string());
+        }
+        lines
+    }
 }
 
 fn build_transcription_service(
@@ -87,11 +137,36 @@ fn build_transcription_service(
     }
 }
 
-fn build_default_transcription_client(config: &EnvConfig) -> Result<Box<dyn TranscriptionClient>> {
-    match config.transcription.provider {
-        TranscriptionProvider::OpenAi => Ok(Box::new(OpenAiTranscriptionAdapter::new()?)),
+pub(crate) fn build_default_transcription_client(
+    config: &EnvConfig,
+) -> Result<Box<dyn TranscriptionClient>> {
+    let primary = build_transcription_client(&config.transcription, &config.proxy)?;
+    let Some(race) = config.transcription.race_config() else {
+        return Ok(primary);
+    };
+
+    eprintln!(
+        "⚠️  Race mode: each recording is sent to both {} and {}; API usage roughly doubles",
+        config.transcription.provider.as_str(),
+        race.provider.as_str()
+    );
+    let secondary = build_transcription_client(&race, &config.proxy)?;
+    Ok(Box::new(RacingTranscriptionAdapter::new(
+        primary, secondary,
+    )))
+}
+
+fn build_transcription_client(
+    transcription: &TranscriptionConfig,
+    proxy: &ProxyConfig,
+) -> Result<Box<dyn TranscriptionClient>> {
+    match transcription.provider {
+        TranscriptionProvider::OpenAi => Ok(Box::new(OpenAiTranscriptionAdapter::from_config(
+            transcription,
+            proxy,
+        )?)),
         TranscriptionProvider::MlxQwen3Asr => Ok(Box::new(
-            MlxQwen3AsrTranscriptionAdapter::from_config(&config.transcription),
+            MlxQwen3AsrTranscriptionAdapter::from_config(transcription),
         )),
     }
 }
@@ -101,7 +176,7 @@ impl ServiceContainer<CpalAudioBackend> {
     pub fn new() -> Result<Self> {
         let config = AppConfig::from_initialized_env()?;
         let backend = CpalAudioBackend::default();
-        if let Err(err) = backend.warm_up() {
+        if let Err(err) = startup::measure(StartupStage::DeviceProbe, || backend.warm_up()) {
             eprintln!("Input stream warm-up skipped: {}", err);
         }
         let recorder = Rc::new(RefCell::new(Recorder::new(backend)));
@@ -160,7 +235,7 @@ impl<T: AudioBackend + 'static> ServiceContainer<T> {
         let command_handler = Rc::new(RefCell::new(CommandHandler::new(
             recording.clone(),
             transcription.clone(),
-            media_control,
+            media_control.clone(),
             tx.clone(),
         )));
 
@@ -170,6 +245,11 @@ impl<T: AudioBackend + 'static> ServiceContainer<T> {
             transcription_service: transcription,
             transcription_tx: tx,
             transcription_rx: Some(rx),
+            media_control,
+            transcription_permits: Rc::new(TranscriptionPermits::new(
+                config.max_concurrent_transcriptions,
+            )),
+            transcription_worker: None,
         })
     }
 
@@ -179,6 +259,45 @@ impl<T: AudioBackend + 'static> ServiceContainer<T> {
     ) -> Option<mpsc::UnboundedReceiver<TranscriptionMessage>> {
         self.transcription_rx.take()
     }
+
+    /// 依存順にサービスを停止する
+    ///
+    /// 録音の破棄 → 転写の受付停止 → 待ち行列と実行中の転写の完了待ち（`drain_timeout` まで）→
+    /// 音楽の再開の順に行う。待ち行列の転写は録音済みの音声を持つため期限まで続けて実行し、
+    /// 期限までに始められなかった分だけを捨てて件数を報告する。
+    pub async fn shutdown(&mut self, drain_timeout: Duration) -> ShutdownReport {
+        let mut report = ShutdownReport::default();
+
+        // 録音中の音声は転写しきれないため破棄する
+        if self.recording_service.borrow().is_recording() {
+            match self.recording_service.borrow().cancel_recording().await {
+                Ok(context) => report.cancelled_session = Some(context.session_id),
+                Err(err) => eprintln!("Failed to discard recording at shutdown: {}", err),
+            }
+        }
+
+        // 新しい転写を受け付けず、待ち行列に残った分は期限まで転写する
+        // （ワーカーを起動していなければ転写する手段がないため捨てる）
+        let deadline = tokio::time::Instant::now() + drain_timeout;
+        report.dropped_transcriptions = match self.transcription_worker.take() {
+            Some(worker) => worker.stop(deadline).await,
+            None => self.transcription_rx.as_mut().map_or(0, close_and_drain),
+        };
+
+        // 全許可を取得できれば実行中の転写はない
+        report.transcriptions_drained = self.transcription_permits.drain(deadline).await;
+
+        // 転写後の再開に間に合わなかった一時停止を戻す
+        let media = self.media_control.borrow();
+        if media.is_paused_by_recording().unwrap_or(false) {
+            if let Ok(session_id) = self.recording_service.borrow().latest_session_id() {
+                let _ = media.resume_if_paused_for_session(session_id).await;
+            }
+            report.media_resumed = !media.is_paused_by_recording().unwrap_or(true);
+        }
+
+        report
+    }
 }
 
 /// テスト用のヘルパー実装
@@ -251,6 +370,7 @@ pub mod test_helpers {
             &self,
             _audio: AudioData,
             _language: &str,
+            _prompt: Option<&str>,
         ) -> Result<TranscriptionOutput> {
             Ok(TranscriptionOutput::from_text(self.response.clone()))
         }
@@ -319,7 +439,7 @@ pub mod test_helpers {
             let command_handler = Rc::new(RefCell::new(CommandHandler::new(
                 recording_service.clone(),
                 transcription_service.clone(),
-                media_control_service,
+                media_control_service.clone(),
                 transcription_tx.clone(),
             )));
 
@@ -329,6 +449,11 @@ pub mod test_helpers {
                 transcription_service,
                 transcription_tx,
                 transcription_rx: Some(transcription_rx),
+                media_control: media_control_service,
+                transcription_permits: Rc::new(TranscriptionPermits::new(
+                    EnvConfig::get().recommended_transcription_parallelism(),
+                )),
+                transcription_worker: None,
             })
         }
     }
@@ -336,12 +461,20 @@ pub mod test_helpers {
 
 #[cfg(test)]
 mod tests {
-    use super::build_default_transcription_client;
     use super::test_helpers::*;
+    use super::{ShutdownReport, build_default_transcription_client};
+    use crate::infrastructure::command_handler::TranscriptionMessage;
+    use crate::infrastructure::external::text_input;
+    use crate::infrastructure::external::text_input_worker::{
+        TextInputEngine, TextInputWorkerError,
+    };
+    use crate::infrastructure::transcription_worker::spawn_transcription_worker;
     use crate::utils::config::{
-        AudioConfig, EnvConfig, PathConfig, PreferredAudioFormat, ProfilingConfig, ProxyConfig,
-        RecordingConfig, TranscriptionConfig, TranscriptionProvider,
+        AudioConfig, EnvConfig, OpenAiRequestConfig, PathConfig, PreferredAudioFormat,
+        ProfilingConfig, ProxyConfig, RecordingConfig, ResourceConfig, ScreenLockAction,
+        TextInputConfig, TranscriptionConfig, TranscriptionProvider,
     };
+    use std::sync::Arc;
 
     fn mlx_env_config() -> EnvConfig {
         EnvConfig {
@@ -357,7 +490,12 @@ mod tests {
                 streaming_enabled: false,
                 log_path: None,
                 low_confidence_selection_enabled: false,
+                caret_context_enabled: false,
+                timestamp_format: None,
                 mlx_qwen3_asr_command: "mlx-qwen3-asr".to_string(),
+                openai_base_url: None,
+                openai_request: OpenAiRequestConfig::default(),
+                race_provider: None,
             },
             proxy: ProxyConfig {
                 all: None,
@@ -367,11 +505,18 @@ mod tests {
             audio: AudioConfig {
                 input_device_priorities: Vec::new(),
                 preferred_format: PreferredAudioFormat::Flac,
+                flac_compression_level: AudioConfig::DEFAULT_FLAC_COMPRESSION_LEVEL,
+                cache_entries: AudioConfig::DEFAULT_CACHE_ENTRIES,
             },
             recording: RecordingConfig {
                 max_duration_secs: 30,
+                min_duration_ms: 500,
+                max_extension_secs: 10,
+                screen_lock_action: ScreenLockAction::Discard,
             },
             profiling: ProfilingConfig { enabled: false },
+            resources: ResourceConfig::default(),
+            text_input: TextInputConfig::default(),
         }
     }
 
@@ -415,4 +560,161 @@ mod tests {
 
         assert!(result.is_ok());
     }
+
+    /// 終了時は録音中のセッションを破棄し、転写の完了待ちを済ませて新しい転写を受け付けない
+    #[tokio::test(flavor = "current_thread")]
+    async fn shutdown_discards_recording_and_drains_transcriptions() {
+        let mut container = TestServiceContainerBuilder::new()
+            .build()
+            .await
+            .expect("Failed to create test container");
+        let session_id = container
+            .recording_service
+            .borrow()
+            .start_recording(crate::application::RecordingOptions::default())
+            .await
+            .unwrap();
+
+        let report = container
+            .shutdown(std::time::Duration::from_millis(100))
+            .await;
+
+        assert_eq!(report.cancelled_session, Some(session_id));
+        assert!(report.transcriptions_drained);
+        assert!(!report.media_resumed);
+        assert!(!container.recording_service.borrow().is_recording());
+        assert!(container.transcription_permits.is_closed());
+    }
+
+    /// 入力したテキストを記録するだけの模擬入力エンジン
+    #[derive(Default)]
+    struct RecordingTextInput {
+        typed: std::sync::Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl TextInputEngine for RecordingTextInput {
+        async fn type_text(&self, text: &str) -> Result<(), TextInputWorkerError> {
+            self.typed.lock().unwrap().push(text.to_string());
+            Ok(())
+        }
+
+        async fn type_text_continuous(&self, text: &str) -> Result<(), TextInputWorkerError> {
+            self.type_text(text).await
+        }
+
+        async fn replace_suffix(
+            &self,
+            _delete_count: usize,
+            text: &str,
+        ) -> Result<(), TextInputWorkerError> {
+            self.type_text(text).await
+        }
+
+        async fn replace_suffix_continuous(
+            &self,
+            _delete_count: usize,
+            text: &str,
+        ) -> Result<(), TextInputWorkerError> {
+            self.type_text(text).await
+        }
+
+        async fn select_recent_range(
+            &self,
+            _trailing_char_count: usize,
+            _char_count: usize,
+        ) -> Result<(), TextInputWorkerError> {
+            Ok(())
+        }
+
+        async fn press_return(&self, _with_shift: bool) -> Result<(), TextInputWorkerError> {
+            Ok(())
+        }
+    }
+
+    fn queued_message(session_id: u64) -> TranscriptionMessage {
+        TranscriptionMessage {
+            result: crate::application::RecordedAudio {
+                audio_data: crate::application::AudioData {
+                    bytes: vec![0; 4],
+                    mime_type: "audio/wav",
+                    file_name: "audio.wav".to_string(),
+                },
+                duration_ms: 1_000,
+                snr_db: None,
+            },
+            resume_music: false,
+            session_id,
+            readback: false,
+            label: None,
+            then: crate::domain::input::TrailingAction::None,
+            voice_command: false,
+            diff_reply: None,
+            verbosity: crate::ipc::Verbosity::Normal,
+        }
+    }
+
+    /// 転写ワーカーを起動し、停止前に転写メッセージを 2 件積んだコンテナで終了処理を行う
+    async fn shutdown_with_queued_transcriptions(
+        drain_timeout: std::time::Duration,
+    ) -> ShutdownReport {
+        let mut container = TestServiceContainerBuilder::new()
+            .build()
+            .await
+            .expect("Failed to create test container");
+        let rx = container.take_transcription_rx().unwrap();
+        container.transcription_worker = Some(spawn_transcription_worker(
+            container.transcription_permits.clone(),
+            rx,
+            container.transcription_service.clone(),
+            container.recording_service.clone(),
+        ));
+        for session_id in 1..=2 {
+            container
+                .transcription_tx
+                .send(queued_message(session_id))
+                .unwrap();
+        }
+
+        let report = container.shutdown(drain_timeout).await;
+        assert!(container.transcription_tx.is_closed());
+        report
+    }
+
+    /// 終了時に待ち行列へ残っていた転写は捨てずに期限内に転写して入力する
+    #[tokio::test(flavor = "current_thread")]
+    async fn shutdown_transcribes_queued_messages_before_deadline() {
+        let text_input = Arc::new(RecordingTextInput::default());
+        text_input::install_engine(text_input.clone()).unwrap();
+
+        let report = tokio::task::LocalSet::new()
+            .run_until(shutdown_with_queued_transcriptions(
+                std::time::Duration::from_secs(5),
+            ))
+            .await;
+
+        assert_eq!(report.dropped_transcriptions, 0);
+        assert!(report.transcriptions_drained);
+        assert_eq!(
+            *text_input.typed.lock().unwrap(),
+            vec!["test transcription", "test transcription"]
+        );
+    }
+
+    /// 期限までに始められなかった転写だけを捨て、その件数を報告する
+    #[tokio::test(flavor = "current_thread")]
+    async fn shutdown_drops_queued_messages_left_after_deadline() {
+        let report = tokio::task::LocalSet::new()
+            .run_until(shutdown_with_queued_transcriptions(
+                std::time::Duration::ZERO,
+            ))
+            .await;
+
+        assert_eq!(report.dropped_transcriptions, 2);
+        assert!(
+            report
+                .format_lines()
+                .contains(&"Dropped 2 queued transcription(s)".to_string())
+        );
+    }
 }
@@ -0,0 +1,434 @@
+//! 長時間稼働を想定したソークテスト
+//!
+//! # 責任
+//! - 模擬バックエンドでの録音・転写・入力サイクルの反復実行
+//! - RSS・ファイルディスクリプタ・生存タスク数の増加検知
+//!
+//! 数日稼働後に報告される緩やかなリークを、実デバイスや API を使わずに再現するための仕組み。
+//! 各サイクルはデーモンと同じく `CommandHandler` の Start / Stop と転写ワーカーを通り、
+//! 録音デバイス・転写 API・テキスト入力だけを模擬に差し替える。
+//! `voice_inputd --soak [cycles]` から実行する。
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::sync::{Notify, mpsc};
+
+use crate::application::{
+    AudioBackend, AudioBackendError, AudioData, DictRepository, Recorder, RecordingConfig,
+    RecordingService, TranscriptionClient, TranscriptionService,
+};
+use crate::domain::dict::{EntryStatus, WordEntry};
+use crate::domain::input::TrailingAction;
+use crate::domain::transcription::TranscriptionOutput;
+use crate::error::{Result, VoiceInputError};
+use crate::infrastructure::command_handler::CommandHandler;
+use crate::infrastructure::external::{
+    sound, text_input,
+    text_input_worker::{TextInputEngine, TextInputWorkerError},
+};
+use crate::infrastructure::media_control_service::MediaControlService;
+use crate::infrastructure::resource_limits::current_rss_bytes;
+use crate::infrastructure::transcription_worker::{
+    TranscriptionPermits, spawn_transcription_worker,
+};
+use crate::ipc::IpcCmd;
+
+/// 既定のサイクル数
+pub const DEFAULT_SOAK_CYCLES: usize = 10_000;
+
+/// 模擬録音が返す音声（44 バイトの WAV ヘッダ + 無音）
+const SIMULATED_AUDIO_BYTES: usize = 44 + 3200;
+
+/// 模擬転写が返すテキスト
+const SIMULATED_TEXT: &str = "ソークテストの模擬転写です";
+
+/// 1 サイクルの転写が入力まで届くのを待つ上限
+const CYCLE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// リーク判定の許容量
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SoakLimits {
+    /// ウォームアップ後に許容する RSS 増加量（バイト）
+    pub max_rss_growth_bytes: u64,
+    /// 許容するファイルディスクリプタの増加数
+    pub max_fd_growth: usize,
+    /// 許容する生存タスクの増加数
+    pub max_task_growth: usize,
+}
+
+impl Default for SoakLimits {
+    fn default() -> Self {
+        Self {
+            max_rss_growth_bytes: 8 * 1024 * 1024,
+            max_fd_growth: 0,
+            max_task_growth: 0,
+        }
+    }
+}
+
+/// リソース使用量の計測値
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResourceSample {
+    pub rss_bytes: Option<u64>,
+    pub open_fds: Option<usize>,
+    pub alive_tasks: usize,
+}
+
+impl ResourceSample {
+    /// 現在のプロセスとランタイムの使用量を計測する
+    pub fn capture() -> Self {
+        Self {
+            rss_bytes: current_rss_bytes().ok(),
+            open_fds: count_open_fds(),
+            alive_tasks: tokio::runtime::Handle::current()
+                .metrics()
+                .num_alive_tasks(),
+        }
+    }
+}
+
+/// ソークテストの結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SoakReport {
+    pub cycles: usize,
+    /// ウォームアップ直後の計測値
+    pub baseline: ResourceSample,
+    /// 全サイクル完了後の計測値
+    pub final_sample: ResourceSample,
+}
+
+impl SoakReport {
+    /// 許容量を超えた増加を人が読める形で返す。空ならリークなし
+    pub fn violations(&self, limits: &SoakLimits) -> Vec<String> {
+        let mut violations = Vec::new();
+        if let (Some(start), Some(end)) = (self.baseline.rss_bytes, self.final_sample.rss_bytes) {
+            let growth = end.saturating_sub(start);
+            if growth > limits.max_rss_growth_bytes {
+                violations.push(format!(
+                    "rss grew by {} bytes (limit {})",
+                    growth, limits.max_rss_growth_bytes
+                ));
+            }
+        }
+        if let (Some(start), Some(end)) = (self.baseline.open_fds, self.final_sample.open_fds) {
+            let growth = end.saturating_sub(start);
+            if growth > limits.max_fd_growth {
+                violations.push(format!(
+                    "open fds grew by {} (limit {})",
+                    growth, limits.max_fd_growth
+                ));
+            }
+        }
+        let task_growth = self
+            .final_sample
+            .alive_tasks
+            .saturating_sub(self.baseline.alive_tasks);
+        if task_growth > limits.max_task_growth {
+            violations.push(format!(
+                "alive tasks grew by {} (limit {})",
+                task_growth, limits.max_task_growth
+            ));
+        }
+        violations
+    }
+
+    /// 結果を 1 行ずつ整形する
+    pub fn format_lines(&self) -> Vec<String> {
+        fn or_na<T: std::fmt::Display>(value: Option<T>) -> String {
+            value.map_or_else(|| "n/a".to_string(), |v| v.to_string())
+        }
+
+        vec![
+            format!("cycles={}", self.cycles),
+            format!(
+                "rss_bytes={} -> {}",
+                or_na(self.baseline.rss_bytes),
+                or_na(self.final_sample.rss_bytes)
+            ),
+            format!(
+                "open_fds={} -> {}",
+                or_na(self.baseline.open_fds),
+                or_na(self.final_sample.open_fds)
+            ),
+            format!(
+                "alive_tasks={} -> {}",
+                self.baseline.alive_tasks, self.final_sample.alive_tasks
+            ),
+        ]
+    }
+}
+
+/// 録音・転写・入力の模擬サイクルを `cycles` 回実行する
+///
+/// 転写ワーカーが `spawn_local` でタスクを起動するため、`LocalSet` 上で呼び出すこと。
+/// テキスト入力エンジンを模擬に差し替え、効果音と Apple Music の操作も止めるため、
+/// デーモンとは別のプロセスで実行する。
+/// 最初の 1 割（最低 1 回）をウォームアップとして計測の基準から除外する。
+pub async fn run_soak(cycles: usize) -> Result<SoakReport> {
+    sound::silence();
+    let input = Arc::new(SimulatedTextInput::default());
+    text_input::install_engine(input.clone())
+        .map_err(|e| VoiceInputError::SystemError(e.to_string()))?;
+
+    let recording = Rc::new(RefCell::new(RecordingService::new(
+        Rc::new(RefCell::new(
+            Recorder::new(SimulatedAudioBackend::default()),
+        )),
+        RecordingConfig {
+            // 即座に停止する模擬録音も転写まで進める
+            min_duration_ms: 0,
+            ..RecordingConfig::default()
+        },
+    )));
+    let transcription = Rc::new(RefCell::new(TranscriptionService::new(
+        Box::new(SimulatedTranscriptionClient),
+        Box::new(SimulatedDictRepository),
+        1,
+    )));
+    let (tx, rx) = mpsc::unbounded_channel();
+    let handler = CommandHandler::new(
+        recording.clone(),
+        transcription.clone(),
+        Rc::new(RefCell::new(MediaControlService::new())),
+        tx,
+    );
+    let permits = Rc::new(TranscriptionPermits::new(1));
+    let worker = spawn_transcription_worker(permits.clone(), rx, transcription, recording);
+
+    let warm_up = (cycles / 10).max(1).min(cycles);
+    for cycle in 0..warm_up {
+        run_cycle(&handler, &permits, &input, cycle + 1).await?;
+    }
+    let baseline = ResourceSample::capture();
+
+    for cycle in warm_up..cycles {
+        run_cycle(&handler, &permits, &input, cycle + 1).await?;
+    }
+    tokio::task::yield_now().await;
+    let final_sample = ResourceSample::capture();
+
+    worker.stop(tokio::time::Instant::now()).await;
+    Ok(SoakReport {
+        cycles,
+        baseline,
+        final_sample,
+    })
+}
+
+/// Start / Stop を送り、転写が入力されて転写タスクが終わるまで待つ
+async fn run_cycle<T: AudioBackend + 'static>(
+    handler: &CommandHandler<T>,
+    permits: &TranscriptionPermits,
+    input: &SimulatedTextInput,
+    expected_typed: usize,
+) -> Result<()> {
+    for cmd in [
+        IpcCmd::Start {
+            prompt: None,
+            readback: false,
+            label: None,
+            then: TrailingAction::None,
+        },
+        IpcCmd::Stop,
+    ] {
+        let resp = handler.handle(cmd).await?;
+        if !resp.ok {
+            return Err(VoiceInputError::SystemError(format!(
+                "simulated command failed: {}",
+                resp.msg
+            )));
+        }
+    }
+
+    tokio::time::timeout(CYCLE_TIMEOUT, async {
+        input.wait_for_typed(expected_typed).await;
+        while !permits.is_idle() {
+            tokio::task::yield_now().await;
+        }
+    })
+    .await
+    .map_err(|_| {
+        VoiceInputError::SystemError(format!(
+            "simulated input was not typed within {:?}",
+            CYCLE_TIMEOUT
+        ))
+    })
+}
+
+fn count_open_fds() -> Option<usize> {
+    std::fs::read_dir("/dev/fd")
+        .ok()
+        .map(|entries| entries.count())
+}
+
+/// 固定長の無音 WAV を返す模擬録音バックエンド
+#[derive(Default)]
+struct SimulatedAudioBackend {
+    recording: std::cell::Cell<bool>,
+}
+
+impl AudioBackend for SimulatedAudioBackend {
+    fn start_recording(&self) -> std::result::Result<(), AudioBackendError> {
+        self.recording.set(true);
+        Ok(())
+    }
+
+    fn stop_recording(&self) -> std::result::Result<AudioData, AudioBackendError> {
+        self.recording.set(false);
+        Ok(AudioData {
+            bytes: vec![0u8; SIMULATED_AUDIO_BYTES],
+            mime_type: "audio/wav",
+            file_name: "audio.wav".to_string(),
+        })
+    }
+
+    fn is_recording(&self) -> bool {
+        self.recording.get()
+    }
+}
+
+/// 固定テキストを返す模擬転写クライアント
+struct SimulatedTranscriptionClient;
+
+#[async_trait]
+impl TranscriptionClient for SimulatedTranscriptionClient {
+    async fn transcribe(
+        &self,
+        _audio: AudioData,
+        _language: &str,
+        _prompt: Option<&str>,
+    ) -> Result<TranscriptionOutput> {
+        Ok(TranscriptionOutput::from_text(SIMULATED_TEXT))
+    }
+}
+
+/// 入力した回数だけを数える模擬テキスト入力
+#[derive(Default)]
+struct SimulatedTextInput {
+    typed: AtomicUsize,
+    typed_notify: Notify,
+}
+
+impl SimulatedTextInput {
+    /// 入力回数が `count` に達するまで待つ
+    async fn wait_for_typed(&self, count: usize) {
+        loop {
+            let notified = self.typed_notify.notified();
+            if self.typed.load(Ordering::SeqCst) >= count {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    fn record_typed(&self) -> std::result::Result<(), TextInputWorkerError> {
+        self.typed.fetch_add(1, Ordering::SeqCst);
+        self.typed_notify.notify_waiters();
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl TextInputEngine for SimulatedTextInput {
+    async fn type_text(&self, _text: &str) -> std::result::Result<(), TextInputWorkerError> {
+        self.record_typed()
+    }
+
+    async fn type_text_continuous(
+        &self,
+        _text: &str,
+    ) -> std::result::Result<(), TextInputWorkerError> {
+        self.record_typed()
+    }
+
+    async fn replace_suffix(
+        &self,
+        _delete_count: usize,
+        _text: &str,
+    ) -> std::result::Result<(), TextInputWorkerError> {
+        Ok(())
+    }
+
+    async fn replace_suffix_continuous(
+        &self,
+        _delete_count: usize,
+        _text: &str,
+    ) -> std::result::Result<(), TextInputWorkerError> {
+        Ok(())
+    }
+
+    async fn select_recent_range(
+        &self,
+        _trailing_char_count: usize,
+        _char_count: usize,
+    ) -> std::result::Result<(), TextInputWorkerError> {
+        Ok(())
+    }
+
+    async fn press_return(
+        &self,
+        _with_shift: bool,
+    ) -> std::result::Result<(), TextInputWorkerError> {
+        Ok(())
+    }
+}
+
+/// 辞書置換の経路も通すため 1 件だけ登録語を持つ模擬辞書
+struct SimulatedDictRepository;
+
+impl DictRepository for SimulatedDictRepository {
+    fn load(&self) -> std::io::Result<Vec<WordEntry>> {
+        Ok(vec![WordEntry {
+            surface: "模擬".to_string(),
+            replacement: "シミュレート".to_string(),
+            hit: 0,
+            status: EntryStatus::Active,
+        }])
+    }
+
+    fn save(&self, _all: &[WordEntry]) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 許容量を超えた増加は違反として報告される
+    #[test]
+    fn violations_report_growth_beyond_limits() {
+        let report = SoakReport {
+            cycles: 10,
+            baseline: ResourceSample {
+                rss_bytes: Some(1_000),
+                open_fds: Some(10),
+                alive_tasks: 1,
+            },
+            final_sample: ResourceSample {
+                rss_bytes: Some(10_000),
+                open_fds: Some(12),
+                alive_tasks: 3,
+            },
+        };
+        let limits = SoakLimits {
+            max_rss_growth_bytes: 5_000,
+            max_fd_growth: 0,
+            max_task_growth: 0,
+        };
+
+        assert_eq!(
+            report.violations(&limits),
+            vec![
+                "rss grew by 9000 bytes (limit 5000)",
+                "open fds grew by 2 (limit 0)",
+                "alive tasks grew by 2 (limit 0)",
+            ]
+        );
+    }
+}
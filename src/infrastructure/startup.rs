@@ -0,0 +1,300 @@
+//! デーモン起動段階の計測
+//!
+//! # 責任
+//! - 起動段階ごとの所要時間の記録
+//! - 段階ごとの期限超過の検知（実行中の段階も監視スレッドで打ち切る）
+//! - `voice_input health` 向けの整形
+//!
+//! 起動が遅い・固まるといった報告を切り分けられるよう、プロセス全体で共有するレジストリとして提供する。
+
+use std::sync::Mutex;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+/// 計測対象の起動段階
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartupStage {
+    /// `.env` と環境変数設定の読み込み
+    ConfigLoad,
+    /// IPC ソケットの確保とバインド
+    SocketBind,
+    /// 入力デバイスの検出とストリームのウォームアップ
+    DeviceProbe,
+    /// サービスコンテナの構築（デバイス検出を含む）
+    ServiceContainer,
+    /// テキスト入力ワーカーの起動
+    TextInputWorker,
+}
+
+impl StartupStage {
+    /// 表示用の名前を返す
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ConfigLoad => "config_load",
+            Self::SocketBind => "socket_bind",
+            Self::DeviceProbe => "device_probe",
+            Self::ServiceContainer => "service_container",
+            Self::TextInputWorker => "text_input_worker",
+        }
+    }
+}
+
+/// 起動段階の期限超過
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+#[error(
+    "startup stage {} exceeded the {deadline_ms}ms deadline after {elapsed_ms}ms (VOICE_INPUT_STARTUP_STAGE_DEADLINE_MS)",
+    stage.as_str()
+)]
+pub struct StartupDeadlineExceeded {
+    pub stage: StartupStage,
+    pub elapsed_ms: u128,
+    pub deadline_ms: u128,
+}
+
+/// 起動段階ごとの所要時間
+#[derive(Debug, Clone, Default)]
+pub struct StartupTimings {
+    stages: Vec<(StartupStage, Duration)>,
+}
+
+impl StartupTimings {
+    /// 空の記録を作成する
+    pub const fn new() -> Self {
+        Self { stages: Vec::new() }
+    }
+
+    /// 段階の所要時間を記録する。同じ段階の記録は置き換える
+    pub fn record(&mut self, stage: StartupStage, elapsed: Duration) {
+        match self
+            .stages
+            .iter_mut()
+            .find(|(recorded, _)| *recorded == stage)
+        {
+            Some(entry) => entry.1 = elapsed,
+            None => self.stages.push((stage, elapsed)),
+        }
+    }
+
+    /// 期限を超えた最初の段階を返す。`deadline` が `None` なら検査しない
+    pub fn check_deadline(
+        &self,
+        deadline: Option<Duration>,
+    ) -> Result<(), StartupDeadlineExceeded> {
+        let Some(deadline) = deadline else {
+            return Ok(());
+        };
+        match self.stages.iter().find(|(_, elapsed)| *elapsed > deadline) {
+            Some((stage, elapsed)) => Err(StartupDeadlineExceeded {
+                stage: *stage,
+                elapsed_ms: elapsed.as_millis(),
+                deadline_ms: deadline.as_millis(),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// ヘルスチェック表示用の行に整形する
+    ///
+    /// デバイス検出はサービスコンテナ構築に含まれるため合計には加えない。
+    pub fn format_lines(&self) -> Vec<String> {
+        if self.stages.is_empty() {
+            return vec!["Startup: not recorded".to_string()];
+        }
+
+        let mut lines: Vec<String> = self
+            .stages
+            .iter()
+            .map(|(stage, elapsed)| {
+                format!("Startup {}: {}ms", stage.as_str(), elapsed.as_millis())
+            })
+            .collect();
+        let total: Duration = self
+            .stages
+            .iter()
+            .filter(|(stage, _)| *stage != StartupStage::DeviceProbe)
+            .map(|(_, elapsed)| *elapsed)
+            .sum();
+        lines.push(format!("Startup total: {}ms", total.as_millis()));
+        lines
+    }
+}
+
+static TIMINGS: Mutex<StartupTimings> = Mutex::new(StartupTimings::new());
+
+/// 段階ごとの期限（`None` なら監視しない）
+static STAGE_DEADLINE: Mutex<Option<Duration>> = Mutex::new(None);
+
+/// 以降に計測する段階の期限を設定する
+pub fn set_stage_deadline(deadline: Option<Duration>) {
+    *STAGE_DEADLINE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = deadline;
+}
+
+/// 実行中の段階の期限を監視するスレッドへの合図
+///
+/// 段階が終わって破棄されると監視スレッドは何もせずに終わる。
+struct StageWatchdog {
+    _finished: mpsc::Sender<()>,
+}
+
+impl StageWatchdog {
+    /// 段階が `deadline` までに終わらなければ `on_timeout` を呼ぶ監視スレッドを起動する
+    ///
+    /// 起動段階は同期処理のため、ランタイム側のタイムアウトでは打ち切れない。
+    fn arm(
+        stage: StartupStage,
+        deadline: Duration,
+        on_timeout: impl FnOnce(StartupDeadlineExceeded) + Send + 'static,
+    ) -> Option<Self> {
+        let (finished, wait) = mpsc::channel::<()>();
+        let started_at = Instant::now();
+        let spawned = std::thread::Builder::new()
+            .name("startup-stage-watchdog".to_string())
+            .spawn(move || {
+                if let Err(RecvTimeoutError::Timeout) = wait.recv_timeout(deadline) {
+                    on_timeout(StartupDeadlineExceeded {
+                        stage,
+                        elapsed_ms: started_at.elapsed().as_millis(),
+                        deadline_ms: deadline.as_millis(),
+                    });
+                }
+            });
+        match spawned {
+            Ok(_) => Some(Self {
+                _finished: finished,
+            }),
+            Err(err) => {
+                eprintln!("Failed to start startup stage watchdog: {}", err);
+                None
+            }
+        }
+    }
+}
+
+/// 段階の所要時間を記録する
+pub fn record(stage: StartupStage, elapsed: Duration) {
+    let elapsed_ms = elapsed.as_millis();
+    TIMINGS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .record(stage, elapsed);
+    crate::utils::profiling::log_point(
+        "startup.stage",
+        &format!("stage={} elapsed_ms={}", stage.as_str(), elapsed_ms),
+    );
+}
+
+/// 処理を実行して所要時間を記録する
+///
+/// 期限が設定されていれば、期限内に終わらない段階は段階名を示してプロセスを終了させる。
+/// 固まったまま待受を始めず、LaunchAgent の再起動とログで原因を追えるようにする。
+pub fn measure<T>(stage: StartupStage, f: impl FnOnce() -> T) -> T {
+    let deadline = *STAGE_DEADLINE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let _watchdog = deadline.and_then(|deadline| {
+        StageWatchdog::arm(stage, deadline, |exceeded| {
+            eprintln!("{}", exceeded);
+            std::process::exit(1);
+        })
+    });
+    let started_at = Instant::now();
+    let value = f();
+    record(stage, started_at.elapsed());
+    value
+}
+
+/// 現在の記録の複製を返す
+pub fn snapshot() -> StartupTimings {
+    TIMINGS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{StageWatchdog, StartupDeadlineExceeded, StartupStage, StartupTimings};
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    /// 記録がない場合は未記録と表示する
+    #[test]
+    fn empty_timings_format_as_not_recorded() {
+        assert_eq!(
+            StartupTimings::new().format_lines(),
+            vec!["Startup: not recorded"]
+        );
+    }
+
+    /// 段階ごとの行と、デバイス検出を二重に数えない合計を表示する
+    #[test]
+    fn stages_are_formatted_with_total_excluding_nested_probe() {
+        let mut timings = StartupTimings::new();
+        timings.record(StartupStage::ConfigLoad, Duration::from_millis(5));
+        timings.record(StartupStage::DeviceProbe, Duration::from_millis(120));
+        timings.record(StartupStage::ServiceContainer, Duration::from_millis(130));
+
+        assert_eq!(
+            timings.format_lines(),
+            vec![
+                "Startup config_load: 5ms",
+                "Startup device_probe: 120ms",
+                "Startup service_container: 130ms",
+                "Startup total: 135ms",
+            ]
+        );
+    }
+
+    /// 期限を超えた段階があればその段階を報告する
+    #[test]
+    fn stage_over_deadline_is_reported() {
+        let mut timings = StartupTimings::new();
+        timings.record(StartupStage::ConfigLoad, Duration::from_millis(5));
+        timings.record(StartupStage::DeviceProbe, Duration::from_millis(3_000));
+
+        assert_eq!(
+            timings.check_deadline(Some(Duration::from_secs(2))),
+            Err(StartupDeadlineExceeded {
+                stage: StartupStage::DeviceProbe,
+                elapsed_ms: 3_000,
+                deadline_ms: 2_000,
+            })
+        );
+        assert_eq!(timings.check_deadline(None), Ok(()));
+    }
+
+    /// 期限内に終わらない段階は、終わるのを待たずに段階名付きで報告する
+    #[test]
+    fn watchdog_reports_stage_still_running_past_deadline() {
+        let (tx, rx) = mpsc::channel();
+        let watchdog = StageWatchdog::arm(
+            StartupStage::DeviceProbe,
+            Duration::from_millis(20),
+            move |exceeded| tx.send(exceeded).unwrap(),
+        );
+
+        let exceeded = rx
+            .recv_timeout(Duration::from_secs(5))
+            .expect("watchdog should fire while the stage is still running");
+        assert_eq!(exceeded.stage, StartupStage::DeviceProbe);
+        assert_eq!(exceeded.deadline_ms, 20);
+        assert!(exceeded.elapsed_ms >= 20);
+        drop(watchdog);
+    }
+
+    /// 期限内に終わった段階では何もしない
+    #[test]
+    fn watchdog_stays_quiet_when_stage_finishes_in_time() {
+        let (tx, rx) = mpsc::channel();
+        let watchdog = StageWatchdog::arm(
+            StartupStage::SocketBind,
+            Duration::from_millis(200),
+            move |exceeded| tx.send(exceeded).unwrap(),
+        );
+        drop(watchdog);
+
+        assert!(rx.recv_timeout(Duration::from_millis(400)).is_err());
+    }
+}
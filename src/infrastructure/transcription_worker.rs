@@ -2,36 +2,80 @@
 //!
 //! # 責任
 //! - 録音結果の転写処理
+//! - 再転写用の録音キャッシュへの保存
 //! - 辞書変換の適用
 //! - 直接入力処理
 
 #![allow(clippy::await_holding_refcell_ref)]
 
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 use std::sync::Arc;
-use tokio::sync::Semaphore;
+use tokio::sync::{AcquireError, OwnedSemaphorePermit, Semaphore, mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
 
 use crate::application::AudioBackend;
 use crate::application::{
-    RecordedAudio, RecordingService, TranscriptionEvent, TranscriptionOptions, TranscriptionService,
+    RecordingService, TranscriptionEvent, TranscriptionOptions, TranscriptionService,
 };
+use crate::domain::input::TrailingAction;
 use crate::domain::transcription::{FinalizedTranscription, LowConfidenceSelection};
 use crate::error::Result;
+use crate::infrastructure::audio_cache::AudioCache;
 use crate::infrastructure::command_handler::TranscriptionMessage;
-use crate::infrastructure::external::{sound::resume_apple_music, text_input};
+use crate::infrastructure::external::{
+    caret_context::{self, CARET_CONTEXT_MAX_CHARS},
+    frontmost_app, notification,
+    sound::resume_apple_music,
+    speech, text_input,
+};
+use crate::infrastructure::last_error::{self, Subsystem};
 use crate::utils::config::EnvConfig;
 use crate::utils::profiling;
 use async_trait::async_trait;
 
+/// これを下回る SNR（dB）の録音は雑音が多いとみなす
+const NOISY_CAPTURE_SNR_DB: f32 = 10.0;
+
+/// 雑音の多い録音で転写へ添える文脈ヒント
+///
+/// Whisper 系のプロンプトは直前の書き起こしとして扱われ、指示文は従われずにそのまま
+/// 出力へ混ざることがあるため、短い文脈の例だけを渡す。
+const NOISY_CAPTURE_PROMPT: &str = "騒がしい場所での音声メモ。";
+
+/// 推定 SNR から雑音の多い録音かを判定する
+fn is_noisy_capture(snr_db: Option<f32>) -> bool {
+    snr_db.is_some_and(|snr| snr < NOISY_CAPTURE_SNR_DB)
+}
+
+/// 雑音ヒントとキャレット直前の文脈から転写プロンプトを組み立てる
+fn build_transcription_prompt(noisy_capture: bool, caret_context: Option<&str>) -> Option<String> {
+    let parts: Vec<&str> = noisy_capture
+        .then_some(NOISY_CAPTURE_PROMPT)
+        .into_iter()
+        .chain(caret_context)
+        .collect();
+    (!parts.is_empty()).then(|| parts.join("\n"))
+}
+
 /// 転写結果を処理
 pub async fn handle_transcription<T: AudioBackend>(
-    result: RecordedAudio,
-    resume_music: bool,
-    session_id: u64,
+    message: TranscriptionMessage,
     recording_service: Rc<RefCell<RecordingService<T>>>,
     transcription_service: Rc<RefCell<TranscriptionService>>,
 ) -> Result<()> {
+    let TranscriptionMessage {
+        result,
+        resume_music,
+        session_id,
+        readback,
+        label,
+        then,
+        voice_command,
+        diff_reply,
+        verbosity: _,
+    } = message;
     let overall_timer = profiling::Timer::start("transcription.handle");
 
     // エラーが発生しても確実に音楽を再開するためにdeferパターンで実装
@@ -44,50 +88,96 @@ pub async fn handle_transcription<T: AudioBackend>(
         }
     });
 
+    // 転写に失敗しても録り直さずに再転写できるよう、録音した音声だけを残す
+    // （ファイル書き込みでランタイムを止めないよう blocking スレッドで行う）
+    let cache = AudioCache::open_default();
+    if result.duration_ms > 0 && cache.is_enabled() {
+        let audio = result.audio_data.clone();
+        tokio::task::spawn_blocking(move || {
+            if let Err(e) = cache.store(session_id, &audio) {
+                eprintln!("Failed to cache recording (session {}): {}", session_id, e);
+            }
+        });
+    }
+
+    // 雑音の多い録音では背景音を書き起こさないようヒントを添え、計測ログにも残す
+    let noisy_capture = is_noisy_capture(result.snr_db);
+    if let Some(snr_db) = result.snr_db.filter(|_| noisy_capture) {
+        eprintln!(
+            "Noisy recording detected (session {}, snr={:.1}dB); adding noise hint",
+            session_id, snr_db
+        );
+        profiling::log_point(
+            "transcription.noisy_capture",
+            &format!("session={} snr_db={:.1}", session_id, snr_db),
+        );
+    }
+
+    // 書きかけの文に続けて話した場合に備え、明示的に有効化されていればキャレット直前を文脈にする
+    let caret_context = if EnvConfig::get().transcription.caret_context_enabled {
+        caret_context::text_before_caret(CARET_CONTEXT_MAX_CHARS).await
+    } else {
+        None
+    };
+
     // 転写オプションを構築
     let options = TranscriptionOptions {
         language: "ja".to_string(),
-        prompt: None, // メモリモードではプロンプトファイルを使用しない
+        prompt: build_transcription_prompt(noisy_capture, caret_context.as_deref()),
+        label,
+        voice_command,
+        diff_reply,
     };
 
-    let finalized = if EnvConfig::get().transcription.streaming_enabled {
-        let (event_tx, mut event_rx) = tokio::sync::mpsc::unbounded_channel();
-        let input_task = tokio::task::spawn_local(async move {
-            process_streaming_events(&mut event_rx, &ProfiledTextApplier).await
-        });
+    // 読み上げや音声コマンドの解釈は入力前に全文が必要なため、ストリーミング入力を使わない
+    let finalized =
+        if EnvConfig::get().transcription.streaming_enabled && !readback && !voice_command {
+            let (event_tx, mut event_rx) = tokio::sync::mpsc::unbounded_channel();
+            let input_task = tokio::task::spawn_local(async move {
+                wait_for_app_paste_delay().await;
+                process_streaming_events(&mut event_rx, &ProfiledTextApplier).await
+            });
 
-        let finalized = transcription_service
-            .borrow()
-            .transcribe_streaming(result.audio_data, options, event_tx)
-            .await?;
-
-        let streamed_finalized = match input_task.await {
-            Ok(value) => value,
-            Err(e) => {
-                eprintln!("Streaming input task failed: {}", e);
-                None
-            }
-        };
+            let finalized = transcription_service
+                .borrow()
+                .transcribe_streaming(result.audio_data, options, event_tx)
+                .await?;
+
+            let streamed_finalized = match input_task.await {
+                Ok(value) => value,
+                Err(e) => {
+                    eprintln!("Streaming input task failed: {}", e);
+                    None
+                }
+            };
 
-        if let Some((finalized_for_selection, input_succeeded)) = streamed_finalized.as_ref() {
-            if *input_succeeded {
-                maybe_select_low_confidence(finalized_for_selection, session_id, recording_service)
-                    .await;
+            if let Some((finalized_for_selection, input_succeeded)) = streamed_finalized.as_ref() {
+                if *input_succeeded {
+                    finish_input(then, finalized_for_selection, session_id, recording_service)
+                        .await;
+                }
             }
-        }
 
-        finalized
-    } else {
-        let finalized = transcription_service
-            .borrow()
-            .transcribe(result.audio_data, options)
-            .await?;
-        let input_succeeded = type_text_with_profile(&finalized.text).await;
-        if input_succeeded {
-            maybe_select_low_confidence(&finalized, session_id, recording_service).await;
-        }
-        finalized
-    };
+            finalized
+        } else {
+            let finalized = transcription_service
+                .borrow()
+                .transcribe(result.audio_data, options)
+                .await?;
+            if voice_command {
+                notify_voice_command_result(&finalized).await;
+                return Ok(());
+            }
+            if readback {
+                read_back_with_profile(&finalized.text).await;
+            }
+            wait_for_app_paste_delay().await;
+            let input_succeeded = type_text_with_profile(&finalized.text).await;
+            if input_succeeded {
+                finish_input(then, &finalized, session_id, recording_service).await;
+            }
+            finalized
+        };
 
     if profiling::enabled() {
         overall_timer.log_with(&format!("text_len={}", finalized.text.len()));
@@ -98,6 +188,109 @@ pub async fn handle_transcription<T: AudioBackend>(
     Ok(())
 }
 
+/// 音声コマンドの実行結果を通知する（転写結果は入力しない）
+async fn notify_voice_command_result(finalized: &FinalizedTranscription) {
+    match finalized.registered_entry.as_ref() {
+        Some(entry) => {
+            println!(
+                "Registered dictionary entry by voice: {} -> {}",
+                entry.surface, entry.replacement
+            );
+            notification::notify(&format!(
+                "辞書に登録しました: {} → {}",
+                entry.surface, entry.replacement
+            ))
+            .await;
+        }
+        None => {
+            eprintln!("Voice command not recognized");
+            notification::notify(
+                "音声コマンドを認識できませんでした（例: 単語登録：X を Y に登録）",
+            )
+            .await;
+        }
+    }
+}
+
+/// 入力成功後の仕上げとして、入力後の動作か低信頼語の選択のどちらかを行う
+///
+/// 選択範囲は入力直後のカーソル位置を基準にするため、入力後の動作を指定した場合は選択しない。
+async fn finish_input<T: AudioBackend>(
+    then: TrailingAction,
+    finalized: &FinalizedTranscription,
+    session_id: u64,
+    recording_service: Rc<RefCell<RecordingService<T>>>,
+) {
+    let then = effective_trailing_action(then, &finalized.text);
+    if then.is_none() {
+        maybe_select_low_confidence(finalized, session_id, recording_service).await;
+    } else {
+        apply_trailing_action(then).await;
+    }
+}
+
+/// 入力したテキストが空白だけなら入力後の動作を行わない
+///
+/// 無音や雑音で何も入力していないときに Enter を押すと、入力欄に残っている下書きを送信してしまう。
+fn effective_trailing_action(then: TrailingAction, text: &str) -> TrailingAction {
+    if !then.is_none() && text.trim().is_empty() {
+        profiling::log_point(
+            "text_input.trailing_action",
+            &format!("action={} skipped=empty_text", then),
+        );
+        return TrailingAction::None;
+    }
+    then
+}
+
+/// 入力後の動作を行う。失敗しても入力済みのテキストはそのまま残す
+async fn apply_trailing_action(action: TrailingAction) {
+    let result = match action {
+        TrailingAction::None => return,
+        TrailingAction::Space => text_input::type_text_continuous(" ").await,
+        TrailingAction::Newline => text_input::press_return(true).await,
+        TrailingAction::Enter => text_input::press_return(false).await,
+    };
+    profiling::log_point(
+        "text_input.trailing_action",
+        &format!("action={} ok={}", action, result.is_ok()),
+    );
+    if let Err(e) = result {
+        eprintln!("Trailing action '{}' failed: {}", action, e);
+        last_error::record(Subsystem::TextInput, e.to_string());
+    }
+}
+
+/// 最前面アプリに入力前待機時間が設定されていれば、その分だけ待つ
+///
+/// 設定がない場合は最前面アプリの問い合わせ自体を省き、入力までの遅延を増やさない。
+async fn wait_for_app_paste_delay() {
+    let delays = &EnvConfig::get().text_input.paste_delays_ms;
+    if delays.is_empty() {
+        return;
+    }
+
+    let Some(app) = frontmost_app::frontmost_app_name().await else {
+        return;
+    };
+    if let Some(&delay_ms) = delays.get(&app) {
+        profiling::log_point(
+            "text_input.paste_delay",
+            &format!("app={} delay_ms={}", app, delay_ms),
+        );
+        tokio::time::sleep(tokio::time::Duration::from_millis(delay_ms)).await;
+    }
+}
+
+async fn read_back_with_profile(text: &str) {
+    let speech_timer = profiling::Timer::start("speech.read_back");
+    // 読み上げに失敗しても入力は続ける
+    if let Err(e) = speech::read_back(text).await {
+        eprintln!("Readback failed: {}", e);
+    }
+    speech_timer.log();
+}
+
 async fn type_text_with_profile(text: &str) -> bool {
     let input_timer = profiling::Timer::start("text_input");
     match text_input::type_text(text).await {
@@ -116,6 +309,7 @@ async fn type_text_with_profile(text: &str) -> bool {
                 input_timer.log();
             }
             eprintln!("Direct input failed: {}", e);
+            last_error::record(Subsystem::TextInput, e.to_string());
             false
         }
     }
@@ -139,6 +333,7 @@ async fn type_text_continuous_with_profile(text: &str) -> bool {
                 input_timer.log();
             }
             eprintln!("Direct input continuous failed: {}", e);
+            last_error::record(Subsystem::TextInput, e.to_string());
             false
         }
     }
@@ -175,6 +370,7 @@ async fn apply_text_patch_continuous_with_profile(current: &str, next: &str) ->
                 input_timer.log();
             }
             eprintln!("Direct input continuous patch failed: {}", e);
+            last_error::record(Subsystem::TextInput, e.to_string());
             false
         }
     }
@@ -259,41 +455,199 @@ async fn process_streaming_events(
     None
 }
 
-/// 転写ワーカーを起動
-pub async fn spawn_transcription_worker<T: AudioBackend + 'static>(
+/// 同時転写数を制限する許可
+///
+/// メモリ逼迫時に同時転写を 1 件へ絞るための予約もここで持ち、終了時に確実に手放す。
+/// 再起動してよいかを判断できるよう、許可を待っている転写の件数も転写ワーカーから受け取る。
+pub struct TranscriptionPermits {
     semaphore: Arc<Semaphore>,
-    mut rx: tokio::sync::mpsc::UnboundedReceiver<TranscriptionMessage>,
+    total: usize,
+    throttle: RefCell<Option<OwnedSemaphorePermit>>,
+    queued: Cell<usize>,
+}
+
+impl TranscriptionPermits {
+    /// 最大同時転写数を指定して作成
+    pub fn new(total: usize) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(total)),
+            total,
+            throttle: RefCell::new(None),
+            queued: Cell::new(0),
+        }
+    }
+
+    /// 転写 1 件分の許可を待つ（閉じた後はエラー）
+    pub async fn acquire(&self) -> std::result::Result<OwnedSemaphorePermit, AcquireError> {
+        self.semaphore.clone().acquire_owned().await
+    }
+
+    /// 1 件分を残して許可を予約し、同時転写を 1 件に絞る。新たに絞った場合だけ true
+    pub fn throttle(&self) -> bool {
+        let mut throttle = self.throttle.borrow_mut();
+        let reserved = self.total.saturating_sub(1) as u32;
+        if throttle.is_some() || reserved == 0 {
+            return false;
+        }
+        *throttle = self.semaphore.clone().try_acquire_many_owned(reserved).ok();
+        throttle.is_some()
+    }
+
+    /// 同時転写の絞り込みを解除する。解除した場合だけ true
+    pub fn lift_throttle(&self) -> bool {
+        self.throttle.borrow_mut().take().is_some()
+    }
+
+    /// 実行中の転写の完了を `deadline` まで待ち、以後の許可を閉じる。期限内に終われば true
+    ///
+    /// 絞り込みの予約を持ったままだと全許可が揃わないため、先に手放す。
+    pub async fn drain(&self, deadline: Instant) -> bool {
+        self.lift_throttle();
+        let drained = matches!(
+            tokio::time::timeout_at(deadline, self.semaphore.acquire_many(self.total as u32)).await,
+            Ok(Ok(_))
+        );
+        self.semaphore.close();
+        drained
+    }
+
+    /// 許可が閉じられたか
+    pub fn is_closed(&self) -> bool {
+        self.semaphore.is_closed()
+    }
+
+    /// 許可を待っている転写の件数を記録する
+    pub fn set_queued(&self, queued: usize) {
+        self.queued.set(queued);
+    }
+
+    /// 実行中の転写も許可を待っている転写もないか
+    ///
+    /// 絞り込みのために予約した許可は実行中として数えない。
+    pub fn is_idle(&self) -> bool {
+        let reserved = self
+            .throttle
+            .borrow()
+            .as_ref()
+            .map_or(0, |permit| permit.num_permits());
+        self.queued.get() == 0 && self.semaphore.available_permits() + reserved >= self.total
+    }
+}
+
+/// 起動した転写ワーカーを止めるためのハンドル
+pub struct TranscriptionWorkerHandle {
+    stop: oneshot::Sender<Instant>,
+    task: JoinHandle<usize>,
+}
+
+impl TranscriptionWorkerHandle {
+    /// 新しい転写の受付を止め、待ち行列に残っていた転写を `deadline` まで続けて実行する
+    ///
+    /// 期限までに実行を始められずに捨てた件数を返す。
+    pub async fn stop(self, deadline: Instant) -> usize {
+        let _ = self.stop.send(deadline);
+        self.task.await.unwrap_or(0)
+    }
+}
+
+/// 転写ワーカーを起動
+pub fn spawn_transcription_worker<T: AudioBackend + 'static>(
+    permits: Rc<TranscriptionPermits>,
+    rx: mpsc::UnboundedReceiver<TranscriptionMessage>,
     transcription_service: Rc<RefCell<TranscriptionService>>,
     recording_service: Rc<RefCell<RecordingService<T>>>,
-) {
-    use tokio::task::spawn_local;
-
-    while let Some(message) = rx.recv().await {
-        let permit = match semaphore.clone().acquire_owned().await {
-            Ok(p) => p,
-            Err(e) => {
-                eprintln!("semaphore acquire error: {}", e);
-                continue;
-            }
-        };
+) -> TranscriptionWorkerHandle {
+    let (stop, stop_rx) = oneshot::channel();
+    let task = tokio::task::spawn_local(run_transcription_worker(
+        permits,
+        rx,
+        stop_rx,
+        transcription_service,
+        recording_service,
+    ));
+    TranscriptionWorkerHandle { stop, task }
+}
 
+/// 停止を求められるまで転写メッセージを処理し、その後は待ち行列を期限まで処理する
+///
+/// 待ち行列のメッセージは録音済みの音声を持つため、停止時も捨てずに転写する。
+/// 期限までに許可を得られなかった件数を返す。
+async fn run_transcription_worker<T: AudioBackend + 'static>(
+    permits: Rc<TranscriptionPermits>,
+    mut rx: mpsc::UnboundedReceiver<TranscriptionMessage>,
+    mut stop: oneshot::Receiver<Instant>,
+    transcription_service: Rc<RefCell<TranscriptionService>>,
+    recording_service: Rc<RefCell<RecordingService<T>>>,
+) -> usize {
+    let spawn_handling = |message: TranscriptionMessage, permit: OwnedSemaphorePermit| {
         let transcription_service = transcription_service.clone();
         let recording_service = recording_service.clone();
-        spawn_local(async move {
-            if let Err(e) = handle_transcription(
-                message.result,
-                message.resume_music,
-                message.session_id,
-                recording_service,
-                transcription_service,
-            )
-            .await
+        // 転写を依頼したリクエストの詳しさで計測ログを出す
+        let verbosity = message.verbosity;
+        tokio::task::spawn_local(profiling::scope_request(verbosity, async move {
+            if let Err(e) =
+                handle_transcription(message, recording_service, transcription_service).await
             {
                 eprintln!("Transcription handling failed: {}", e);
+                last_error::record(Subsystem::Transcription, e.to_string());
             }
             drop(permit);
-        });
+        }));
+    };
+
+    // 許可を待つ間に停止を求められた転写も、待ち行列の先頭として期限まで扱う
+    let mut pending = None;
+    let deadline = loop {
+        permits.set_queued(rx.len());
+        let message = tokio::select! {
+            biased;
+            deadline = &mut stop => break deadline.unwrap_or_else(|_| Instant::now()),
+            message = rx.recv() => match message {
+                Some(message) => message,
+                None => return 0,
+            },
+        };
+        permits.set_queued(rx.len() + 1);
+        tokio::select! {
+            biased;
+            deadline = &mut stop => {
+                pending = Some(message);
+                break deadline.unwrap_or_else(|_| Instant::now());
+            }
+            permit = permits.acquire() => match permit {
+                Ok(permit) => spawn_handling(message, permit),
+                Err(e) => eprintln!("semaphore acquire error: {}", e),
+            },
+        }
+    };
+
+    rx.close();
+    let mut dropped = 0;
+    while let Some(message) = pending.take().or_else(|| rx.try_recv().ok()) {
+        if Instant::now() >= deadline {
+            dropped += 1;
+            continue;
+        }
+        permits.set_queued(rx.len() + 1);
+        match tokio::time::timeout_at(deadline, permits.acquire()).await {
+            Ok(Ok(permit)) => spawn_handling(message, permit),
+            _ => dropped += 1,
+        }
     }
+    permits.set_queued(0);
+    dropped
+}
+
+/// 転写チャンネルを閉じ、待ち行列に残っていたメッセージを捨てて件数を返す
+///
+/// 転写ワーカーを起動していない場合の終了処理で使う。
+pub fn close_and_drain(rx: &mut mpsc::UnboundedReceiver<TranscriptionMessage>) -> usize {
+    rx.close();
+    let mut dropped = 0;
+    while rx.try_recv().is_ok() {
+        dropped += 1;
+    }
+    dropped
 }
 
 async fn select_recent_range_with_profile(trailing_char_count: usize, char_count: usize) {
@@ -319,6 +673,7 @@ async fn select_recent_range_with_profile(trailing_char_count: usize, char_count
                 input_timer.log();
             }
             eprintln!("Direct input selection failed: {}", e);
+            last_error::record(Subsystem::TextInput, e.to_string());
         }
     }
 }
@@ -372,14 +727,80 @@ fn selection_to_recent_range(
 #[cfg(test)]
 mod tests {
     use super::{
-        TextApplier, diff_text_for_patch, process_streaming_events, selection_to_recent_range,
+        NOISY_CAPTURE_PROMPT, TextApplier, TranscriptionPermits, build_transcription_prompt,
+        diff_text_for_patch, effective_trailing_action, is_noisy_capture, process_streaming_events,
+        selection_to_recent_range,
     };
     use crate::application::TranscriptionEvent;
+    use crate::domain::input::TrailingAction;
     use crate::domain::transcription::{FinalizedTranscription, LowConfidenceSelection};
     use async_trait::async_trait;
     use std::cell::{Cell, RefCell};
     use std::rc::Rc;
 
+    /// 同時転写を絞るための予約を持ったままでも、終了時の完了待ちは全許可を揃えられる
+    #[tokio::test(flavor = "current_thread")]
+    async fn drain_releases_throttle_reservation() {
+        let permits = TranscriptionPermits::new(3);
+        assert!(permits.throttle());
+        assert!(!permits.throttle());
+
+        let deadline = tokio::time::Instant::now() + std::time::Duration::from_millis(100);
+        assert!(permits.drain(deadline).await);
+        assert!(permits.is_closed());
+    }
+
+    /// 絞り込みの予約だけなら待機中とみなし、実行中や許可待ちの転写があれば待機中でない
+    #[tokio::test(flavor = "current_thread")]
+    async fn idle_ignores_throttle_reservation_but_not_running_or_queued_work() {
+        let permits = TranscriptionPermits::new(3);
+        assert!(permits.throttle());
+        assert!(permits.is_idle());
+
+        let running = permits.acquire().await.expect("permit");
+        assert!(!permits.is_idle());
+        drop(running);
+        assert!(permits.is_idle());
+
+        permits.set_queued(1);
+        assert!(!permits.is_idle());
+    }
+
+    /// SNRが閾値未満のときだけ雑音の多い録音と判定する
+    #[test]
+    fn noisy_capture_is_detected_only_below_threshold() {
+        assert!(is_noisy_capture(Some(4.5)));
+        assert!(!is_noisy_capture(Some(25.0)));
+        assert!(!is_noisy_capture(None));
+    }
+
+    /// 空白だけの転写結果では Enter などの入力後の動作を行わない
+    #[test]
+    fn trailing_action_is_skipped_for_blank_text() {
+        assert_eq!(
+            effective_trailing_action(TrailingAction::Enter, " \n"),
+            TrailingAction::None
+        );
+        assert_eq!(
+            effective_trailing_action(TrailingAction::Enter, "送信します"),
+            TrailingAction::Enter
+        );
+    }
+
+    /// 雑音ヒントとキャレット直前の文脈を改行で連結し、どちらもなければプロンプトなし
+    #[test]
+    fn transcription_prompt_combines_noise_hint_and_caret_context() {
+        assert_eq!(build_transcription_prompt(false, None), None);
+        assert_eq!(
+            build_transcription_prompt(false, Some("書きかけの")),
+            Some("書きかけの".to_string())
+        );
+        assert_eq!(
+            build_transcription_prompt(true, Some("書きかけの")),
+            Some(format!("{}\n書きかけの", NOISY_CAPTURE_PROMPT))
+        );
+    }
+
     /// 末尾追記だけなら削除せず差分だけ追加する
     #[test]
     fn diff_text_for_patch_appends_suffix_without_deleting() {
@@ -447,6 +868,7 @@ mod tests {
             .send(TranscriptionEvent::Completed(FinalizedTranscription {
                 text: "これはtestです".to_string(),
                 low_confidence_selection: None,
+                registered_entry: None,
             }))
             .unwrap();
         drop(event_tx);
@@ -468,6 +890,7 @@ mod tests {
                 FinalizedTranscription {
                     text: "これはtestです".to_string(),
                     low_confidence_selection: None,
+                    registered_entry: None,
                 },
                 true,
             ))
@@ -514,6 +937,7 @@ mod tests {
                     start_char_index: 0,
                     char_count: 2,
                 }),
+                registered_entry: None,
             }))
             .unwrap();
         drop(event_tx);
@@ -529,6 +953,7 @@ mod tests {
                         start_char_index: 0,
                         char_count: 2,
                     }),
+                    registered_entry: None,
                 },
                 false,
             ))
@@ -584,6 +1009,7 @@ mod tests {
                     start_char_index: 3,
                     char_count: 4,
                 }),
+                registered_entry: None,
             }))
             .unwrap();
         drop(event_tx);
@@ -600,6 +1026,7 @@ mod tests {
                         start_char_index: 3,
                         char_count: 4,
                     }),
+                    registered_entry: None,
                 },
                 false,
             ))
@@ -1,7 +1,10 @@
 //! Unix Domain Socket (UDS) ベースのシンプルな IPC モジュール。
 //! `voice_input` CLI ↔ `voice_inputd` デーモン間の通信で利用します。
 use crate::application::AudioData;
+use crate::domain::input::TrailingAction;
 use crate::utils::config::EnvConfig;
+pub use crate::utils::profiling::Verbosity;
+use crate::utils::user::current_uid;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 
@@ -15,12 +18,165 @@ pub enum IpcError {
     Connect(#[source] std::io::Error),
     #[error("failed to send IPC command")]
     Send(#[source] tokio_util::codec::LinesCodecError),
+    #[error("failed to send audio to daemon")]
+    SendBody(#[source] std::io::Error),
     #[error("failed to serialize IPC command")]
     Serialize(#[source] serde_json::Error),
     #[error("failed to decode IPC response")]
     Deserialize(#[source] serde_json::Error),
     #[error("no response from daemon")]
     NoResponse,
+    #[error("socket {path} is owned by another user (uid {owner_uid})")]
+    SocketOwnedByOtherUser { path: PathBuf, owner_uid: u32 },
+    #[error("another daemon is already listening on {0}")]
+    DaemonAlreadyRunning(PathBuf),
+    #[error("failed to remove stale socket")]
+    RemoveStaleSocket(#[source] std::io::Error),
+}
+
+/// IPC プロトコル違反
+///
+/// 接続を黙って切らずに `IpcResp` として返し、クライアント側で原因を表示できるようにする。
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum IpcProtocolError {
+    #[error("protocol error (malformed_json): {0}")]
+    MalformedJson(String),
+    #[error(
+        "protocol error (unknown_command): {detail}; daemon {daemon_version} may be older than the CLI, restart it"
+    )]
+    UnknownCommand {
+        detail: String,
+        daemon_version: &'static str,
+    },
+    #[error("protocol error (invalid_arguments): {0}")]
+    InvalidArguments(String),
+    #[error("protocol error (line_too_long): request exceeds {max} bytes")]
+    LineTooLong { max: usize },
+    #[error("protocol error (body_too_large): audio exceeds {max} bytes")]
+    BodyTooLarge { max: usize },
+    #[error("protocol error (io): {0}")]
+    Io(String),
+}
+
+impl IpcProtocolError {
+    /// クライアントへ返す失敗レスポンスに変換する
+    pub fn to_resp(&self) -> IpcResp {
+        IpcResp {
+            ok: false,
+            msg: self.to_string(),
+        }
+    }
+}
+
+/// 1 リクエスト行の最大バイト数。音声はパスで渡すため通常のコマンドは数 KB に収まる
+pub const MAX_IPC_LINE_BYTES: usize = 64 * 1024;
+
+/// デーモンが受信に使う行コーデック（行長上限付き）
+pub fn ipc_line_codec() -> tokio_util::codec::LinesCodec {
+    tokio_util::codec::LinesCodec::new_with_max_length(MAX_IPC_LINE_BYTES)
+}
+
+/// コマンド行に続けて送れる音声の最大バイト数。転写 API のアップロード上限に合わせる
+pub const MAX_IPC_BODY_BYTES: usize = 25 * 1024 * 1024;
+
+/// コーデックから読み出した 1 行を `IpcCmd` へ復号する
+pub fn decode_cmd_line(
+    line: Result<String, tokio_util::codec::LinesCodecError>,
+) -> Result<IpcCmd, IpcProtocolError> {
+    use tokio_util::codec::LinesCodecError;
+
+    match line {
+        Ok(line) => decode_cmd(&line),
+        Err(LinesCodecError::MaxLineLengthExceeded) => Err(IpcProtocolError::LineTooLong {
+            max: MAX_IPC_LINE_BYTES,
+        }),
+        Err(LinesCodecError::Io(e)) => Err(IpcProtocolError::Io(e.to_string())),
+    }
+}
+
+/// コマンド行に続く生バイト列を送信側が書き込みを閉じるまで読み出す
+///
+/// `buffered` は行コーデックがコマンド行と一緒に読み込んでいた残りのバイト列。
+pub async fn read_cmd_body<R>(buffered: &[u8], io: R) -> Result<Vec<u8>, IpcProtocolError>
+where
+    R: tokio::io::AsyncRead + Unpin,
+{
+    use tokio::io::AsyncReadExt;
+
+    let mut body = buffered.to_vec();
+    io.take((MAX_IPC_BODY_BYTES + 1 - body.len().min(MAX_IPC_BODY_BYTES)) as u64)
+        .read_to_end(&mut body)
+        .await
+        .map_err(|e| IpcProtocolError::Io(e.to_string()))?;
+    if body.len() > MAX_IPC_BODY_BYTES {
+        return Err(IpcProtocolError::BodyTooLarge {
+            max: MAX_IPC_BODY_BYTES,
+        });
+    }
+    Ok(body)
+}
+
+/// JSON 文字列を `IpcCmd` へ復号し、失敗理由を分類する
+pub fn decode_cmd(line: &str) -> Result<IpcCmd, IpcProtocolError> {
+    use serde_json::error::Category;
+
+    serde_json::from_str(line).map_err(|e| match e.classify() {
+        Category::Data if names_unknown_command(line) => IpcProtocolError::UnknownCommand {
+            detail: e.to_string(),
+            daemon_version: env!("CARGO_PKG_VERSION"),
+        },
+        Category::Data => IpcProtocolError::InvalidArguments(e.to_string()),
+        Category::Syntax | Category::Eof | Category::Io => {
+            IpcProtocolError::MalformedJson(e.to_string())
+        }
+    })
+}
+
+/// `IpcCmd` の外部タグとして使われるコマンド名（`WithVerbosity` などの包みも含む）
+///
+/// `IpcCmd::tag` が返す名前と一致することをテストで全バリアントについて確かめる。
+const IPC_CMD_TAGS: [&str; 16] = [
+    "Start",
+    "StartVoiceCommand",
+    "Stop",
+    "CancelRecording",
+    "Toggle",
+    "TranscribeFile",
+    "TranscribeStream",
+    "RetryLast",
+    "Status",
+    "StatusVerbose",
+    "ListDevices",
+    "Health",
+    "Audit",
+    "ReloadConfig",
+    "WithVerbosity",
+    "WithDiff",
+];
+
+/// 行の最上位のタグ（包みの場合は内側のコマンドも）がこのデーモンの知らないコマンド名か
+///
+/// 既知コマンドの引数に含まれる未知の列挙値（`then` など）は引数の誤りとして扱う。
+fn names_unknown_command(line: &str) -> bool {
+    fn unknown_tag(value: &serde_json::Value) -> bool {
+        let (tag, args) = match value {
+            serde_json::Value::String(tag) => (tag.as_str(), None),
+            serde_json::Value::Object(map) if map.len() == 1 => match map.iter().next() {
+                Some((tag, args)) => (tag.as_str(), Some(args)),
+                None => return false,
+            },
+            _ => return false,
+        };
+        if !IPC_CMD_TAGS.contains(&tag) {
+            return true;
+        }
+        match (tag, args.and_then(|args| args.get("cmd"))) {
+            ("WithVerbosity" | "WithDiff", Some(cmd)) => unknown_tag(cmd),
+            _ => false,
+        }
+    }
+
+    serde_json::from_str(line).is_ok_and(|value| unknown_tag(&value))
 }
 
 #[cfg(test)]
@@ -31,6 +187,31 @@ pub fn socket_path() -> PathBuf {
     EnvConfig::get().paths.ipc_socket_path()
 }
 
+/// デーモンがソケットをバインドできるよう既存ファイルを確認・掃除します。
+///
+/// 他ユーザーのソケットや稼働中デーモンのソケットは削除せずエラーにし、
+/// 応答のない自分のソケットだけを古いものとして削除します。
+pub fn claim_socket_path(path: &Path) -> Result<(), IpcError> {
+    use std::os::unix::fs::MetadataExt;
+
+    let Ok(metadata) = std::fs::symlink_metadata(path) else {
+        return Ok(());
+    };
+
+    if metadata.uid() != current_uid() {
+        return Err(IpcError::SocketOwnedByOtherUser {
+            path: path.to_path_buf(),
+            owner_uid: metadata.uid(),
+        });
+    }
+
+    if std::os::unix::net::UnixStream::connect(path).is_ok() {
+        return Err(IpcError::DaemonAlreadyRunning(path.to_path_buf()));
+    }
+
+    std::fs::remove_file(path).map_err(IpcError::RemoveStaleSocket)
+}
+
 /// CLI からデーモンへ送るコマンド列挙。
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum IpcCmd {
@@ -38,18 +219,123 @@ pub enum IpcCmd {
     Start {
         #[serde(default)]
         prompt: Option<String>,
+        /// 入力前に転写結果を読み上げる
+        #[serde(default)]
+        readback: bool,
+        /// 転写ログへ残すセッションラベル
+        #[serde(default)]
+        label: Option<String>,
+        /// 入力後に続けて行う動作
+        #[serde(default)]
+        then: TrailingAction,
     },
+    /// 音声コマンドとして録音開始（転写結果は入力せずコマンドとして解釈）
+    StartVoiceCommand,
     /// 録音停止
     Stop,
+    /// 録音中止（転写・入力を行わず音声を破棄）
+    CancelRecording,
     /// 録音トグル
     Toggle {
         #[serde(default)]
         prompt: Option<String>,
+        /// 入力前に転写結果を読み上げる
+        #[serde(default)]
+        readback: bool,
+        /// 入力後に続けて行う動作
+        #[serde(default)]
+        then: TrailingAction,
+    },
+    /// 既存の音声ファイルを転写して入力
+    TranscribeFile {
+        path: PathBuf,
     },
+    /// コマンド行に続けて送る WAV / FLAC のバイト列を転写して入力
+    TranscribeStream,
+    /// キャッシュに残っている直近の録音を再転写して入力
+    RetryLast,
     /// ステータス取得
     Status,
+    /// サブシステム別の直近エラーを含むステータス取得
+    StatusVerbose,
     ListDevices,
     Health,
+    /// 直近に受け付けた IPC コマンドの記録を取得
+    Audit,
+    /// 環境変数ファイルを読み直して設定を再読み込み
+    ReloadConfig,
+    /// 出力の詳しさを指定してコマンドを実行
+    WithVerbosity {
+        verbosity: Verbosity,
+        cmd: Box<IpcCmd>,
+    },
+    /// 転写を伴うコマンドを実行し、その転写が終わるまで待って後処理差分も返す
+    WithDiff {
+        cmd: Box<IpcCmd>,
+    },
+}
+
+impl IpcCmd {
+    /// 出力の詳しさを添える。通常なら旧デーモンとも互換の元のコマンドのまま返す
+    pub fn with_verbosity(self, verbosity: Verbosity) -> Self {
+        match verbosity {
+            Verbosity::Normal => self,
+            verbosity => IpcCmd::WithVerbosity {
+                verbosity,
+                cmd: Box::new(self),
+            },
+        }
+    }
+
+    /// 差分表示を指定した場合だけ、転写完了まで待って後処理差分も返すよう包む
+    pub fn with_diff(self, show_diff: bool) -> Self {
+        if show_diff {
+            IpcCmd::WithDiff {
+                cmd: Box::new(self),
+            }
+        } else {
+            self
+        }
+    }
+
+    /// コマンド行の後に音声のバイト列が続くか
+    pub fn has_body(&self) -> bool {
+        match self {
+            IpcCmd::TranscribeStream => true,
+            IpcCmd::WithVerbosity { cmd, .. } | IpcCmd::WithDiff { cmd } => cmd.has_body(),
+            _ => false,
+        }
+    }
+
+    /// JSON の外部タグとして使われるコマンド名。包みは包み自身の名前を返す
+    pub fn tag(&self) -> &'static str {
+        match self {
+            IpcCmd::Start { .. } => "Start",
+            IpcCmd::StartVoiceCommand => "StartVoiceCommand",
+            IpcCmd::Stop => "Stop",
+            IpcCmd::CancelRecording => "CancelRecording",
+            IpcCmd::Toggle { .. } => "Toggle",
+            IpcCmd::TranscribeFile { .. } => "TranscribeFile",
+            IpcCmd::TranscribeStream => "TranscribeStream",
+            IpcCmd::RetryLast => "RetryLast",
+            IpcCmd::Status => "Status",
+            IpcCmd::StatusVerbose => "StatusVerbose",
+            IpcCmd::ListDevices => "ListDevices",
+            IpcCmd::Health => "Health",
+            IpcCmd::Audit => "Audit",
+            IpcCmd::ReloadConfig => "ReloadConfig",
+            IpcCmd::WithVerbosity { .. } => "WithVerbosity",
+            IpcCmd::WithDiff { .. } => "WithDiff",
+        }
+    }
+
+    /// 監査ログなどに記録するコマンド種別名。引数は含めない
+    pub fn name(&self) -> &'static str {
+        match self {
+            IpcCmd::WithVerbosity { cmd, .. } | IpcCmd::WithDiff { cmd } => cmd.name(),
+            cmd => cmd.tag(),
+        }
+    }
 }
 
 /// デーモンからの汎用レスポンス。
@@ -94,7 +380,25 @@ impl From<AudioDataDto> for AudioData {
 
 /// コマンドを送信して `IpcResp` を取得する同期ユーティリティ。
 pub fn send_cmd(cmd: &IpcCmd) -> Result<IpcResp, IpcError> {
+    send_cmd_inner(cmd, None::<tokio::io::Empty>)
+}
+
+/// コマンド行に続けて `body` を EOF まで送り、`IpcResp` を取得する同期ユーティリティ。
+///
+/// 標準入力などを一時ファイルや全体のバッファを介さずにデーモンへ流す。
+pub fn send_cmd_with_body<R>(cmd: &IpcCmd, body: R) -> Result<IpcResp, IpcError>
+where
+    R: tokio::io::AsyncRead + Unpin,
+{
+    send_cmd_inner(cmd, Some(body))
+}
+
+fn send_cmd_inner<R>(cmd: &IpcCmd, body: Option<R>) -> Result<IpcResp, IpcError>
+where
+    R: tokio::io::AsyncRead + Unpin,
+{
     use futures::{SinkExt, StreamExt};
+    use tokio::io::AsyncWriteExt;
     use tokio::net::UnixStream;
     use tokio_util::codec::{FramedRead, FramedWrite, LinesCodec};
 
@@ -117,6 +421,14 @@ pub fn send_cmd(cmd: &IpcCmd) -> Result<IpcResp, IpcError> {
                 .send(serde_json::to_string(cmd).map_err(IpcError::Serialize)?)
                 .await
                 .map_err(IpcError::Send)?;
+            if let Some(mut body) = body {
+                let mut w = writer.into_inner();
+                tokio::io::copy(&mut body, &mut w)
+                    .await
+                    .map_err(IpcError::SendBody)?;
+                // 書き込み側を閉じてデーモンへ終端を伝える
+                w.shutdown().await.map_err(IpcError::SendBody)?;
+            }
             if let Some(Ok(line)) = reader.next().await {
                 serde_json::from_str::<IpcResp>(&line).map_err(IpcError::Deserialize)
             } else {
@@ -172,7 +484,7 @@ mod tests {
             let config = EnvConfig::try_from_env().unwrap();
             assert_eq!(
                 config.paths.ipc_socket_path(),
-                PathBuf::from("/tmp/voice_input.sock")
+                PathBuf::from(format!("/tmp/voice_input-{}.sock", current_uid()))
             );
 
             restore_env("VOICE_INPUT_SOCKET_PATH", orig_path);
@@ -219,6 +531,190 @@ mod tests {
         });
     }
 
+    /// 既定ソケットパスはユーザーごとに異なる
+    #[test]
+    fn default_socket_path_is_namespaced_by_uid() {
+        use crate::utils::config::default_socket_path_for_uid;
+
+        assert_eq!(
+            default_socket_path_for_uid(501),
+            PathBuf::from("/tmp/voice_input-501.sock")
+        );
+        assert_ne!(
+            default_socket_path_for_uid(501),
+            default_socket_path_for_uid(502)
+        );
+    }
+
+    /// 応答のない古いソケットは削除してバインドできる
+    #[test]
+    fn claim_socket_path_removes_stale_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("voice_input.sock");
+        drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+
+        claim_socket_path(&path).unwrap();
+
+        assert!(!path.exists());
+        std::os::unix::net::UnixListener::bind(&path).unwrap();
+    }
+
+    /// 稼働中デーモンのソケットは削除せずエラーにする
+    #[test]
+    fn claim_socket_path_rejects_live_daemon() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("voice_input.sock");
+        let _listener = std::os::unix::net::UnixListener::bind(&path).unwrap();
+
+        let result = claim_socket_path(&path);
+
+        assert!(matches!(result, Err(IpcError::DaemonAlreadyRunning(_))));
+        assert!(path.exists());
+    }
+
+    /// 壊れた JSON は malformed_json として分類される
+    #[test]
+    fn decode_cmd_classifies_malformed_json() {
+        assert!(matches!(
+            decode_cmd("{\"Start\":"),
+            Err(IpcProtocolError::MalformedJson(_))
+        ));
+        assert!(matches!(
+            decode_cmd("not json"),
+            Err(IpcProtocolError::MalformedJson(_))
+        ));
+    }
+
+    /// 未知のコマンドはデーモンのバージョン付きで unknown_command として分類される
+    #[test]
+    fn decode_cmd_classifies_unknown_command() {
+        let error = decode_cmd("\"Teleport\"").unwrap_err();
+
+        assert!(matches!(error, IpcProtocolError::UnknownCommand { .. }));
+        assert!(error.to_string().contains(env!("CARGO_PKG_VERSION")));
+        assert!(matches!(
+            decode_cmd(r#"{"WithDiff":{"cmd":{"Teleport":{}}}}"#),
+            Err(IpcProtocolError::UnknownCommand { .. })
+        ));
+    }
+
+    /// 既知コマンドの引数に含まれる未知の列挙値は invalid_arguments として分類される
+    #[test]
+    fn decode_cmd_classifies_unknown_argument_variant_as_invalid_arguments() {
+        assert!(matches!(
+            decode_cmd(r#"{"Start":{"then":"Teleport"}}"#),
+            Err(IpcProtocolError::InvalidArguments(_))
+        ));
+        assert!(matches!(
+            decode_cmd(r#"{"WithVerbosity":{"verbosity":"Loud","cmd":"Stop"}}"#),
+            Err(IpcProtocolError::InvalidArguments(_))
+        ));
+    }
+
+    /// 既知のコマンド名は全バリアントの外部タグとちょうど一致する
+    #[test]
+    fn known_tags_match_every_command_variant() {
+        let commands = [
+            IpcCmd::Start {
+                prompt: None,
+                readback: false,
+                label: None,
+                then: TrailingAction::None,
+            },
+            IpcCmd::StartVoiceCommand,
+            IpcCmd::Stop,
+            IpcCmd::CancelRecording,
+            IpcCmd::Toggle {
+                prompt: None,
+                readback: false,
+                then: TrailingAction::None,
+            },
+            IpcCmd::TranscribeFile {
+                path: PathBuf::from("a.wav"),
+            },
+            IpcCmd::TranscribeStream,
+            IpcCmd::RetryLast,
+            IpcCmd::Status,
+            IpcCmd::StatusVerbose,
+            IpcCmd::ListDevices,
+            IpcCmd::Health,
+            IpcCmd::Audit,
+            IpcCmd::ReloadConfig,
+            IpcCmd::Stop.with_verbosity(Verbosity::Quiet),
+            IpcCmd::Stop.with_diff(true),
+        ];
+        // バリアントを追加するとこの match が網羅的でなくなり、上の一覧への追加を促す
+        let mut covered = [false; IPC_CMD_TAGS.len()];
+        for cmd in &commands {
+            let index = match cmd {
+                IpcCmd::Start { .. } => 0,
+                IpcCmd::StartVoiceCommand => 1,
+                IpcCmd::Stop => 2,
+                IpcCmd::CancelRecording => 3,
+                IpcCmd::Toggle { .. } => 4,
+                IpcCmd::TranscribeFile { .. } => 5,
+                IpcCmd::TranscribeStream => 6,
+                IpcCmd::RetryLast => 7,
+                IpcCmd::Status => 8,
+                IpcCmd::StatusVerbose => 9,
+                IpcCmd::ListDevices => 10,
+                IpcCmd::Health => 11,
+                IpcCmd::Audit => 12,
+                IpcCmd::ReloadConfig => 13,
+                IpcCmd::WithVerbosity { .. } => 14,
+                IpcCmd::WithDiff { .. } => 15,
+            };
+            covered[index] = true;
+        }
+        assert!(covered.iter().all(|&c| c), "{:?}", covered);
+
+        let tags: std::collections::BTreeSet<_> = commands.iter().map(IpcCmd::tag).collect();
+        assert_eq!(tags, IPC_CMD_TAGS.into_iter().collect());
+
+        for cmd in commands {
+            let line = serde_json::to_string(&cmd).unwrap();
+            assert!(!names_unknown_command(&line), "{}", line);
+            // 引数のないコマンドは文字列、引数のあるコマンドは 1 キーのオブジェクトになる
+            let tag = format!("\"{}\"", cmd.tag());
+            assert!(
+                line == tag || line.starts_with(&format!("{{{}:", tag)),
+                "{}",
+                line
+            );
+        }
+    }
+
+    /// 既知コマンドの引数型が合わない場合は invalid_arguments として分類される
+    #[test]
+    fn decode_cmd_classifies_invalid_arguments() {
+        assert!(matches!(
+            decode_cmd(r#"{"Start":{"readback":"yes"}}"#),
+            Err(IpcProtocolError::InvalidArguments(_))
+        ));
+    }
+
+    /// 行長上限を超えたリクエストは line_too_long として応答される
+    #[tokio::test]
+    async fn oversized_line_is_rejected_by_codec() {
+        use futures::StreamExt;
+        use tokio_util::codec::FramedRead;
+
+        let mut input = vec![b'a'; MAX_IPC_LINE_BYTES + 1];
+        input.push(b'\n');
+        let mut reader = FramedRead::new(&input[..], ipc_line_codec());
+
+        let line = reader.next().await.unwrap();
+        let error = decode_cmd_line(line).unwrap_err();
+
+        assert_eq!(
+            error,
+            IpcProtocolError::LineTooLong {
+                max: MAX_IPC_LINE_BYTES
+            }
+        );
+        assert!(!error.to_resp().ok);
+    }
+
     /// AudioDataDtoがバイト列を保持する
     #[test]
     fn audio_data_dto_holds_bytes() {
@@ -320,13 +816,16 @@ mod tests {
         // Test that existing IPC commands still work
         let cmd = IpcCmd::Start {
             prompt: Some("test prompt".to_string()),
+            readback: false,
+            label: None,
+            then: TrailingAction::None,
         };
 
         let json = serde_json::to_string(&cmd).unwrap();
         let deserialized: IpcCmd = serde_json::from_str(&json).unwrap();
 
         match deserialized {
-            IpcCmd::Start { prompt } => {
+            IpcCmd::Start { prompt, .. } => {
                 assert_eq!(prompt, Some("test prompt".to_string()));
             }
             _ => panic!("Expected Start command"),
@@ -349,7 +848,12 @@ mod tests {
     #[test]
     fn ipc_commands_remain_backward_compatible() {
         // 既存のIPCコマンドが引き続き動作することを確認
-        let cmd = IpcCmd::Start { prompt: None };
+        let cmd = IpcCmd::Start {
+            prompt: None,
+            readback: false,
+            label: None,
+            then: TrailingAction::None,
+        };
         let json = serde_json::to_string(&cmd).unwrap();
         assert!(json.contains("Start"));
 
@@ -361,14 +865,35 @@ mod tests {
 
         let cmd = IpcCmd::Toggle {
             prompt: Some("test".to_string()),
+            readback: false,
+            then: TrailingAction::None,
         };
         let json = serde_json::to_string(&cmd).unwrap();
         let deserialized: IpcCmd = serde_json::from_str(&json).unwrap();
         match deserialized {
-            IpcCmd::Toggle { prompt } => {
+            IpcCmd::Toggle { prompt, .. } => {
                 assert_eq!(prompt, Some("test".to_string()));
             }
             _ => panic!("Expected Toggle command"),
         }
     }
+
+    /// コマンド行と一緒に読まれた分と残りのストリームをつないで本文とする
+    #[tokio::test]
+    async fn cmd_body_joins_buffered_bytes_and_stream() {
+        let body = read_cmd_body(b"RIFF", &b"....WAVE"[..]).await.unwrap();
+        assert_eq!(body, b"RIFF....WAVE");
+    }
+
+    /// 上限を超える本文は最後まで読まずに拒否する
+    #[tokio::test]
+    async fn cmd_body_over_limit_is_rejected() {
+        let result = read_cmd_body(&[], tokio::io::repeat(0)).await;
+        assert_eq!(
+            result,
+            Err(IpcProtocolError::BodyTooLarge {
+                max: MAX_IPC_BODY_BYTES
+            })
+        );
+    }
 }
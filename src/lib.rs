@@ -6,6 +6,7 @@ pub mod utils {
     pub mod config;
     pub mod env;
     pub mod profiling;
+    pub(crate) mod user;
 }
 
 pub mod cli;
@@ -1,13 +1,22 @@
 //! voice_input CLI: `voice_inputd` デーモンの簡易コントローラ。
-//! 録音操作（Start/Stop/Toggle/Status）のほか、ヘルスチェック、デバイス一覧、
+//! 録音操作（Start/Stop/Toggle/Status）、音声ファイル転写のほか、ヘルスチェック、デバイス一覧、
 //! 辞書操作、設定操作の各コマンドを `ipc::send_cmd` で送信します。
+//! `bench` はデーモンを介さずこのプロセス内で計測します。
 use clap::Parser;
 use voice_input::{
     application::DictionaryService,
     cli::{Cli, Cmd, ConfigCmd, ConfigField, DictCmd},
-    domain::dict::{EntryStatus, WordEntry},
-    infrastructure::{config::AppConfig, dict::JsonFileDictRepo},
-    ipc::{IpcCmd, send_cmd},
+    domain::{
+        dict::{EntryStatus, WordEntry},
+        input::TrailingAction,
+    },
+    infrastructure::{
+        bench::{BenchOptions, run_bench},
+        config::AppConfig,
+        dict::JsonFileDictRepo,
+        external::clipboard_audio::audio_path_from_clipboard,
+    },
+    ipc::{IpcCmd, IpcResp, Verbosity, send_cmd, send_cmd_with_body},
     load_env,
     utils::config::EnvConfig,
 };
@@ -19,6 +28,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     EnvConfig::init()?;
 
     let cli = Cli::parse();
+    let verbosity = Verbosity::from_flags(cli.quiet, cli.verbose);
+    let relay = |cmd: IpcCmd| relay(cmd, verbosity);
 
     /* ── 追加: デバイス一覧フラグ ── */
     if cli.list_devices {
@@ -31,13 +42,73 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     /* ───── コマンド解析 ──────────── */
-    match cli.cmd.unwrap_or(Cmd::Toggle { prompt: None }) {
+    match cli.cmd.unwrap_or(Cmd::Toggle {
+        prompt: None,
+        readback: false,
+        then: TrailingAction::None,
+    }) {
         /* 録音系 → IPC */
-        Cmd::Start { prompt } => relay(IpcCmd::Start { prompt })?,
-        Cmd::Stop => relay(IpcCmd::Stop)?,
-        Cmd::Toggle { prompt } => relay(IpcCmd::Toggle { prompt })?,
+        Cmd::Start {
+            prompt,
+            readback,
+            label,
+            then,
+        } => relay(IpcCmd::Start {
+            prompt,
+            readback,
+            label,
+            then,
+        })?,
+        Cmd::Stop { show_diff } => relay(IpcCmd::Stop.with_diff(show_diff))?,
+        Cmd::VoiceCommand => relay(IpcCmd::StartVoiceCommand)?,
+        Cmd::Cancel => relay(IpcCmd::CancelRecording)?,
+        Cmd::Toggle {
+            prompt,
+            readback,
+            then,
+        } => relay(IpcCmd::Toggle {
+            prompt,
+            readback,
+            then,
+        })?,
+        Cmd::Transcribe {
+            file, show_diff, ..
+        } => {
+            // `--from-clipboard` 指定時は file と排他のため未指定になる
+            let path = match file {
+                Some(path) => path,
+                None => audio_path_from_clipboard()?,
+            };
+            if path.as_os_str() == "-" {
+                relay_stdin_ok(IpcCmd::TranscribeStream.with_diff(show_diff), verbosity)?;
+            } else {
+                // デーモンとカレントディレクトリが異なるため絶対パスで渡す
+                let path = std::fs::canonicalize(path)?;
+                relay(IpcCmd::TranscribeFile { path }.with_diff(show_diff))?;
+            }
+        }
+        Cmd::RetryLast { show_diff } => relay(IpcCmd::RetryLast.with_diff(show_diff))?,
+        Cmd::Status if cli.verbose => relay(IpcCmd::StatusVerbose)?,
         Cmd::Status => relay(IpcCmd::Status)?,
         Cmd::Health => relay(IpcCmd::Health)?,
+        Cmd::Audit => relay(IpcCmd::Audit)?,
+        Cmd::ReloadConfig => relay(IpcCmd::ReloadConfig)?,
+        Cmd::Bench { skip_api, paste } => {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()?;
+            let local = tokio::task::LocalSet::new();
+            let report = local.block_on(&runtime, run_bench(BenchOptions { skip_api, paste }));
+            let config = EnvConfig::get();
+            let provider = format!(
+                "{} ({})",
+                config.transcription.provider.as_str(),
+                config.transcription.model
+            );
+            for line in report.format_lines(&provider) {
+                println!("{line}");
+            }
+        }
 
         /* 辞書操作 → ローカル JSON */
         Cmd::Dict { action } => {
@@ -53,12 +124,16 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                         hit: 0,
                         status: EntryStatus::Active,
                     })?;
-                    println!("✅ Added/updated entry for “{surface}”");
+                    if !cli.quiet {
+                        println!("✅ Added/updated entry for “{surface}”");
+                    }
                 }
                 DictCmd::Remove { surface } => {
                     if service.delete(&surface)? {
-                        println!("🗑️  Removed “{surface}”");
-                    } else {
+                        if !cli.quiet {
+                            println!("🗑️  Removed “{surface}”");
+                        }
+                    } else if !cli.quiet {
                         println!("ℹ️  No entry found for “{surface}”");
                     }
                 }
@@ -73,6 +148,25 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                         }
                     }
                 }
+                DictCmd::Analyze => {
+                    let analyses = service.analyze()?;
+                    if analyses.is_empty() {
+                        println!("(no entries)");
+                    } else {
+                        println!("─ Dictionary analysis ──────");
+                        for a in &analyses {
+                            println!(
+                                "• {:<20} → {} hits={} ({:.1}%)",
+                                a.surface, a.replacement, a.hit, a.hit_share
+                            );
+                            for issue in &a.issues {
+                                println!("    ⚠️  {issue}");
+                            }
+                        }
+                        let flagged = analyses.iter().filter(|a| !a.issues.is_empty()).count();
+                        println!("{flagged} of {} entries flagged", analyses.len());
+                    }
+                }
             }
         }
         Cmd::Config { action } => match action {
@@ -80,7 +174,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 ConfigField::DictPath { path } => {
                     let mut cfg = AppConfig::load();
                     cfg.set_dict_path(std::path::PathBuf::from(&path))?;
-                    println!("✅ dict-path set to {path}");
+                    if !cli.quiet {
+                        println!("✅ dict-path set to {path}");
+                    }
                 }
             },
         },
@@ -88,12 +184,37 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-fn relay(cmd: IpcCmd) -> Result<(), Box<dyn std::error::Error>> {
-    let resp = send_cmd(&cmd)?;
+fn relay(cmd: IpcCmd, verbosity: Verbosity) -> Result<(), Box<dyn std::error::Error>> {
+    relay_ok(cmd, verbosity).map(|_| ())
+}
+
+/// コマンドを送信して結果を表示し、デーモンが成功を返したかを返す
+///
+/// `--quiet` では成功時に何も表示せず、`--verbose` では往復時間も標準エラーへ表示する。
+fn relay_ok(cmd: IpcCmd, verbosity: Verbosity) -> Result<bool, Box<dyn std::error::Error>> {
+    let started_at = std::time::Instant::now();
+    let resp = send_cmd(&cmd.with_verbosity(verbosity))?;
+    Ok(report_resp(resp, verbosity, started_at))
+}
+
+/// 標準入力の内容をコマンドに続けて送り、結果を表示する
+fn relay_stdin_ok(cmd: IpcCmd, verbosity: Verbosity) -> Result<bool, Box<dyn std::error::Error>> {
+    let started_at = std::time::Instant::now();
+    let resp = send_cmd_with_body(&cmd.with_verbosity(verbosity), tokio::io::stdin())?;
+    Ok(report_resp(resp, verbosity, started_at))
+}
+
+/// レスポンスを表示し、成功したかを返す
+fn report_resp(resp: IpcResp, verbosity: Verbosity, started_at: std::time::Instant) -> bool {
+    if verbosity == Verbosity::Verbose {
+        eprintln!("ipc round-trip: {}ms", started_at.elapsed().as_millis());
+    }
     if resp.ok {
-        println!("{}", resp.msg);
+        if verbosity != Verbosity::Quiet {
+            println!("{}", resp.msg);
+        }
     } else {
         eprintln!("Error: {}", resp.msg);
     }
-    Ok(())
+    resp.ok
 }
@@ -7,11 +7,14 @@
 //! プロセス起動時に一度だけ初期化し、以降はどこからでもアクセス可能。
 
 use once_cell::sync::OnceCell;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
-/// グローバル環境変数設定
-static ENV_CONFIG: OnceCell<Arc<EnvConfig>> = OnceCell::new();
+use crate::utils::user::current_uid;
+
+/// グローバル環境変数設定（`replace` で差し替えられる）
+static ENV_CONFIG: OnceCell<RwLock<Arc<EnvConfig>>> = OnceCell::new();
 
 #[cfg(test)]
 use std::sync::Mutex;
@@ -37,12 +40,36 @@ pub enum ConfigError {
         "TRANSCRIPTION_MODEL={value} is unsupported for provider {provider}. Supported OpenAI models: gpt-4o-mini-transcribe, gpt-4o-transcribe"
     )]
     UnsupportedTranscriptionModel { provider: String, value: String },
+    #[error("OPENAI_TIMEOUT_SECS must be a positive integer: {value}")]
+    InvalidOpenAiTimeout { value: String },
     #[error("VOICE_INPUT_MAX_SECS must be an integer: {value}")]
     InvalidMaxDurationSecs { value: String },
+    #[error("VOICE_INPUT_MIN_RECORDING_MS must be an integer: {value}")]
+    InvalidMinRecordingMs { value: String },
+    #[error("VOICE_INPUT_MAX_EXTENSION_SECS must be an integer: {value}")]
+    InvalidMaxExtensionSecs { value: String },
+    #[error(
+        "VOICE_INPUT_SCREEN_LOCK_ACTION must be one of 'discard', 'transcribe', 'ignore': {value}"
+    )]
+    InvalidScreenLockAction { value: String },
+    #[error("VOICE_INPUT_PASTE_DELAYS entries must be '<app name>=<ms>': {value}")]
+    InvalidPasteDelay { value: String },
+    #[error("VOICE_INPUT_NICE must be an integer between -20 and 19: {value}")]
+    InvalidNiceLevel { value: String },
+    #[error("VOICE_INPUT_MAX_RSS_MB must be a positive integer: {value}")]
+    InvalidMaxRssMb { value: String },
+    #[error("VOICE_INPUT_STARTUP_STAGE_DEADLINE_MS must be a positive integer: {value}")]
+    InvalidStartupStageDeadline { value: String },
     #[error("{name} must be either 'true' or 'false': {value}")]
     InvalidBooleanEnv { name: &'static str, value: String },
     #[error("VOICE_INPUT_AUDIO_FORMAT must be either 'flac' or 'wav': {value}")]
     InvalidAudioFormat { value: String },
+    #[error("VOICE_INPUT_FLAC_COMPRESSION_LEVEL must be an integer from 0 to 8: {value}")]
+    InvalidFlacCompressionLevel { value: String },
+    #[error("VOICE_INPUT_AUDIO_CACHE_ENTRIES must be a non-negative integer: {value}")]
+    InvalidAudioCacheEntries { value: String },
+    #[error("VOICE_INPUT_TIMESTAMP_FORMAT is not a valid strftime format: {value}")]
+    InvalidTimestampFormat { value: String },
     #[error(
         "VOICE_INPUT_AUDIO_FORMAT={value} is unsupported for provider {provider}. Supported formats: {supported}"
     )]
@@ -64,10 +91,10 @@ impl TranscriptionProvider {
     const DEFAULT: Self = Self::OpenAi;
 
     /// 環境変数から転写バックエンド設定を生成
-    pub fn from_env() -> Result<Self, ConfigError> {
-        match std::env::var("TRANSCRIPTION_PROVIDER") {
-            Ok(value) => Self::parse(&value),
-            Err(_) => Ok(Self::DEFAULT),
+    fn from_vars(vars: &EnvVars) -> Result<Self, ConfigError> {
+        match vars.var("TRANSCRIPTION_PROVIDER") {
+            Some(value) => Self::parse(&value),
+            None => Ok(Self::DEFAULT),
         }
     }
 
@@ -128,8 +155,31 @@ pub struct TranscriptionConfig {
     pub log_path: Option<PathBuf>,
     /// 低信頼語の自動選択を有効にする
     pub low_confidence_selection_enabled: bool,
+    /// フォーカス中の入力欄でキャレット直前の文字列を読み取り、転写の文脈に使う
+    pub caret_context_enabled: bool,
+    /// 「日時を挿入」マクロの書式（strftime 形式）。未指定ならロケールの既定
+    pub timestamp_format: Option<String>,
     /// mlx-qwen3-asr コマンド名
     pub mlx_qwen3_asr_command: String,
+    /// OpenAI API のベース URL 上書き（モックサーバーや互換 API 向け）
+    pub openai_base_url: Option<String>,
+    /// OpenAI API リクエストの付加設定
+    pub openai_request: OpenAiRequestConfig,
+    /// 同じ音声を並行して送り、先に成功した結果を使う 2 つ目のバックエンド（実験的）
+    pub race_provider: Option<TranscriptionProvider>,
+}
+
+/// OpenAI API リクエストの付加設定（組織・プロジェクト単位のキー向け）
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OpenAiRequestConfig {
+    /// `OpenAI-Organization` ヘッダに送る組織 ID
+    pub organization: Option<String>,
+    /// `OpenAI-Project` ヘッダに送るプロジェクト ID
+    pub project: Option<String>,
+    /// リクエスト全体のタイムアウト秒数（未指定なら無制限）
+    pub timeout_secs: Option<u64>,
+    /// 各リクエストに付与する利用者タグ
+    pub user: Option<String>,
 }
 
 impl TranscriptionConfig {
@@ -137,6 +187,19 @@ impl TranscriptionConfig {
     pub fn recommended_parallelism(&self) -> usize {
         if self.streaming_enabled { 1 } else { 2 }
     }
+
+    /// 競争モードで 2 つ目に使うバックエンドの設定を返す
+    ///
+    /// モデルはバックエンドごとに異なるため、競争相手は既定のモデルを使う。
+    pub fn race_config(&self) -> Option<TranscriptionConfig> {
+        let provider = self.race_provider?;
+        Some(TranscriptionConfig {
+            provider,
+            model: provider.default_model().to_string(),
+            race_provider: None,
+            ..self.clone()
+        })
+    }
 }
 
 /// パス系の設定
@@ -154,7 +217,6 @@ impl PathConfig {
     /// IPC ソケットパスを返す
     pub fn ipc_socket_path(&self) -> PathBuf {
         const SOCKET_FILENAME: &str = "voice_input.sock";
-        const DEFAULT_SOCKET_PATH: &str = "/tmp/voice_input.sock";
 
         if let Some(path) = self.socket_path.as_ref() {
             return path.clone();
@@ -164,10 +226,18 @@ impl PathConfig {
             return dir.join(SOCKET_FILENAME);
         }
 
-        PathBuf::from(DEFAULT_SOCKET_PATH)
+        default_socket_path_for_uid(current_uid())
     }
 }
 
+/// UID ごとに分けた既定の IPC ソケットパス
+///
+/// 同じ Mac の複数ユーザー（ファストユーザスイッチ）が `/tmp` 上の
+/// 同じソケットを奪い合わないよう、ファイル名へ UID を含める。
+pub fn default_socket_path_for_uid(uid: u32) -> PathBuf {
+    PathBuf::from(format!("/tmp/voice_input-{}.sock", uid))
+}
+
 /// HTTP プロキシ設定
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ProxyConfig {
@@ -186,6 +256,17 @@ pub struct AudioConfig {
     pub input_device_priorities: Vec<String>,
     /// 録音フォーマット
     pub preferred_format: PreferredAudioFormat,
+    /// FLAC の圧縮レベル（0 が最速、8 が最小サイズ）
+    pub flac_compression_level: u8,
+    /// 再転写用にディスクへ残す直近の録音数（0 なら残さない）
+    pub cache_entries: usize,
+}
+
+impl AudioConfig {
+    /// 未指定時の FLAC 圧縮レベル
+    pub const DEFAULT_FLAC_COMPRESSION_LEVEL: u8 = 5;
+    /// 未指定時に残す直近の録音数（録音内容をディスクに残すため既定では保存しない）
+    pub const DEFAULT_CACHE_ENTRIES: usize = 0;
 }
 
 /// 録音フォーマット
@@ -207,6 +288,56 @@ pub struct ProfilingConfig {
 pub struct RecordingConfig {
     /// 最大録音秒数
     pub max_duration_secs: u64,
+    /// 転写対象とする最小録音ミリ秒
+    pub min_duration_ms: u64,
+    /// 発話継続時に自動停止を延長できる上限秒数
+    pub max_extension_secs: u64,
+    /// 録音中に画面がロックされた場合の扱い
+    pub screen_lock_action: ScreenLockAction,
+}
+
+/// 録音中の画面ロック時の扱い
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScreenLockAction {
+    /// 録音を中止して音声を破棄する
+    #[default]
+    Discard,
+    /// 録音を止め、ロック解除後に転写・入力する
+    TranscribeOnUnlock,
+    /// 何もしない
+    Ignore,
+}
+
+impl ScreenLockAction {
+    fn from_vars(vars: &EnvVars) -> Result<Self, ConfigError> {
+        match vars.non_empty("VOICE_INPUT_SCREEN_LOCK_ACTION") {
+            Some(value) => match value.to_ascii_lowercase().as_str() {
+                "discard" => Ok(Self::Discard),
+                "transcribe" => Ok(Self::TranscribeOnUnlock),
+                "ignore" => Ok(Self::Ignore),
+                _ => Err(ConfigError::InvalidScreenLockAction { value }),
+            },
+            None => Ok(Self::default()),
+        }
+    }
+}
+
+/// テキスト入力設定
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TextInputConfig {
+    /// アプリ名ごとの入力前待機時間（ミリ秒）
+    pub paste_delays_ms: BTreeMap<String, u64>,
+}
+
+/// デーモン自身のリソース制限設定
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ResourceConfig {
+    /// 起動時に適用する nice 値
+    pub nice_level: Option<i32>,
+    /// RSS 監視の上限（MB）
+    pub max_rss_mb: Option<u64>,
+    /// 起動段階ごとの期限（ミリ秒）。超えた段階があれば起動を中止する
+    pub startup_stage_deadline_ms: Option<u64>,
 }
 
 /// 環境変数設定
@@ -224,54 +355,102 @@ pub struct EnvConfig {
     pub recording: RecordingConfig,
     /// プロファイリング設定
     pub profiling: ProfilingConfig,
+    /// リソース制限設定
+    pub resources: ResourceConfig,
+    /// テキスト入力設定
+    pub text_input: TextInputConfig,
 }
 
 impl EnvConfig {
     /// 環境変数から設定を構築し、妥当性を検証する
     pub(crate) fn from_env() -> Result<Self, ConfigError> {
-        let provider = TranscriptionProvider::from_env()?;
-        let model = load_transcription_model(provider)?;
-        let streaming_enabled = parse_bool_env("OPENAI_TRANSCRIBE_STREAMING")?;
-        let mlx_qwen3_asr_command = load_mlx_qwen3_asr_command();
-        let preferred_format = PreferredAudioFormat::from_env(provider)?;
-        let max_duration_secs = match std::env::var("VOICE_INPUT_MAX_SECS") {
-            Ok(value) => value
+        Self::from_vars(&EnvVars::default())
+    }
+
+    /// 変数の読み出し元から設定を構築し、妥当性を検証する
+    fn from_vars(vars: &EnvVars) -> Result<Self, ConfigError> {
+        let provider = TranscriptionProvider::from_vars(vars)?;
+        let model = load_transcription_model(vars, provider)?;
+        let streaming_enabled = vars.parse_bool("OPENAI_TRANSCRIBE_STREAMING")?;
+        let mlx_qwen3_asr_command = load_mlx_qwen3_asr_command(vars);
+        let preferred_format = PreferredAudioFormat::from_vars(vars, provider)?;
+        let race_provider = match vars.non_empty("VOICE_INPUT_RACE_PROVIDER") {
+            Some(value) => {
+                let race_provider = TranscriptionProvider::parse(&value)?;
+                preferred_format.validate_for_race_provider(race_provider)?;
+                Some(race_provider)
+            }
+            None => None,
+        };
+        let max_duration_secs = match vars.var("VOICE_INPUT_MAX_SECS") {
+            Some(value) => value
                 .parse()
                 .map_err(|_| ConfigError::InvalidMaxDurationSecs { value })?,
-            Err(_) => 30,
+            None => 30,
+        };
+        let min_duration_ms = match vars.non_empty("VOICE_INPUT_MIN_RECORDING_MS") {
+            Some(value) => value
+                .parse()
+                .map_err(|_| ConfigError::InvalidMinRecordingMs { value })?,
+            None => 500,
         };
+        let max_extension_secs = match vars.non_empty("VOICE_INPUT_MAX_EXTENSION_SECS") {
+            Some(value) => value
+                .parse()
+                .map_err(|_| ConfigError::InvalidMaxExtensionSecs { value })?,
+            None => 10,
+        };
+        let screen_lock_action = ScreenLockAction::from_vars(vars)?;
+        let resources = load_resource_config(vars)?;
+        let paste_delays_ms = load_paste_delays(vars)?;
 
         Ok(Self {
             paths: PathConfig {
-                xdg_data_home: non_empty_env("XDG_DATA_HOME").map(PathBuf::from),
-                socket_path: non_empty_env("VOICE_INPUT_SOCKET_PATH").map(PathBuf::from),
-                socket_dir: non_empty_env("VOICE_INPUT_SOCKET_DIR").map(PathBuf::from),
+                xdg_data_home: vars.non_empty("XDG_DATA_HOME").map(PathBuf::from),
+                socket_path: vars.non_empty("VOICE_INPUT_SOCKET_PATH").map(PathBuf::from),
+                socket_dir: vars.non_empty("VOICE_INPUT_SOCKET_DIR").map(PathBuf::from),
             },
             transcription: TranscriptionConfig {
                 provider,
-                api_key: non_empty_env("TRANSCRIPTION_API_KEY")
-                    .or_else(|| non_empty_env("OPENAI_API_KEY")),
+                api_key: vars
+                    .non_empty("TRANSCRIPTION_API_KEY")
+                    .or_else(|| vars.non_empty("OPENAI_API_KEY")),
                 model,
                 streaming_enabled,
-                log_path: non_empty_env("OPENAI_TRANSCRIPTION_LOG_PATH").map(PathBuf::from),
-                low_confidence_selection_enabled: parse_bool_env(
-                    "VOICE_INPUT_LOW_CONFIDENCE_SELECTION",
-                )?,
+                log_path: vars
+                    .non_empty("OPENAI_TRANSCRIPTION_LOG_PATH")
+                    .map(PathBuf::from),
+                low_confidence_selection_enabled: vars
+                    .parse_bool("VOICE_INPUT_LOW_CONFIDENCE_SELECTION")?,
+                caret_context_enabled: vars.parse_bool("VOICE_INPUT_CARET_CONTEXT")?,
+                timestamp_format: load_timestamp_format(vars)?,
                 mlx_qwen3_asr_command,
+                openai_base_url: vars.non_empty("OPENAI_BASE_URL"),
+                openai_request: load_openai_request_config(vars)?,
+                race_provider,
             },
             proxy: ProxyConfig {
-                all: non_empty_env_with_lowercase_fallback("ALL_PROXY"),
-                https: non_empty_env_with_lowercase_fallback("HTTPS_PROXY"),
-                http: non_empty_env_with_lowercase_fallback("HTTP_PROXY"),
+                all: vars.non_empty_with_lowercase_fallback("ALL_PROXY"),
+                https: vars.non_empty_with_lowercase_fallback("HTTPS_PROXY"),
+                http: vars.non_empty_with_lowercase_fallback("HTTP_PROXY"),
             },
             audio: AudioConfig {
-                input_device_priorities: csv_env("INPUT_DEVICE_PRIORITY"),
+                input_device_priorities: vars.csv("INPUT_DEVICE_PRIORITY"),
                 preferred_format,
+                flac_compression_level: load_flac_compression_level(vars)?,
+                cache_entries: load_audio_cache_entries(vars)?,
+            },
+            recording: RecordingConfig {
+                max_duration_secs,
+                min_duration_ms,
+                max_extension_secs,
+                screen_lock_action,
             },
-            recording: RecordingConfig { max_duration_secs },
             profiling: ProfilingConfig {
-                enabled: parse_bool_env("VOICE_INPUT_PROFILE")?,
+                enabled: vars.parse_bool("VOICE_INPUT_PROFILE")?,
             },
+            resources,
+            text_input: TextInputConfig { paste_delays_ms },
         })
     }
 
@@ -297,10 +476,205 @@ impl EnvConfig {
         let config = EnvConfig::from_env()?;
 
         // 並列実行時の競合を考慮：既に他のスレッドが初期化していても成功とする
-        let _ = ENV_CONFIG.set(Arc::new(config));
+        let _ = ENV_CONFIG.set(RwLock::new(Arc::new(config)));
         Ok(())
     }
 
+    /// 環境変数ファイルの値から次の設定を組み立てる（グローバル設定はまだ差し替えない）
+    ///
+    /// ファイルの値はプロセス環境より優先し、プロセス環境は書き換えない。
+    /// 再起動が必要な設定は現在の値のまま残し、変更点として報告する。
+    /// 読み込みや検証に失敗した場合は現在の設定を使い続ける。
+    ///
+    /// # Panics
+    /// `init()`が呼ばれていない場合パニックする
+    pub fn prepare_reload(
+        file_vars: HashMap<String, String>,
+    ) -> Result<(EnvConfig, ConfigChanges), ConfigError> {
+        let reloaded = Self::from_vars(&EnvVars::with_overrides(file_vars))?;
+        Ok(Self::get().merge_reloaded(reloaded))
+    }
+
+    /// `prepare_reload` で組み立てた設定へ差し替える
+    ///
+    /// # Panics
+    /// `init()`が呼ばれていない場合パニックする
+    pub fn replace(config: EnvConfig) {
+        *ENV_CONFIG
+            .get()
+            .expect("EnvConfig not initialized. Call EnvConfig::init() first")
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Arc::new(config);
+    }
+
+    /// 読み直した設定のうち再起動が必要なものを現在の値へ戻し、変更点と合わせて返す
+    fn merge_reloaded(&self, mut reloaded: EnvConfig) -> (EnvConfig, ConfigChanges) {
+        let mut changes = ConfigChanges::default();
+
+        // 起動時に構築したクライアント・ソケット・監視タスクが使う設定
+        let restart = &mut changes.restart_required;
+        keep_running(
+            &self.paths,
+            &mut reloaded.paths,
+            "XDG_DATA_HOME / VOICE_INPUT_SOCKET_*",
+            restart,
+        );
+        let (running, next) = (&self.transcription, &mut reloaded.transcription);
+        keep_running(
+            &running.provider,
+            &mut next.provider,
+            "TRANSCRIPTION_PROVIDER",
+            restart,
+        );
+        keep_running(
+            &running.api_key,
+            &mut next.api_key,
+            "TRANSCRIPTION_API_KEY",
+            restart,
+        );
+        keep_running(
+            &running.model,
+            &mut next.model,
+            "TRANSCRIPTION_MODEL",
+            restart,
+        );
+        keep_running(
+            &running.log_path,
+            &mut next.log_path,
+            "OPENAI_TRANSCRIPTION_LOG_PATH",
+            restart,
+        );
+        keep_running(
+            &running.mlx_qwen3_asr_command,
+            &mut next.mlx_qwen3_asr_command,
+            "MLX_QWEN3_ASR_COMMAND",
+            restart,
+        );
+        keep_running(
+            &running.openai_base_url,
+            &mut next.openai_base_url,
+            "OPENAI_BASE_URL",
+            restart,
+        );
+        keep_running(
+            &running.openai_request,
+            &mut next.openai_request,
+            "OPENAI_TIMEOUT_SECS / OPENAI_ORG_ID / OPENAI_PROJECT_ID / OPENAI_USER",
+            restart,
+        );
+        keep_running(
+            &running.race_provider,
+            &mut next.race_provider,
+            "VOICE_INPUT_RACE_PROVIDER",
+            restart,
+        );
+        keep_running(
+            &self.proxy,
+            &mut reloaded.proxy,
+            "ALL_PROXY / HTTPS_PROXY / HTTP_PROXY",
+            restart,
+        );
+        // 転写バックエンドとの組み合わせで検証済みのため、バックエンドと一緒に切り替える
+        keep_running(
+            &self.audio.preferred_format,
+            &mut reloaded.audio.preferred_format,
+            "VOICE_INPUT_AUDIO_FORMAT",
+            restart,
+        );
+        keep_running(
+            &self.recording.screen_lock_action,
+            &mut reloaded.recording.screen_lock_action,
+            "VOICE_INPUT_SCREEN_LOCK_ACTION",
+            restart,
+        );
+        keep_running(
+            &self.profiling,
+            &mut reloaded.profiling,
+            "VOICE_INPUT_PROFILE",
+            restart,
+        );
+        keep_running(
+            &self.resources,
+            &mut reloaded.resources,
+            "VOICE_INPUT_NICE / VOICE_INPUT_MAX_RSS_MB / VOICE_INPUT_STARTUP_STAGE_DEADLINE_MS",
+            restart,
+        );
+
+        // 使うたびに読み直す設定
+        let applied = &mut changes.applied;
+        let (running, next) = (&self.transcription, &reloaded.transcription);
+        note_applied(
+            &running.streaming_enabled,
+            &next.streaming_enabled,
+            "OPENAI_TRANSCRIBE_STREAMING",
+            applied,
+        );
+        note_applied(
+            &running.low_confidence_selection_enabled,
+            &next.low_confidence_selection_enabled,
+            "VOICE_INPUT_LOW_CONFIDENCE_SELECTION",
+            applied,
+        );
+        note_applied(
+            &running.caret_context_enabled,
+            &next.caret_context_enabled,
+            "VOICE_INPUT_CARET_CONTEXT",
+            applied,
+        );
+        note_applied(
+            &running.timestamp_format,
+            &next.timestamp_format,
+            "VOICE_INPUT_TIMESTAMP_FORMAT",
+            applied,
+        );
+        let (running, next) = (&self.audio, &reloaded.audio);
+        note_applied(
+            &running.input_device_priorities,
+            &next.input_device_priorities,
+            "INPUT_DEVICE_PRIORITY",
+            applied,
+        );
+        note_applied(
+            &running.flac_compression_level,
+            &next.flac_compression_level,
+            "VOICE_INPUT_FLAC_COMPRESSION_LEVEL",
+            applied,
+        );
+        note_applied(
+            &running.cache_entries,
+            &next.cache_entries,
+            "VOICE_INPUT_AUDIO_CACHE_ENTRIES",
+            applied,
+        );
+        let (running, next) = (&self.recording, &reloaded.recording);
+        note_applied(
+            &running.max_duration_secs,
+            &next.max_duration_secs,
+            "VOICE_INPUT_MAX_SECS",
+            applied,
+        );
+        note_applied(
+            &running.min_duration_ms,
+            &next.min_duration_ms,
+            "VOICE_INPUT_MIN_RECORDING_MS",
+            applied,
+        );
+        note_applied(
+            &running.max_extension_secs,
+            &next.max_extension_secs,
+            "VOICE_INPUT_MAX_EXTENSION_SECS",
+            applied,
+        );
+        note_applied(
+            &self.text_input,
+            &reloaded.text_input,
+            "VOICE_INPUT_PASTE_DELAYS",
+            applied,
+        );
+
+        (reloaded, changes)
+    }
+
     /// 設定を取得
     ///
     /// # Panics
@@ -309,6 +683,8 @@ impl EnvConfig {
         ENV_CONFIG
             .get()
             .expect("EnvConfig not initialized. Call EnvConfig::init() first")
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
             .clone()
     }
 
@@ -321,7 +697,7 @@ impl EnvConfig {
         let _lock = TEST_LOCK.lock().unwrap();
 
         if ENV_CONFIG.get().is_none() {
-            ENV_CONFIG.set(Arc::new(config)).ok();
+            ENV_CONFIG.set(RwLock::new(Arc::new(config))).ok();
         }
     }
 
@@ -332,7 +708,7 @@ impl EnvConfig {
 
         if ENV_CONFIG.get().is_none() {
             let config = Self::load_for_test_init().expect("test env config should be valid");
-            ENV_CONFIG.set(Arc::new(config)).ok();
+            ENV_CONFIG.set(RwLock::new(Arc::new(config))).ok();
         }
     }
 
@@ -342,58 +718,245 @@ impl EnvConfig {
     }
 }
 
-fn non_empty_env(name: &str) -> Option<String> {
-    std::env::var(name)
-        .ok()
-        .map(|value| value.trim().to_string())
-        .filter(|value| !value.is_empty())
+/// 設定の再読み込みで変わった項目（環境変数名）
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigChanges {
+    /// 実行中のデーモンへ反映した設定
+    pub applied: Vec<&'static str>,
+    /// 反映にデーモンの再起動が必要な設定
+    pub restart_required: Vec<&'static str>,
 }
 
-fn non_empty_env_with_lowercase_fallback(name: &str) -> Option<String> {
-    non_empty_env(name).or_else(|| non_empty_env(&name.to_ascii_lowercase()))
+impl ConfigChanges {
+    /// 表示用の行に整形する
+    pub fn format_lines(&self) -> Vec<String> {
+        if self.applied.is_empty() && self.restart_required.is_empty() {
+            return vec!["config reloaded: no changes".to_string()];
+        }
+        let mut lines = Vec::new();
+        if !self.applied.is_empty() {
+            lines.push(format!("applied: {}", self.applied.join(", ")));
+        }
+        if !self.restart_required.is_empty() {
+            lines.push(format!(
+                "restart required: {}",
+                self.restart_required.join(", ")
+            ));
+        }
+        lines
+    }
 }
 
-fn csv_env(name: &str) -> Vec<String> {
-    non_empty_env(name)
-        .map(|value| {
-            value
-                .split(',')
-                .map(|entry| entry.trim().to_string())
-                .filter(|entry| !entry.is_empty())
-                .collect()
-        })
-        .unwrap_or_default()
+/// 値が変わっていれば再起動が必要な設定として記録し、現在の値へ戻す
+fn keep_running<T: PartialEq + Clone>(
+    running: &T,
+    reloaded: &mut T,
+    name: &'static str,
+    changes: &mut Vec<&'static str>,
+) {
+    if running != reloaded {
+        changes.push(name);
+        *reloaded = running.clone();
+    }
+}
+
+/// 値が変わっていれば反映した設定として記録する
+fn note_applied<T: PartialEq>(
+    running: &T,
+    reloaded: &T,
+    name: &'static str,
+    changes: &mut Vec<&'static str>,
+) {
+    if running != reloaded {
+        changes.push(name);
+    }
+}
+
+/// 設定の読み出し元となる変数
+///
+/// 再読み込みでは環境変数ファイルの値をプロセス環境より優先して読む。
+/// プロセス環境は読むだけで書き換えない。
+#[derive(Debug, Default)]
+struct EnvVars {
+    overrides: HashMap<String, String>,
+}
+
+impl EnvVars {
+    fn with_overrides(overrides: HashMap<String, String>) -> Self {
+        Self { overrides }
+    }
+
+    fn var(&self, name: &str) -> Option<String> {
+        match self.overrides.get(name) {
+            Some(value) => Some(value.clone()),
+            None => std::env::var(name).ok(),
+        }
+    }
+
+    fn non_empty(&self, name: &str) -> Option<String> {
+        self.var(name)
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    }
+
+    fn non_empty_with_lowercase_fallback(&self, name: &str) -> Option<String> {
+        self.non_empty(name)
+            .or_else(|| self.non_empty(&name.to_ascii_lowercase()))
+    }
+
+    fn csv(&self, name: &str) -> Vec<String> {
+        self.non_empty(name)
+            .map(|value| {
+                value
+                    .split(',')
+                    .map(|entry| entry.trim().to_string())
+                    .filter(|entry| !entry.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn parse_bool(&self, name: &'static str) -> Result<bool, ConfigError> {
+        match self.var(name) {
+            Some(value) => match value.as_str() {
+                "true" => Ok(true),
+                "false" => Ok(false),
+                _ => Err(ConfigError::InvalidBooleanEnv { name, value }),
+            },
+            None => Ok(false),
+        }
+    }
 }
 
-fn load_transcription_model(provider: TranscriptionProvider) -> Result<String, ConfigError> {
-    let value = non_empty_env("TRANSCRIPTION_MODEL").or_else(|| match provider {
-        TranscriptionProvider::OpenAi => non_empty_env("OPENAI_TRANSCRIBE_MODEL"),
-        TranscriptionProvider::MlxQwen3Asr => None,
-    });
+fn load_transcription_model(
+    vars: &EnvVars,
+    provider: TranscriptionProvider,
+) -> Result<String, ConfigError> {
+    let value = vars
+        .non_empty("TRANSCRIPTION_MODEL")
+        .or_else(|| match provider {
+            TranscriptionProvider::OpenAi => vars.non_empty("OPENAI_TRANSCRIBE_MODEL"),
+            TranscriptionProvider::MlxQwen3Asr => None,
+        });
 
     let model = value.unwrap_or_else(|| provider.default_model().to_string());
     provider.validate_model(&model)?;
     Ok(model)
 }
 
-fn load_mlx_qwen3_asr_command() -> String {
-    non_empty_env("MLX_QWEN3_ASR_COMMAND").unwrap_or_else(|| "mlx-qwen3-asr".into())
+fn load_mlx_qwen3_asr_command(vars: &EnvVars) -> String {
+    vars.non_empty("MLX_QWEN3_ASR_COMMAND")
+        .unwrap_or_else(|| "mlx-qwen3-asr".into())
 }
 
-fn parse_bool_env(name: &'static str) -> Result<bool, ConfigError> {
-    match std::env::var(name) {
-        Ok(value) => match value.as_str() {
-            "true" => Ok(true),
-            "false" => Ok(false),
-            _ => Err(ConfigError::InvalidBooleanEnv { name, value }),
+/// OpenAI API リクエストの付加設定を読み込む
+fn load_openai_request_config(vars: &EnvVars) -> Result<OpenAiRequestConfig, ConfigError> {
+    let timeout_secs = match vars.non_empty("OPENAI_TIMEOUT_SECS") {
+        Some(value) => match value.parse::<u64>() {
+            Ok(secs) if secs > 0 => Some(secs),
+            _ => return Err(ConfigError::InvalidOpenAiTimeout { value }),
         },
-        Err(_) => Ok(false),
+        None => None,
+    };
+
+    Ok(OpenAiRequestConfig {
+        organization: vars
+            .non_empty("OPENAI_ORG_ID")
+            .or_else(|| vars.non_empty("OPENAI_ORGANIZATION")),
+        project: vars.non_empty("OPENAI_PROJECT_ID"),
+        timeout_secs,
+        user: vars.non_empty("OPENAI_USER"),
+    })
+}
+
+/// `アプリ名=ミリ秒` のカンマ区切りをアプリ別の入力前待機時間として読み込む
+fn load_paste_delays(vars: &EnvVars) -> Result<BTreeMap<String, u64>, ConfigError> {
+    vars.csv("VOICE_INPUT_PASTE_DELAYS")
+        .into_iter()
+        .map(|entry| {
+            entry
+                .rsplit_once('=')
+                .and_then(|(app, ms)| {
+                    let app = app.trim();
+                    let ms = ms.trim().parse::<u64>().ok()?;
+                    (!app.is_empty()).then(|| (app.to_string(), ms))
+                })
+                .ok_or(ConfigError::InvalidPasteDelay { value: entry })
+        })
+        .collect()
+}
+
+fn load_resource_config(vars: &EnvVars) -> Result<ResourceConfig, ConfigError> {
+    let nice_level = match vars.non_empty("VOICE_INPUT_NICE") {
+        Some(value) => Some(
+            value
+                .parse::<i32>()
+                .ok()
+                .filter(|level| (-20..=19).contains(level))
+                .ok_or(ConfigError::InvalidNiceLevel { value })?,
+        ),
+        None => None,
+    };
+    let max_rss_mb = match vars.non_empty("VOICE_INPUT_MAX_RSS_MB") {
+        Some(value) => Some(
+            value
+                .parse::<u64>()
+                .ok()
+                .filter(|mb| *mb > 0)
+                .ok_or(ConfigError::InvalidMaxRssMb { value })?,
+        ),
+        None => None,
+    };
+    let startup_stage_deadline_ms = match vars.non_empty("VOICE_INPUT_STARTUP_STAGE_DEADLINE_MS") {
+        Some(value) => Some(
+            value
+                .parse::<u64>()
+                .ok()
+                .filter(|ms| *ms > 0)
+                .ok_or(ConfigError::InvalidStartupStageDeadline { value })?,
+        ),
+        None => None,
+    };
+
+    Ok(ResourceConfig {
+        nice_level,
+        max_rss_mb,
+        startup_stage_deadline_ms,
+    })
+}
+
+fn load_timestamp_format(vars: &EnvVars) -> Result<Option<String>, ConfigError> {
+    match vars.non_empty("VOICE_INPUT_TIMESTAMP_FORMAT") {
+        Some(value) if chrono::format::StrftimeItems::new(&value).parse().is_err() => {
+            Err(ConfigError::InvalidTimestampFormat { value })
+        }
+        value => Ok(value),
+    }
+}
+
+fn load_flac_compression_level(vars: &EnvVars) -> Result<u8, ConfigError> {
+    match vars.non_empty("VOICE_INPUT_FLAC_COMPRESSION_LEVEL") {
+        Some(value) => value
+            .parse::<u8>()
+            .ok()
+            .filter(|level| *level <= 8)
+            .ok_or(ConfigError::InvalidFlacCompressionLevel { value }),
+        None => Ok(AudioConfig::DEFAULT_FLAC_COMPRESSION_LEVEL),
+    }
+}
+
+fn load_audio_cache_entries(vars: &EnvVars) -> Result<usize, ConfigError> {
+    match vars.non_empty("VOICE_INPUT_AUDIO_CACHE_ENTRIES") {
+        Some(value) => value
+            .parse()
+            .map_err(|_| ConfigError::InvalidAudioCacheEntries { value }),
+        None => Ok(AudioConfig::DEFAULT_CACHE_ENTRIES),
     }
 }
 
 impl PreferredAudioFormat {
-    fn from_env(provider: TranscriptionProvider) -> Result<Self, ConfigError> {
-        match non_empty_env("VOICE_INPUT_AUDIO_FORMAT") {
+    fn from_vars(vars: &EnvVars, provider: TranscriptionProvider) -> Result<Self, ConfigError> {
+        match vars.non_empty("VOICE_INPUT_AUDIO_FORMAT") {
             Some(value) => Self::parse_for_provider(provider, &value),
             None => Ok(match provider {
                 TranscriptionProvider::OpenAi => Self::Flac,
@@ -427,14 +990,28 @@ impl PreferredAudioFormat {
 
         Ok(format)
     }
+
+    /// 競争モードの 2 つ目のバックエンドでも送れる形式か検証する
+    fn validate_for_race_provider(
+        self,
+        provider: TranscriptionProvider,
+    ) -> Result<(), ConfigError> {
+        match self {
+            Self::Flac => Self::parse_for_provider(provider, "flac").map(|_| ()),
+            Self::Wav => Ok(()),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::{
-        AudioConfig, ConfigError, EnvConfig, PathConfig, PreferredAudioFormat, ProfilingConfig,
-        ProxyConfig, RecordingConfig, TranscriptionConfig, TranscriptionProvider, lock_test_env,
+        AudioConfig, ConfigError, EnvConfig, EnvVars, OpenAiRequestConfig, PathConfig,
+        PreferredAudioFormat, ProfilingConfig, ProxyConfig, RecordingConfig, ResourceConfig,
+        ScreenLockAction, TextInputConfig, TranscriptionConfig, TranscriptionProvider,
+        lock_test_env,
     };
+    use std::collections::HashMap;
     use std::path::PathBuf;
 
     fn sample_env_config(transcription: TranscriptionConfig) -> EnvConfig {
@@ -453,11 +1030,18 @@ mod tests {
             audio: AudioConfig {
                 input_device_priorities: Vec::new(),
                 preferred_format: PreferredAudioFormat::Flac,
+                flac_compression_level: AudioConfig::DEFAULT_FLAC_COMPRESSION_LEVEL,
+                cache_entries: AudioConfig::DEFAULT_CACHE_ENTRIES,
             },
             recording: RecordingConfig {
                 max_duration_secs: 30,
+                min_duration_ms: 500,
+                max_extension_secs: 10,
+                screen_lock_action: ScreenLockAction::Discard,
             },
             profiling: ProfilingConfig { enabled: false },
+            resources: ResourceConfig::default(),
+            text_input: TextInputConfig::default(),
         }
     }
 
@@ -469,7 +1053,12 @@ mod tests {
             streaming_enabled: false,
             log_path: None,
             low_confidence_selection_enabled: false,
+            caret_context_enabled: false,
+            timestamp_format: None,
             mlx_qwen3_asr_command: "mlx-qwen3-asr".to_string(),
+            openai_base_url: None,
+            openai_request: OpenAiRequestConfig::default(),
+            race_provider: None,
         }
     }
 
@@ -602,6 +1191,94 @@ mod tests {
         }
     }
 
+    /// 競争モードの相手は既定モデルを使い、送る音声形式に対応している必要がある
+    #[test]
+    fn race_provider_uses_default_model_and_checks_audio_format() {
+        let _lock = lock_test_env();
+        unsafe {
+            std::env::set_var("VOICE_INPUT_RACE_PROVIDER", "mlx-qwen3-asr");
+        }
+        let flac_error = EnvConfig::from_env().unwrap_err();
+        unsafe {
+            std::env::set_var("VOICE_INPUT_AUDIO_FORMAT", "wav");
+        }
+        let config = EnvConfig::from_env().unwrap();
+        unsafe {
+            std::env::remove_var("VOICE_INPUT_RACE_PROVIDER");
+            std::env::remove_var("VOICE_INPUT_AUDIO_FORMAT");
+        }
+
+        assert_eq!(
+            flac_error,
+            ConfigError::UnsupportedAudioFormatForProvider {
+                provider: "mlx-qwen3-asr".to_string(),
+                value: "flac".to_string(),
+                supported: "wav",
+            }
+        );
+        let race = config.transcription.race_config().unwrap();
+        assert_eq!(race.provider, TranscriptionProvider::MlxQwen3Asr);
+        assert_eq!(race.model, "Qwen/Qwen3-ASR-1.7B");
+        assert_eq!(race.race_provider, None);
+        assert!(
+            EnvConfig::from_env()
+                .unwrap()
+                .transcription
+                .race_config()
+                .is_none()
+        );
+    }
+
+    /// キャレット前の文脈取得は明示的に有効化した場合だけ行う
+    #[test]
+    fn caret_context_is_opt_in() {
+        let _lock = lock_test_env();
+        assert!(
+            !EnvConfig::from_env()
+                .unwrap()
+                .transcription
+                .caret_context_enabled
+        );
+
+        unsafe {
+            std::env::set_var("VOICE_INPUT_CARET_CONTEXT", "true");
+        }
+        let config = EnvConfig::from_env().unwrap();
+        unsafe {
+            std::env::remove_var("VOICE_INPUT_CARET_CONTEXT");
+        }
+
+        assert!(config.transcription.caret_context_enabled);
+    }
+
+    /// 日時マクロの書式は strftime として解釈できるものだけ受け付ける
+    #[test]
+    fn timestamp_format_is_validated() {
+        let _lock = lock_test_env();
+        unsafe {
+            std::env::set_var("VOICE_INPUT_TIMESTAMP_FORMAT", "%Y/%m/%d %H:%M");
+        }
+        let format = EnvConfig::from_env()
+            .unwrap()
+            .transcription
+            .timestamp_format;
+        unsafe {
+            std::env::set_var("VOICE_INPUT_TIMESTAMP_FORMAT", "%Q");
+        }
+        let error = EnvConfig::from_env().unwrap_err();
+        unsafe {
+            std::env::remove_var("VOICE_INPUT_TIMESTAMP_FORMAT");
+        }
+
+        assert_eq!(format.as_deref(), Some("%Y/%m/%d %H:%M"));
+        assert_eq!(
+            error,
+            ConfigError::InvalidTimestampFormat {
+                value: "%Q".to_string()
+            }
+        );
+    }
+
     /// 録音最大秒数は環境変数から読み込める
     #[test]
     fn max_duration_secs_is_loaded_from_environment() {
@@ -619,6 +1296,160 @@ mod tests {
         }
     }
 
+    /// 再読み込みで読んだファイルの値はプロセス環境より優先し、プロセス環境は書き換えない
+    #[test]
+    fn env_file_values_override_process_env_without_writing_it() {
+        let _lock = lock_test_env();
+        unsafe {
+            std::env::set_var("VOICE_INPUT_MAX_SECS", "45");
+            std::env::set_var("VOICE_INPUT_MAX_EXTENSION_SECS", "5");
+        }
+        let file_vars = HashMap::from([("VOICE_INPUT_MAX_SECS".to_string(), "60".to_string())]);
+
+        let config = EnvConfig::from_vars(&EnvVars::with_overrides(file_vars)).unwrap();
+
+        assert_eq!(config.recording.max_duration_secs, 60);
+        assert_eq!(config.recording.max_extension_secs, 5);
+        assert_eq!(std::env::var("VOICE_INPUT_MAX_SECS").as_deref(), Ok("45"));
+
+        unsafe {
+            std::env::remove_var("VOICE_INPUT_MAX_SECS");
+            std::env::remove_var("VOICE_INPUT_MAX_EXTENSION_SECS");
+        }
+    }
+
+    /// 最小録音ミリ秒は未設定なら500msになり、環境変数で上書きできる
+    #[test]
+    fn min_recording_ms_defaults_and_is_loaded_from_environment() {
+        let _lock = lock_test_env();
+        unsafe {
+            std::env::remove_var("VOICE_INPUT_MIN_RECORDING_MS");
+        }
+        assert_eq!(
+            EnvConfig::from_env().unwrap().recording.min_duration_ms,
+            500
+        );
+
+        unsafe {
+            std::env::set_var("VOICE_INPUT_MIN_RECORDING_MS", "0");
+        }
+        assert_eq!(EnvConfig::from_env().unwrap().recording.min_duration_ms, 0);
+
+        unsafe {
+            std::env::remove_var("VOICE_INPUT_MIN_RECORDING_MS");
+        }
+    }
+
+    /// 最小録音ミリ秒が整数でない場合は設定エラーになる
+    #[test]
+    fn try_from_env_rejects_invalid_min_recording_ms() {
+        let _lock = lock_test_env();
+        unsafe {
+            std::env::set_var("VOICE_INPUT_MIN_RECORDING_MS", "half");
+        }
+
+        let result = EnvConfig::try_from_env();
+
+        assert_eq!(
+            result,
+            Err(ConfigError::InvalidMinRecordingMs {
+                value: "half".to_string(),
+            })
+        );
+
+        unsafe {
+            std::env::remove_var("VOICE_INPUT_MIN_RECORDING_MS");
+        }
+    }
+
+    /// 自動停止の延長上限は未設定なら10秒になり、整数でない場合は設定エラーになる
+    #[test]
+    fn max_extension_secs_defaults_and_rejects_invalid_value() {
+        let _lock = lock_test_env();
+        unsafe {
+            std::env::remove_var("VOICE_INPUT_MAX_EXTENSION_SECS");
+        }
+        assert_eq!(
+            EnvConfig::from_env().unwrap().recording.max_extension_secs,
+            10
+        );
+
+        unsafe {
+            std::env::set_var("VOICE_INPUT_MAX_EXTENSION_SECS", "long");
+        }
+        assert_eq!(
+            EnvConfig::try_from_env(),
+            Err(ConfigError::InvalidMaxExtensionSecs {
+                value: "long".to_string(),
+            })
+        );
+
+        unsafe {
+            std::env::remove_var("VOICE_INPUT_MAX_EXTENSION_SECS");
+        }
+    }
+
+    /// 画面ロック時の扱いは未設定なら破棄になり、不正な値は設定エラーになる
+    #[test]
+    fn screen_lock_action_defaults_to_discard_and_rejects_unknown_value() {
+        let _lock = lock_test_env();
+        unsafe {
+            std::env::remove_var("VOICE_INPUT_SCREEN_LOCK_ACTION");
+        }
+        assert_eq!(
+            EnvConfig::from_env().unwrap().recording.screen_lock_action,
+            ScreenLockAction::Discard
+        );
+
+        unsafe {
+            std::env::set_var("VOICE_INPUT_SCREEN_LOCK_ACTION", "Transcribe");
+        }
+        assert_eq!(
+            EnvConfig::from_env().unwrap().recording.screen_lock_action,
+            ScreenLockAction::TranscribeOnUnlock
+        );
+
+        unsafe {
+            std::env::set_var("VOICE_INPUT_SCREEN_LOCK_ACTION", "pause");
+        }
+        assert_eq!(
+            EnvConfig::try_from_env(),
+            Err(ConfigError::InvalidScreenLockAction {
+                value: "pause".to_string(),
+            })
+        );
+
+        unsafe {
+            std::env::remove_var("VOICE_INPUT_SCREEN_LOCK_ACTION");
+        }
+    }
+
+    /// アプリ別の入力前待機時間を読み込み、形式が不正な項目は設定エラーになる
+    #[test]
+    fn paste_delays_are_parsed_per_app() {
+        let _lock = lock_test_env();
+        unsafe {
+            std::env::set_var("VOICE_INPUT_PASTE_DELAYS", "Slack=150, Microsoft Word = 80");
+        }
+        let delays = EnvConfig::from_env().unwrap().text_input.paste_delays_ms;
+        assert_eq!(delays.get("Slack"), Some(&150));
+        assert_eq!(delays.get("Microsoft Word"), Some(&80));
+
+        unsafe {
+            std::env::set_var("VOICE_INPUT_PASTE_DELAYS", "Slack");
+        }
+        assert_eq!(
+            EnvConfig::try_from_env(),
+            Err(ConfigError::InvalidPasteDelay {
+                value: "Slack".to_string(),
+            })
+        );
+
+        unsafe {
+            std::env::remove_var("VOICE_INPUT_PASTE_DELAYS");
+        }
+    }
+
     /// OpenAI の未対応モデルが環境変数に指定されている場合は設定構築に失敗する
     #[test]
     fn unsupported_openai_model_in_env_fails_config_loading() {
@@ -1095,4 +1926,253 @@ mod tests {
             std::env::remove_var("VOICE_INPUT_AUDIO_FORMAT");
         }
     }
+
+    /// 組織・プロジェクト・タイムアウト・利用者タグは環境変数から読み込む
+    #[test]
+    fn openai_request_options_are_loaded_from_environment() {
+        let _lock = lock_test_env();
+        unsafe {
+            std::env::set_var("OPENAI_ORG_ID", "org-123");
+            std::env::set_var("OPENAI_PROJECT_ID", "proj_456");
+            std::env::set_var("OPENAI_TIMEOUT_SECS", "45");
+            std::env::set_var("OPENAI_USER", "voice-input-alice");
+        }
+
+        let config = EnvConfig::from_env().unwrap();
+
+        assert_eq!(
+            config.transcription.openai_request,
+            OpenAiRequestConfig {
+                organization: Some("org-123".to_string()),
+                project: Some("proj_456".to_string()),
+                timeout_secs: Some(45),
+                user: Some("voice-input-alice".to_string()),
+            }
+        );
+
+        unsafe {
+            std::env::remove_var("OPENAI_ORG_ID");
+            std::env::remove_var("OPENAI_PROJECT_ID");
+            std::env::remove_var("OPENAI_TIMEOUT_SECS");
+            std::env::remove_var("OPENAI_USER");
+        }
+    }
+
+    /// 0 や数値以外のタイムアウトは設定エラーになる
+    #[test]
+    fn invalid_openai_timeout_fails_config_loading() {
+        let _lock = lock_test_env();
+        unsafe {
+            std::env::set_var("OPENAI_TIMEOUT_SECS", "0");
+        }
+
+        let error = EnvConfig::from_env().unwrap_err();
+
+        assert_eq!(
+            error,
+            ConfigError::InvalidOpenAiTimeout {
+                value: "0".to_string()
+            }
+        );
+
+        unsafe {
+            std::env::remove_var("OPENAI_TIMEOUT_SECS");
+        }
+    }
+
+    /// FLAC 圧縮レベルは既定 5 で、0〜8 の範囲外は設定エラーになる
+    #[test]
+    fn flac_compression_level_is_loaded_and_validated() {
+        let _lock = lock_test_env();
+        assert_eq!(
+            EnvConfig::from_env().unwrap().audio.flac_compression_level,
+            AudioConfig::DEFAULT_FLAC_COMPRESSION_LEVEL
+        );
+
+        unsafe {
+            std::env::set_var("VOICE_INPUT_FLAC_COMPRESSION_LEVEL", "1");
+        }
+        let level = EnvConfig::from_env().unwrap().audio.flac_compression_level;
+        unsafe {
+            std::env::set_var("VOICE_INPUT_FLAC_COMPRESSION_LEVEL", "9");
+        }
+        let error = EnvConfig::from_env().unwrap_err();
+        unsafe {
+            std::env::remove_var("VOICE_INPUT_FLAC_COMPRESSION_LEVEL");
+        }
+
+        assert_eq!(level, 1);
+        assert_eq!(
+            error,
+            ConfigError::InvalidFlacCompressionLevel {
+                value: "9".to_string()
+            }
+        );
+    }
+
+    /// 再読み込みでは実行中に反映できる設定だけを差し替え、再起動が必要な設定は現在の値に戻す
+    #[test]
+    fn reload_applies_hot_settings_and_keeps_restart_required_ones() {
+        let running = sample_env_config(openai_transcription_config());
+        let mut reloaded = running.clone();
+        reloaded.recording.max_duration_secs = 60;
+        reloaded.audio.input_device_priorities = vec!["USB Mic".to_string()];
+        reloaded.transcription.provider = TranscriptionProvider::MlxQwen3Asr;
+        reloaded.resources.nice_level = Some(5);
+
+        let (merged, changes) = running.merge_reloaded(reloaded);
+
+        assert_eq!(
+            changes.applied,
+            vec!["INPUT_DEVICE_PRIORITY", "VOICE_INPUT_MAX_SECS"]
+        );
+        assert_eq!(
+            changes.restart_required,
+            vec![
+                "TRANSCRIPTION_PROVIDER",
+                "VOICE_INPUT_NICE / VOICE_INPUT_MAX_RSS_MB / VOICE_INPUT_STARTUP_STAGE_DEADLINE_MS",
+            ]
+        );
+        assert_eq!(merged.recording.max_duration_secs, 60);
+        assert_eq!(merged.transcription.provider, TranscriptionProvider::OpenAi);
+        assert_eq!(merged.resources.nice_level, None);
+
+        let (_, unchanged) = running.merge_reloaded(running.clone());
+        assert_eq!(
+            unchanged.format_lines(),
+            vec!["config reloaded: no changes"]
+        );
+    }
+
+    /// 録音キャッシュの件数は既定 5 で、0 を指定すると無効になる
+    #[test]
+    fn audio_cache_entries_are_loaded_and_validated() {
+        let _lock = lock_test_env();
+        assert_eq!(
+            EnvConfig::from_env().unwrap().audio.cache_entries,
+            AudioConfig::DEFAULT_CACHE_ENTRIES
+        );
+
+        unsafe {
+            std::env::set_var("VOICE_INPUT_AUDIO_CACHE_ENTRIES", "0");
+        }
+        let entries = EnvConfig::from_env().unwrap().audio.cache_entries;
+        unsafe {
+            std::env::set_var("VOICE_INPUT_AUDIO_CACHE_ENTRIES", "-1");
+        }
+        let error = EnvConfig::from_env().unwrap_err();
+        unsafe {
+            std::env::remove_var("VOICE_INPUT_AUDIO_CACHE_ENTRIES");
+        }
+
+        assert_eq!(entries, 0);
+        assert_eq!(
+            error,
+            ConfigError::InvalidAudioCacheEntries {
+                value: "-1".to_string()
+            }
+        );
+    }
+
+    /// OpenAI のベース URL は環境変数から上書きできる
+    #[test]
+    fn openai_base_url_is_loaded_from_environment() {
+        let _lock = lock_test_env();
+        unsafe {
+            std::env::set_var("OPENAI_BASE_URL", "http://127.0.0.1:8080/v1");
+        }
+
+        let config = EnvConfig::from_env().unwrap();
+
+        assert_eq!(
+            config.transcription.openai_base_url.as_deref(),
+            Some("http://127.0.0.1:8080/v1")
+        );
+
+        unsafe {
+            std::env::remove_var("OPENAI_BASE_URL");
+        }
+    }
+
+    /// リソース制限は未設定なら無効になる
+    #[test]
+    fn resource_limits_are_disabled_by_default() {
+        let _lock = lock_test_env();
+        unsafe {
+            std::env::remove_var("VOICE_INPUT_NICE");
+            std::env::remove_var("VOICE_INPUT_MAX_RSS_MB");
+            std::env::remove_var("VOICE_INPUT_STARTUP_STAGE_DEADLINE_MS");
+        }
+
+        let config = EnvConfig::from_env().unwrap();
+
+        assert_eq!(config.resources, ResourceConfig::default());
+    }
+
+    /// nice 値・RSS 上限・起動段階の期限は環境変数から読み込める
+    #[test]
+    fn resource_limits_are_loaded_from_environment() {
+        let _lock = lock_test_env();
+        unsafe {
+            std::env::set_var("VOICE_INPUT_NICE", "10");
+            std::env::set_var("VOICE_INPUT_MAX_RSS_MB", "512");
+            std::env::set_var("VOICE_INPUT_STARTUP_STAGE_DEADLINE_MS", "5000");
+        }
+
+        let config = EnvConfig::from_env().unwrap();
+
+        assert_eq!(config.resources.nice_level, Some(10));
+        assert_eq!(config.resources.max_rss_mb, Some(512));
+        assert_eq!(config.resources.startup_stage_deadline_ms, Some(5000));
+
+        unsafe {
+            std::env::remove_var("VOICE_INPUT_NICE");
+            std::env::remove_var("VOICE_INPUT_MAX_RSS_MB");
+            std::env::remove_var("VOICE_INPUT_STARTUP_STAGE_DEADLINE_MS");
+        }
+    }
+
+    /// nice 値は -20..=19 の範囲外を拒否する
+    #[test]
+    fn try_from_env_rejects_out_of_range_nice_level() {
+        let _lock = lock_test_env();
+        unsafe {
+            std::env::set_var("VOICE_INPUT_NICE", "25");
+        }
+
+        let result = EnvConfig::try_from_env();
+
+        assert_eq!(
+            result,
+            Err(ConfigError::InvalidNiceLevel {
+                value: "25".to_string(),
+            })
+        );
+
+        unsafe {
+            std::env::remove_var("VOICE_INPUT_NICE");
+        }
+    }
+
+    /// RSS 上限は 0 を拒否する
+    #[test]
+    fn try_from_env_rejects_zero_max_rss() {
+        let _lock = lock_test_env();
+        unsafe {
+            std::env::set_var("VOICE_INPUT_MAX_RSS_MB", "0");
+        }
+
+        let result = EnvConfig::try_from_env();
+
+        assert_eq!(
+            result,
+            Err(ConfigError::InvalidMaxRssMb {
+                value: "0".to_string(),
+            })
+        );
+
+        unsafe {
+            std::env::remove_var("VOICE_INPUT_MAX_RSS_MB");
+        }
+    }
 }
@@ -1,5 +1,7 @@
 #![allow(clippy::disallowed_methods)]
 
+use std::collections::HashMap;
+
 /// Environment loading helpers.
 ///
 /// Loads environment variables from `.env` if present, or from the file
@@ -13,3 +15,19 @@ pub fn load_env() {
         dotenvy::dotenv().ok();
     }
 }
+
+/// Reads the environment file for a configuration reload.
+///
+/// Unlike [`load_env`], this never writes to the process environment: changing
+/// it while other threads read it is undefined behavior. The returned values
+/// take precedence over the process environment when the configuration is
+/// rebuilt, so variables removed from the file keep their startup values until
+/// the daemon restarts. A missing or unreadable file yields an empty map.
+pub fn read_env_file() -> HashMap<String, String> {
+    let iter = match std::env::var("VOICE_INPUT_ENV_PATH") {
+        Ok(path) => dotenvy::from_path_iter(path),
+        Err(_) => dotenvy::dotenv_iter(),
+    };
+    iter.map(|entries| entries.filter_map(Result::ok).collect())
+        .unwrap_or_default()
+}
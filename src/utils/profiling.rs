@@ -3,12 +3,43 @@
 //! 環境設定で有効化された場合のみ、処理時間や任意タイミングのログを標準エラーへ出力する。
 
 use crate::utils::config::EnvConfig;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
 use std::sync::OnceLock;
 use std::time::{Duration, Instant};
 
 #[cfg(test)]
 use std::sync::atomic::{AtomicI8, AtomicUsize, Ordering};
 
+/// リクエスト単位の出力の詳しさ
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Verbosity {
+    /// 成功時は何も出力しない。デーモン側の計測ログも抑える
+    Quiet,
+    /// 通常の出力
+    #[default]
+    Normal,
+    /// デーモン側で処理段階ごとの計測ログを出力する
+    Verbose,
+}
+
+impl Verbosity {
+    /// CLI の `--quiet` / `--verbose` から決める
+    pub fn from_flags(quiet: bool, verbose: bool) -> Self {
+        match (quiet, verbose) {
+            (true, _) => Verbosity::Quiet,
+            (false, true) => Verbosity::Verbose,
+            (false, false) => Verbosity::Normal,
+        }
+    }
+}
+
+tokio::task_local! {
+    /// 処理中のリクエストが指定した出力の詳しさ
+    static REQUEST_VERBOSITY: Verbosity;
+}
+
 #[cfg(test)]
 static ENABLED_OVERRIDE: AtomicI8 = AtomicI8::new(-1);
 #[cfg(test)]
@@ -24,7 +55,26 @@ pub fn enabled() -> bool {
             return override_value == 1;
         }
     }
-    *ENABLED.get_or_init(|| EnvConfig::get().profiling.enabled)
+    match request_verbosity() {
+        Verbosity::Quiet => false,
+        Verbosity::Normal => *ENABLED.get_or_init(|| EnvConfig::get().profiling.enabled),
+        Verbosity::Verbose => true,
+    }
+}
+
+/// `future` の実行中だけ、計測ログの出力をリクエストの詳しさに合わせる
+///
+/// タスク単位で切り替えるため、同時に処理している他のリクエストには及ばない。
+/// 内部で起動した別タスクには引き継がれない。
+pub async fn scope_request<F: Future>(verbosity: Verbosity, future: F) -> F::Output {
+    REQUEST_VERBOSITY.scope(verbosity, future).await
+}
+
+/// 処理中のリクエストが指定した出力の詳しさ（リクエスト外では `Normal`）
+pub fn request_verbosity() -> Verbosity {
+    REQUEST_VERBOSITY
+        .try_with(|verbosity| *verbosity)
+        .unwrap_or_default()
 }
 
 /// 計測開始用タイマー。
@@ -117,4 +167,25 @@ mod tests {
         set_enabled_override(false);
         assert!(!enabled());
     }
+
+    /// リクエストの詳しさはそのタスクの中だけで有効で、並行する他のリクエストに漏れない
+    #[tokio::test(flavor = "current_thread")]
+    async fn request_verbosity_is_scoped_to_its_task() {
+        let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+        let quiet = scope_request(Verbosity::Quiet, async move {
+            rx.await.unwrap();
+            request_verbosity()
+        });
+        let verbose = scope_request(Verbosity::Verbose, async move {
+            let observed = request_verbosity();
+            tx.send(()).unwrap();
+            observed
+        });
+
+        assert_eq!(
+            tokio::join!(quiet, verbose),
+            (Verbosity::Quiet, Verbosity::Verbose)
+        );
+        assert_eq!(request_verbosity(), Verbosity::Normal);
+    }
 }
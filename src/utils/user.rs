@@ -0,0 +1,7 @@
+//! 実行ユーザーの情報
+
+/// 実行中のプロセスの実ユーザー ID を返す
+pub(crate) fn current_uid() -> u32 {
+    // SAFETY: getuid は常に成功し、副作用もない
+    unsafe { libc::getuid() }
+}
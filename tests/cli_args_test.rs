@@ -1,4 +1,8 @@
+use clap::Parser;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::UnixListener;
 use std::process::Command;
+use voice_input::cli::Cli;
 
 fn run_cmd(args: &[&str]) -> std::process::Output {
     Command::new("cargo")
@@ -41,3 +45,70 @@ fn start_command_accepts_default_args() {
     assert!(!stderr.contains("error: unexpected argument"));
     assert!(!stderr.contains("error: invalid value"));
 }
+
+/// 未知の入力後動作は拒否される
+#[test]
+fn unknown_then_value_is_rejected() {
+    let output = run_cmd(&["start", "--then", "tab"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("invalid value"));
+}
+
+/// 詳しさの指定はサブコマンドの前後どちらにも置け、解析結果に反映される
+#[test]
+fn verbosity_flags_are_parsed_before_or_after_subcommand() {
+    for (args, quiet, verbose) in [
+        (&["voice_input", "--quiet", "status"][..], true, false),
+        (&["voice_input", "status", "--verbose"][..], false, true),
+        (&["voice_input", "dict", "list", "-q"][..], true, false),
+        (&["voice_input", "status"][..], false, false),
+    ] {
+        let cli = Cli::try_parse_from(args).expect("verbosity flags should parse");
+        assert_eq!((cli.quiet, cli.verbose), (quiet, verbose), "{:?}", args);
+    }
+}
+
+/// 詳しさに応じて表示が変わり、通常以外の詳しさだけがデーモンへ伝わる
+#[test]
+fn verbosity_controls_output_and_is_sent_to_daemon() {
+    let dir = tempfile::tempdir().unwrap();
+    let socket = dir.path().join("voice_input.sock");
+    let listener = UnixListener::bind(&socket).unwrap();
+    let daemon = std::thread::spawn(move || {
+        let mut requests = Vec::new();
+        for _ in 0..3 {
+            let (stream, _) = listener.accept().unwrap();
+            let mut line = String::new();
+            BufReader::new(&stream).read_line(&mut line).unwrap();
+            requests.push(line.trim().to_string());
+            writeln!(&stream, r#"{{"ok":true,"msg":"pong"}}"#).unwrap();
+        }
+        requests
+    });
+
+    let run = |args: &[&str]| {
+        Command::new("cargo")
+            .args(["run", "--quiet", "--bin", "voice_input", "--"])
+            .args(args)
+            .env("VOICE_INPUT_SOCKET_PATH", &socket)
+            .output()
+            .expect("Failed to run command")
+    };
+    let quiet = run(&["--quiet", "status"]);
+    let normal = run(&["status"]);
+    let verbose = run(&["status", "--verbose"]);
+
+    assert_eq!(String::from_utf8_lossy(&quiet.stdout), "");
+    assert_eq!(String::from_utf8_lossy(&normal.stdout), "pong\n");
+    assert!(!String::from_utf8_lossy(&normal.stderr).contains("ipc round-trip"));
+    assert_eq!(String::from_utf8_lossy(&verbose.stdout), "pong\n");
+    assert!(String::from_utf8_lossy(&verbose.stderr).contains("ipc round-trip"));
+    assert_eq!(
+        daemon.join().unwrap(),
+        vec![
+            r#"{"WithVerbosity":{"verbosity":"quiet","cmd":"Status"}}"#,
+            r#""Status""#,
+            r#"{"WithVerbosity":{"verbosity":"verbose","cmd":"StatusVerbose"}}"#,
+        ]
+    );
+}
@@ -1,4 +1,5 @@
-use voice_input::ipc::IpcCmd;
+use voice_input::domain::input::TrailingAction;
+use voice_input::ipc::{IpcCmd, Verbosity};
 
 /// プロンプトが省略された旧形式でもデシリアライズできる
 #[test]
@@ -18,7 +19,7 @@ fn backward_compatibility_with_extra_fields() {
     let cmd: IpcCmd = serde_json::from_str(json_with_extra).unwrap();
 
     match cmd {
-        IpcCmd::Start { prompt } => {
+        IpcCmd::Start { prompt, .. } => {
             assert_eq!(prompt, Some("test".to_string()));
         }
         _ => panic!("Expected Start command"),
@@ -72,3 +73,111 @@ fn forward_compatibility_ignores_unknown_fields() {
         "Should ignore unknown fields for forward compatibility"
     );
 }
+
+/// 詳細ステータスコマンドは既存のStatusと別コマンドとして往復できる
+#[test]
+fn status_verbose_roundtrips_separately_from_status() {
+    let json = serde_json::to_string(&IpcCmd::StatusVerbose).unwrap();
+    let cmd: IpcCmd = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(cmd, IpcCmd::StatusVerbose);
+    assert_eq!(
+        serde_json::from_str::<IpcCmd>(r#"{"Status":null}"#).unwrap(),
+        IpcCmd::Status
+    );
+}
+
+/// 監査ログ取得コマンドは引数なしで往復でき、種別名は詳しさ指定の内側から取る
+#[test]
+fn audit_roundtrips_and_names_inner_command() {
+    let json = serde_json::to_string(&IpcCmd::Audit).unwrap();
+    assert_eq!(
+        serde_json::from_str::<IpcCmd>(&json).unwrap(),
+        IpcCmd::Audit
+    );
+
+    let cmd = IpcCmd::Stop.with_verbosity(Verbosity::Verbose);
+    assert_eq!(cmd.name(), "Stop");
+}
+
+/// 標準入力転写コマンドは詳しさ指定で包んでも本文を伴うコマンドとして扱われる
+#[test]
+fn transcribe_stream_has_body_even_when_wrapped() {
+    let cmd = IpcCmd::TranscribeStream.with_verbosity(Verbosity::Quiet);
+    let json = serde_json::to_string(&cmd).unwrap();
+    let decoded: IpcCmd = serde_json::from_str(&json).unwrap();
+
+    assert!(decoded.has_body());
+    assert!(!IpcCmd::Status.has_body());
+}
+
+/// 読み上げ指定のない旧形式の開始コマンドは読み上げなしとして解釈される
+#[test]
+fn start_without_readback_defaults_to_false() {
+    let cmd: IpcCmd = serde_json::from_str(r#"{"Start":{"prompt":null}}"#).unwrap();
+
+    assert_eq!(
+        cmd,
+        IpcCmd::Start {
+            prompt: None,
+            readback: false,
+            label: None,
+            then: TrailingAction::None,
+        }
+    );
+}
+
+/// 音声ファイル転写コマンドはパス付きでJSON往復できる
+#[test]
+fn transcribe_file_roundtrips_with_path() {
+    let cmd = IpcCmd::TranscribeFile {
+        path: "/tmp/memo.wav".into(),
+    };
+    let json = serde_json::to_string(&cmd).unwrap();
+
+    assert_eq!(json, r#"{"TranscribeFile":{"path":"/tmp/memo.wav"}}"#);
+    assert_eq!(serde_json::from_str::<IpcCmd>(&json).unwrap(), cmd);
+}
+
+/// 録音中止コマンドはJSONで往復できる
+#[test]
+fn cancel_recording_roundtrips() {
+    let json = serde_json::to_string(&IpcCmd::CancelRecording).unwrap();
+
+    assert_eq!(
+        serde_json::from_str::<IpcCmd>(&json).unwrap(),
+        IpcCmd::CancelRecording
+    );
+}
+
+/// 入力後の動作は小文字の名前で指定できる
+#[test]
+fn toggle_accepts_lowercase_trailing_action() {
+    let cmd: IpcCmd = serde_json::from_str(r#"{"Toggle":{"then":"enter"}}"#).unwrap();
+
+    assert_eq!(
+        cmd,
+        IpcCmd::Toggle {
+            prompt: None,
+            readback: false,
+            then: TrailingAction::Enter,
+        }
+    );
+}
+
+/// 通常の詳しさでは旧デーモンと互換の形式のまま送り、それ以外は包んで往復できる
+#[test]
+fn verbosity_wraps_only_non_default_levels() {
+    assert_eq!(
+        serde_json::to_string(&IpcCmd::Stop.with_verbosity(Verbosity::Normal)).unwrap(),
+        r#""Stop""#
+    );
+
+    let cmd = IpcCmd::Stop.with_verbosity(Verbosity::Verbose);
+    let json = serde_json::to_string(&cmd).unwrap();
+    assert_eq!(
+        json,
+        r#"{"WithVerbosity":{"verbosity":"verbose","cmd":"Stop"}}"#
+    );
+    assert_eq!(serde_json::from_str::<IpcCmd>(&json).unwrap(), cmd);
+}
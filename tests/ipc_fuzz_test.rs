@@ -0,0 +1,74 @@
+use futures::StreamExt;
+use proptest::prelude::*;
+use tokio_util::codec::FramedRead;
+use voice_input::domain::input::TrailingAction;
+use voice_input::ipc::{IpcCmd, decode_cmd, decode_cmd_line, ipc_line_codec};
+
+/// 受信バイト列をデーモンと同じコーデック経由で最初の 1 行だけ復号する
+fn decode_first_line(input: &[u8]) -> Option<Result<IpcCmd, String>> {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(async {
+            let mut reader = FramedRead::new(input, ipc_line_codec());
+            reader
+                .next()
+                .await
+                .map(|line| decode_cmd_line(line).map_err(|e| e.to_string()))
+        })
+}
+
+fn start_cmd() -> impl Strategy<Value = IpcCmd> {
+    (
+        proptest::option::of(".*"),
+        any::<bool>(),
+        proptest::option::of(".*"),
+        prop_oneof![
+            Just(TrailingAction::None),
+            Just(TrailingAction::Space),
+            Just(TrailingAction::Newline),
+            Just(TrailingAction::Enter),
+        ],
+    )
+        .prop_map(|(prompt, readback, label, then)| IpcCmd::Start {
+            prompt,
+            readback,
+            label,
+            then,
+        })
+}
+
+proptest! {
+    /// 任意の文字列を復号してもパニックせず、成功か分類済みエラーのどちらかになる
+    #[test]
+    fn arbitrary_text_never_panics(line in ".*") {
+        let _ = decode_cmd(&line);
+    }
+
+    /// 任意のバイト列をコーデック経由で復号してもパニックせず、エラーはプロトコルエラーとして整形される
+    #[test]
+    fn arbitrary_bytes_through_codec_yield_protocol_errors(input in proptest::collection::vec(any::<u8>(), 0..512)) {
+        if let Some(Err(message)) = decode_first_line(&input) {
+            prop_assert!(message.starts_with("protocol error ("), "{}", message);
+        }
+    }
+
+    /// 正しくエンコードされた Start コマンドはコーデック経由で往復できる
+    #[test]
+    fn encoded_start_commands_roundtrip_through_codec(cmd in start_cmd()) {
+        let mut input = serde_json::to_vec(&cmd).unwrap();
+        input.push(b'\n');
+
+        prop_assert_eq!(decode_first_line(&input), Some(Ok(cmd)));
+    }
+
+    /// 正しい JSON の後ろを切り詰めた入力は malformed_json として拒否される
+    #[test]
+    fn truncated_commands_are_rejected_as_malformed(cmd in start_cmd(), cut in 1usize..16) {
+        let json = serde_json::to_string(&cmd).unwrap();
+        let end = json.char_indices().rev().nth(cut.min(json.chars().count() - 1)).map(|(i, _)| i).unwrap();
+
+        let error = decode_cmd(&json[..end]).unwrap_err();
+        prop_assert!(error.to_string().starts_with("protocol error (malformed_json)"), "{}", error);
+    }
+}
@@ -1,3 +1,4 @@
+use voice_input::domain::input::TrailingAction;
 use voice_input::ipc::IpcCmd;
 
 /// Startコマンドがシリアライズ/デシリアライズで保持される
@@ -5,13 +6,16 @@ use voice_input::ipc::IpcCmd;
 fn start_command_serializes_roundtrip() {
     let start_cmd = IpcCmd::Start {
         prompt: Some("test prompt".to_string()),
+        readback: false,
+        label: None,
+        then: TrailingAction::None,
     };
 
     let json = serde_json::to_string(&start_cmd).unwrap();
     let deserialized: IpcCmd = serde_json::from_str(&json).unwrap();
 
     match deserialized {
-        IpcCmd::Start { prompt } => {
+        IpcCmd::Start { prompt, .. } => {
             assert_eq!(prompt, Some("test prompt".to_string()));
         }
         _ => panic!("Expected Start command"),
@@ -21,13 +25,17 @@ fn start_command_serializes_roundtrip() {
 /// Toggleコマンドがシリアライズ/デシリアライズで保持される
 #[test]
 fn toggle_command_serializes_roundtrip() {
-    let toggle_cmd = IpcCmd::Toggle { prompt: None };
+    let toggle_cmd = IpcCmd::Toggle {
+        prompt: None,
+        readback: false,
+        then: TrailingAction::None,
+    };
 
     let json = serde_json::to_string(&toggle_cmd).unwrap();
     let deserialized: IpcCmd = serde_json::from_str(&json).unwrap();
 
     match deserialized {
-        IpcCmd::Toggle { prompt } => {
+        IpcCmd::Toggle { prompt, .. } => {
             assert_eq!(prompt, None);
         }
         _ => panic!("Expected Toggle command"),
@@ -39,12 +47,22 @@ fn toggle_command_serializes_roundtrip() {
 fn ipc_cmds_roundtrip_via_json() {
     // Test various combinations
     let commands = vec![
-        IpcCmd::Start { prompt: None },
+        IpcCmd::Start {
+            prompt: None,
+            readback: false,
+            label: None,
+            then: TrailingAction::None,
+        },
         IpcCmd::Start {
             prompt: Some("hello".to_string()),
+            readback: false,
+            label: None,
+            then: TrailingAction::None,
         },
         IpcCmd::Toggle {
             prompt: Some("world".to_string()),
+            readback: false,
+            then: TrailingAction::None,
         },
         IpcCmd::Stop,
         IpcCmd::Status,
@@ -68,6 +86,9 @@ fn start_command_json_format_contains_prompt() {
     // Verify the actual JSON format
     let cmd = IpcCmd::Start {
         prompt: Some("test".to_string()),
+        readback: false,
+        label: None,
+        then: TrailingAction::None,
     };
 
     let json = serde_json::to_string(&cmd).unwrap();
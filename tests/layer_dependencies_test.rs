@@ -84,3 +84,9 @@ fn domain_does_not_depend_on_outer_layers() {
 fn application_does_not_depend_on_infrastructure() {
     assert_forbidden_dependency_absent("src/application", "crate::infrastructure");
 }
+
+/// utilsはIPCの定義へ依存しない（IPC側がutilsを使う）
+#[test]
+fn utils_does_not_depend_on_ipc() {
+    assert_forbidden_dependency_absent("src/utils", "crate::ipc");
+}
@@ -0,0 +1,27 @@
+//! 反復実行によるリソースリークの検査
+//!
+//! プロセス全体のファイルディスクリプタ数を見るため、並行して動く他のテストと
+//! 同じプロセスにならないよう単独のテストバイナリにしている。
+
+use voice_input::infrastructure::soak::{SoakLimits, run_soak};
+use voice_input::utils::config::EnvConfig;
+
+/// 反復実行してもファイルディスクリプタと生存タスクが増えない
+#[tokio::test(flavor = "current_thread")]
+async fn soak_cycles_do_not_leak_fds_or_tasks() {
+    let _ = EnvConfig::init();
+    let local = tokio::task::LocalSet::new();
+    let report = local.run_until(run_soak(500)).await.unwrap();
+
+    let limits = SoakLimits {
+        // 短いテストでは RSS の揺らぎが大きいため、fd とタスクのみ厳密に見る
+        max_rss_growth_bytes: u64::MAX,
+        ..SoakLimits::default()
+    };
+    assert_eq!(report.cycles, 500);
+    assert!(
+        report.violations(&limits).is_empty(),
+        "{:?}",
+        report.format_lines()
+    );
+}